@@ -1,14 +1,19 @@
 use crate::{
   TakeoffError,
-  coords::{Point, Point3D},
+  coords::{DistanceTrait, Point, Point3D},
   error::TakeoffResult,
   scale::Scale,
   unit::Unit,
 };
 use delaunator::triangulate;
-use geo::{BoundingRect, Geometry, GeometryCollection, LineString, Point as GeoPoint};
+use geo::{
+  Area, BooleanOps, BoundingRect, Contains, Geometry, GeometryCollection, Intersects, LineString,
+  Point as GeoPoint, Polygon as GeoPolygon, Triangle, TriangulateEarcut,
+};
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
 
 #[napi(object)]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -20,6 +25,15 @@ pub struct ContourLineInput {
 }
 
 impl ContourLineInput {
+  /// Create a contour line at a real-world `elevation` in `unit`.
+  pub fn new(points: Vec<Point>, elevation: f64, unit: Unit) -> Self {
+    Self {
+      elevation,
+      points,
+      unit,
+    }
+  }
+
   pub fn to_geometry(&self) -> LineString<f64> {
     LineString::from(self.points.clone())
   }
@@ -34,6 +48,17 @@ pub struct ContourPointOfInterestInput {
   pub unit: Unit,
 }
 
+impl ContourPointOfInterestInput {
+  /// Create a spot elevation at pixel position (`x`, `y`), with `elevation` in `unit`.
+  pub fn new(x: f64, y: f64, elevation: f64, unit: Unit) -> Self {
+    Self {
+      elevation,
+      point: Point::new(x, y),
+      unit,
+    }
+  }
+}
+
 #[napi(object)]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ContourInput {
@@ -48,78 +73,527 @@ pub struct ContourInput {
 }
 
 /// A triangulated 3D surface mesh suitable for volumetric calculations.
+#[napi(object, object_from_js = false)]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SurfaceMesh {
   pub vertices: Vec<Point3D>,
   pub triangles: Vec<[u32; 3]>,
+  /// Missing in meshes serialized before tolerances were configurable.
+  #[serde(default)]
+  pub tolerances: MeshTolerances,
 }
 
-impl SurfaceMesh {
-  const DEDUP_EPSILON: f64 = 1e-9;
+/// Tolerances used when building and sampling a [`SurfaceMesh`].
+///
+/// The defaults suit page coordinates in pixels. Surveys with large coordinate magnitudes
+/// (e.g. state-plane meters) lose precision in the barycentric weights and may need looser
+/// values so queries on triangle edges are not reported as outside the mesh.
+#[napi(object)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MeshTolerances {
+  /// Points closer than this in x and y are the same vertex, both when deduplicating input
+  /// points and when `z_at` snaps a query to a vertex, so a merged point always snaps to the
+  /// kept vertex.
+  pub vertex_epsilon: f64,
+  /// How far a barycentric weight may fall below 0 (or their sum above 1) for a point to
+  /// still count as inside a triangle.
+  pub barycentric_epsilon: f64,
+  /// Triangles whose barycentric denominator is below this are skipped as degenerate.
+  pub degenerate_epsilon: f64,
+}
 
-  const Z_AT_VERTEX_EPSILON: f64 = 1e-12;
+impl Default for MeshTolerances {
+  fn default() -> Self {
+    Self {
+      vertex_epsilon: SurfaceMesh::VERTEX_EPSILON,
+      barycentric_epsilon: 1e-12,
+      degenerate_epsilon: 1e-12,
+    }
+  }
+}
+
+impl MeshTolerances {
+  /// Check that every tolerance is a positive finite number.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::InvalidInput`] naming the first tolerance that is zero, negative
+  /// or not finite; a zero `vertex_epsilon` would overflow the deduplication grid.
+  pub fn validate(&self) -> TakeoffResult<()> {
+    for (name, value) in [
+      ("vertex_epsilon", self.vertex_epsilon),
+      ("barycentric_epsilon", self.barycentric_epsilon),
+      ("degenerate_epsilon", self.degenerate_epsilon),
+    ] {
+      if !(value.is_finite() && value > 0.0) {
+        return Err(TakeoffError::invalid_input(format!(
+          "{name} must be a positive number, got {value}"
+        )));
+      }
+    }
+    Ok(())
+  }
+}
+
+impl SurfaceMesh {
+  /// Default [`MeshTolerances::vertex_epsilon`].
+  pub const VERTEX_EPSILON: f64 = 1e-9;
 
   /// Returns the interpolated z value at (x, y) if the point lies within the mesh boundary.
   /// Uses barycentric interpolation over the containing triangle.
-  /// Returns `None` if the point is outside the mesh.
+  /// Returns `None` if the point is outside the mesh (see [`SurfaceMesh::tolerances`]).
   pub fn z_at(&self, x: f64, y: f64) -> Option<f64> {
+    let vertex_epsilon = self.tolerances.vertex_epsilon;
     for v in &self.vertices {
-      if (v.x - x).abs() < Self::Z_AT_VERTEX_EPSILON && (v.y - y).abs() < Self::Z_AT_VERTEX_EPSILON
-      {
+      if (v.x - x).abs() < vertex_epsilon && (v.y - y).abs() < vertex_epsilon {
         return Some(v.z);
       }
     }
-    for tri in &self.triangles {
-      let a = &self.vertices[tri[0] as usize];
-      let b = &self.vertices[tri[1] as usize];
-      let c = &self.vertices[tri[2] as usize];
-
-      let v0x = b.x - a.x;
-      let v0y = b.y - a.y;
-      let v1x = c.x - a.x;
-      let v1y = c.y - a.y;
-      let v2x = x - a.x;
-      let v2y = y - a.y;
-
-      let dot00 = v0x * v0x + v0y * v0y;
-      let dot01 = v0x * v1x + v0y * v1y;
-      let dot02 = v0x * v2x + v0y * v2y;
-      let dot11 = v1x * v1x + v1y * v1y;
-      let dot12 = v1x * v2x + v1y * v2y;
-
-      let denom = dot00 * dot11 - dot01 * dot01;
-      if denom.abs() < 1e-12 {
-        continue;
+    self.triangles.iter().find_map(|tri| {
+      let [w, u, v] = self.barycentric(tri, x, y)?;
+      let [a, b, c] = tri.map(|i| self.vertices[i as usize].z);
+      Some(w * a + u * b + v * c)
+    })
+  }
+
+  /// Barycentric weights `[w, u, v]` of (x, y) for the triangle's vertices, or `None` if the
+  /// point is outside it or the triangle is degenerate.
+  fn barycentric(&self, tri: &[u32; 3], x: f64, y: f64) -> Option<[f64; 3]> {
+    let MeshTolerances {
+      barycentric_epsilon,
+      degenerate_epsilon,
+      ..
+    } = self.tolerances;
+    let a = &self.vertices[tri[0] as usize];
+    let b = &self.vertices[tri[1] as usize];
+    let c = &self.vertices[tri[2] as usize];
+
+    let v0x = b.x - a.x;
+    let v0y = b.y - a.y;
+    let v1x = c.x - a.x;
+    let v1y = c.y - a.y;
+    let v2x = x - a.x;
+    let v2y = y - a.y;
+
+    let dot00 = v0x * v0x + v0y * v0y;
+    let dot01 = v0x * v1x + v0y * v1y;
+    let dot02 = v0x * v2x + v0y * v2y;
+    let dot11 = v1x * v1x + v1y * v1y;
+    let dot12 = v1x * v2x + v1y * v2y;
+
+    let denom = dot00 * dot11 - dot01 * dot01;
+    if denom.abs() < degenerate_epsilon {
+      return None;
+    }
+    let inv_denom = 1.0 / denom;
+
+    let u = (dot11 * dot02 - dot01 * dot12) * inv_denom;
+    let v = (dot00 * dot12 - dot01 * dot02) * inv_denom;
+    let w = 1.0 - u - v;
+
+    let inside = u >= -barycentric_epsilon
+      && v >= -barycentric_epsilon
+      && (u + v) <= 1.0 + barycentric_epsilon;
+    inside.then_some([w, u, v])
+  }
+
+  /// Insert a survey point without re-triangulating the whole mesh.
+  ///
+  /// Triangles whose circumcircle contains the point are collected by walking out from the
+  /// triangle containing it and replaced by a fan around the new vertex (Bowyer-Watson), so
+  /// the mesh stays Delaunay and only the point's neighbourhood changes. A point off the mesh
+  /// falls back to re-triangulating every vertex, which also undoes any clipping. Like
+  /// deduplication when building the mesh, a point within `vertex_epsilon` of an existing
+  /// vertex is ignored.
+  ///
+  /// # Errors
+  ///
+  /// Returns the errors of [`ContourInput::to_surface_mesh`] for too few or collinear points
+  /// when falling back to a full re-triangulation; the mesh is left unchanged.
+  pub fn insert_point(&mut self, p: Point3D) -> TakeoffResult<()> {
+    let vertex_epsilon = self.tolerances.vertex_epsilon;
+    if self
+      .vertices
+      .iter()
+      .any(|v| (v.x - p.x).abs() < vertex_epsilon && (v.y - p.y).abs() < vertex_epsilon)
+    {
+      return Ok(());
+    }
+    let Some(start) = self
+      .triangles
+      .iter()
+      .position(|tri| self.barycentric(tri, p.x, p.y).is_some())
+    else {
+      let mut vertices = self.vertices.clone();
+      vertices.push(p);
+      *self = Self::triangulate(vertices, self.tolerances)?;
+      return Ok(());
+    };
+
+    let edge_key = |a: u32, b: u32| (a.min(b), a.max(b));
+    let mut edge_triangles: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+    for (t, tri) in self.triangles.iter().enumerate() {
+      for k in 0..3 {
+        let key = edge_key(tri[k], tri[(k + 1) % 3]);
+        edge_triangles.entry(key).or_default().push(t);
       }
-      let inv_denom = 1.0 / denom;
+    }
 
-      let u = (dot11 * dot02 - dot01 * dot12) * inv_denom;
-      let v = (dot00 * dot12 - dot01 * dot02) * inv_denom;
-      let w = 1.0 - u - v;
+    let mut in_cavity = vec![false; self.triangles.len()];
+    in_cavity[start] = true;
+    let mut stack = vec![start];
+    while let Some(t) = stack.pop() {
+      let tri = self.triangles[t];
+      for k in 0..3 {
+        for &n in &edge_triangles[&edge_key(tri[k], tri[(k + 1) % 3])] {
+          if !in_cavity[n] && self.in_circumcircle(&self.triangles[n], p) {
+            in_cavity[n] = true;
+            stack.push(n);
+          }
+        }
+      }
+    }
 
-      if u >= -1e-12 && v >= -1e-12 && (u + v) <= 1.0 + 1e-12 {
-        return Some(w * a.z + u * b.z + v * c.z);
+    // Each cavity boundary edge keeps its orientation, so the fan keeps the mesh's winding.
+    let index = self.vertices.len() as u32;
+    self.vertices.push(p);
+    let mut fan = Vec::new();
+    for (t, tri) in self.triangles.iter().enumerate() {
+      if !in_cavity[t] {
+        continue;
+      }
+      for k in 0..3 {
+        let (a, b) = (tri[k], tri[(k + 1) % 3]);
+        let interior = edge_triangles[&edge_key(a, b)]
+          .iter()
+          .any(|&n| n != t && in_cavity[n]);
+        let corners = [a, b, index].map(|i| self.vertices[i as usize]);
+        if !interior && triangle_plane(corners[0], corners[1], corners[2]).is_some() {
+          fan.push([a, b, index]);
+        }
       }
     }
-    None
+    let mut cavity = in_cavity.into_iter();
+    self.triangles.retain(|_| !cavity.next().unwrap_or(false));
+    self.triangles.extend(fan);
+    Ok(())
+  }
+
+  /// Whether `p` lies strictly inside the circumcircle of the triangle, for either winding.
+  fn in_circumcircle(&self, tri: &[u32; 3], p: Point3D) -> bool {
+    let [a, b, c] = tri.map(|i| self.vertices[i as usize]);
+    let orientation = (b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y);
+    let (adx, ady) = (a.x - p.x, a.y - p.y);
+    let (bdx, bdy) = (b.x - p.x, b.y - p.y);
+    let (cdx, cdy) = (c.x - p.x, c.y - p.y);
+    let det = (adx * adx + ady * ady) * (bdx * cdy - cdx * bdy)
+      + (bdx * bdx + bdy * bdy) * (cdx * ady - adx * cdy)
+      + (cdx * cdx + cdy * cdy) * (adx * bdy - bdx * ady);
+    det * orientation.signum() > 0.0
+  }
+
+  /// Delaunay-triangulate deduplicated vertices.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::SurfaceMeshTooFewPoints`] if there are fewer than 3 vertices.
+  /// Returns [`TakeoffError::SurfaceMeshCollinearPoints`] if all vertices are collinear.
+  fn triangulate(vertices: Vec<Point3D>, tolerances: MeshTolerances) -> TakeoffResult<SurfaceMesh> {
+    if vertices.len() < 3 {
+      return Err(TakeoffError::SurfaceMeshTooFewPoints {
+        count: vertices.len(),
+      });
+    }
+
+    let delaunator_points: Vec<delaunator::Point> = vertices
+      .iter()
+      .copied()
+      .map(delaunator::Point::from)
+      .collect();
+
+    let result = triangulate(&delaunator_points);
+
+    if result.triangles.is_empty() {
+      return Err(TakeoffError::SurfaceMeshCollinearPoints);
+    }
+
+    let triangles: Vec<[u32; 3]> = result
+      .triangles
+      .chunks_exact(3)
+      .map(|chunk| [chunk[0] as u32, chunk[1] as u32, chunk[2] as u32])
+      .collect();
+
+    Ok(SurfaceMesh {
+      vertices,
+      triangles,
+      tolerances,
+    })
   }
 
   /// Deduplicate points by (x, y) within tolerance. Keeps first z when duplicates occur.
-  fn deduplicate_points(points: &[Point3D]) -> Vec<Point3D> {
+  ///
+  /// Kept points are bucketed on a grid of `epsilon`-sized cells, so each point is only
+  /// compared against kept points in its own and the 8 neighbouring cells (near-linear instead
+  /// of a scan over every kept point). Cell indices saturate for coordinates far beyond
+  /// `epsilon`; those points share the edge cells and are still compared exactly.
+  fn deduplicate_points(points: &[Point3D], epsilon: f64) -> Vec<Point3D> {
+    let cell = |v: f64| (v / epsilon).floor() as i64;
     let mut seen: Vec<Point3D> = Vec::new();
+    let mut buckets: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
     for p in points {
-      let is_dup = seen.iter().any(|s| {
-        (s.x - p.x).abs() < Self::DEDUP_EPSILON && (s.y - p.y).abs() < Self::DEDUP_EPSILON
-      });
+      let (cx, cy) = (cell(p.x), cell(p.y));
+      let is_dup = (cx.saturating_sub(1)..=cx.saturating_add(1))
+        .flat_map(|x| (cy.saturating_sub(1)..=cy.saturating_add(1)).map(move |y| (x, y)))
+        .filter_map(|key| buckets.get(&key))
+        .flatten()
+        .any(|&i| {
+          let s = &seen[i];
+          (s.x - p.x).abs() < epsilon && (s.y - p.y).abs() < epsilon
+        });
       if !is_dup {
+        buckets.entry((cx, cy)).or_default().push(seen.len());
         seen.push(*p);
       }
     }
     seen
   }
+
+  /// Length of a path draped over the surface (e.g. a pipe run following grade).
+  ///
+  /// z is sampled with `z_at` at each vertex and segments are measured in 3D. Segments with an
+  /// endpoint off the mesh contribute their planimetric (x/y) length. Only vertices are
+  /// sampled, so densify the path first (see `densify_polyline`) to follow grade changes
+  /// between them.
+  pub fn drape_length(&self, points: &[Point]) -> f64 {
+    let elevations: Vec<Option<f64>> = points.iter().map(|p| self.z_at(p.x, p.y)).collect();
+    points
+      .windows(2)
+      .zip(elevations.windows(2))
+      .map(|(segment, z)| {
+        let dx = segment[1].x - segment[0].x;
+        let dy = segment[1].y - segment[0].y;
+        let dz = match (z[0], z[1]) {
+          (Some(z0), Some(z1)) => z1 - z0,
+          _ => 0.0,
+        };
+        (dx * dx + dy * dy + dz * dz).sqrt()
+      })
+      .sum()
+  }
+
+  /// Percent grade (`100 * rise / run`) of each segment of a path over the terrain.
+  ///
+  /// Uphill segments are positive in the direction of travel. A segment is `None` when either
+  /// endpoint is off the mesh or it has no horizontal run. x/y and z must share a unit, as
+  /// with meshes built by `to_surface_mesh`.
+  pub fn drape_slopes(&self, points: &[Point]) -> Vec<Option<f64>> {
+    let elevations: Vec<Option<f64>> = points.iter().map(|p| self.z_at(p.x, p.y)).collect();
+    points
+      .windows(2)
+      .zip(elevations.windows(2))
+      .map(|(segment, z)| {
+        let run = segment[0].distance_to(&segment[1]);
+        if run <= 0.0 {
+          return None;
+        }
+        Some(100.0 * (z[1]? - z[0]?) / run)
+      })
+      .collect()
+  }
+
+  /// Returns a copy of the mesh with vertex elevations smoothed by Laplacian relaxation.
+  ///
+  /// Each iteration moves every interior vertex's z toward the average z of its
+  /// triangle-edge neighbors: `z' = z + lambda * (avg_neighbors - z)`. Boundary vertices
+  /// (those on an edge used by a single triangle), x/y coordinates and triangles are kept
+  /// unchanged, so volumes computed against the smoothed mesh stay comparable.
+  ///
+  /// `lambda` is expected in `(0, 1]`; `0` leaves the mesh unchanged.
+  pub fn smoothed(&self, iterations: u32, lambda: f64) -> SurfaceMesh {
+    let vertex_count = self.vertices.len();
+    let mut neighbors: Vec<HashSet<usize>> = vec![HashSet::new(); vertex_count];
+    let mut edge_uses: HashMap<(usize, usize), u32> = HashMap::new();
+
+    for tri in &self.triangles {
+      for k in 0..3 {
+        let a = tri[k] as usize;
+        let b = tri[(k + 1) % 3] as usize;
+        neighbors[a].insert(b);
+        neighbors[b].insert(a);
+        *edge_uses.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+      }
+    }
+
+    let mut fixed = vec![false; vertex_count];
+    for (&(a, b), &uses) in &edge_uses {
+      if uses == 1 {
+        fixed[a] = true;
+        fixed[b] = true;
+      }
+    }
+
+    let mut z: Vec<f64> = self.vertices.iter().map(|v| v.z).collect();
+    for _ in 0..iterations {
+      let previous = z.clone();
+      for (i, adjacent) in neighbors.iter().enumerate() {
+        if fixed[i] || adjacent.is_empty() {
+          continue;
+        }
+        let average = adjacent.iter().map(|&j| previous[j]).sum::<f64>() / adjacent.len() as f64;
+        z[i] = previous[i] + lambda * (average - previous[i]);
+      }
+    }
+
+    SurfaceMesh {
+      vertices: self
+        .vertices
+        .iter()
+        .zip(z)
+        .map(|(v, z)| Point3D::new(v.x, v.y, z))
+        .collect(),
+      triangles: self.triangles.clone(),
+      tolerances: self.tolerances,
+    }
+  }
+
+  /// Restrict the mesh to a boundary polygon, e.g. a project boundary within a large imported
+  /// survey.
+  ///
+  /// Triangles fully inside the boundary are kept as they are and triangles outside it are
+  /// dropped. Triangles crossing the boundary are clipped to it and the clipped region is
+  /// re-triangulated, with z interpolated on the original triangle's plane so the surface
+  /// shape is unchanged. Vertices no longer used by any triangle are removed.
+  pub fn clip_to_polygon(&self, boundary: &[Point]) -> SurfaceMesh {
+    let boundary = GeoPolygon::new(LineString::from(boundary.to_vec()), vec![]);
+    let mut vertices: Vec<Point3D> = Vec::new();
+    let mut indices: HashMap<(u64, u64), u32> = HashMap::new();
+    let mut add_vertex = |v: Point3D| {
+      *indices
+        .entry((v.x.to_bits(), v.y.to_bits()))
+        .or_insert_with(|| {
+          vertices.push(v);
+          (vertices.len() - 1) as u32
+        })
+    };
+    let mut triangles: Vec<[u32; 3]> = Vec::new();
+
+    for tri in &self.triangles {
+      let corners = tri.map(|i| self.vertices[i as usize]);
+      let [a, b, c] = corners;
+      let Some(plane) = triangle_plane(a, b, c) else {
+        continue;
+      };
+      let triangle = Triangle::from([(a.x, a.y), (b.x, b.y), (c.x, c.y)]);
+      if boundary.contains(&triangle) {
+        triangles.push(corners.map(&mut add_vertex));
+        continue;
+      }
+      if !boundary.intersects(&triangle) {
+        continue;
+      }
+      let counter_clockwise = triangle.signed_area() > 0.0;
+      for piece in triangle.to_polygon().intersection(&boundary) {
+        for sub in piece.earcut_triangles() {
+          if sub.unsigned_area() <= f64::EPSILON {
+            continue;
+          }
+          let mut points = [sub.v1(), sub.v2(), sub.v3()];
+          if (sub.signed_area() > 0.0) != counter_clockwise {
+            points.swap(1, 2);
+          }
+          triangles.push(points.map(|p| add_vertex(Point3D::new(p.x, p.y, plane(p.x, p.y)))));
+        }
+      }
+    }
+
+    SurfaceMesh {
+      vertices,
+      triangles,
+      tolerances: self.tolerances,
+    }
+  }
+
+  /// Export the mesh as Wavefront OBJ: one `v x y z` line per vertex followed by one
+  /// `f a b c` line per triangle, with 1-based vertex indices.
+  ///
+  /// Coordinates are written as stored, i.e. in pixels for meshes built by
+  /// [`ContourInput::to_surface_mesh`].
+  pub fn to_obj(&self) -> String {
+    let mut obj = String::new();
+    for v in &self.vertices {
+      let _ = writeln!(obj, "v {} {} {}", v.x, v.y, v.z);
+    }
+    for [a, b, c] in &self.triangles {
+      let _ = writeln!(obj, "f {} {} {}", a + 1, b + 1, c + 1);
+    }
+    obj
+  }
+
+  /// Export the mesh as ASCII STL, one facet per triangle with its unit normal computed from
+  /// the vertex winding. Degenerate triangles get a zero normal.
+  ///
+  /// Coordinates are written as stored, as for [`SurfaceMesh::to_obj`].
+  pub fn to_stl_ascii(&self) -> String {
+    let mut stl = String::from("solid surface\n");
+    for tri in &self.triangles {
+      let [a, b, c] = tri.map(|i| self.vertices[i as usize]);
+      let (ux, uy, uz) = (b.x - a.x, b.y - a.y, b.z - a.z);
+      let (vx, vy, vz) = (c.x - a.x, c.y - a.y, c.z - a.z);
+      let (nx, ny, nz) = (uy * vz - uz * vy, uz * vx - ux * vz, ux * vy - uy * vx);
+      let norm = (nx * nx + ny * ny + nz * nz).sqrt();
+      let (nx, ny, nz) = if norm > 0.0 {
+        (nx / norm, ny / norm, nz / norm)
+      } else {
+        (0.0, 0.0, 0.0)
+      };
+      let _ = writeln!(stl, "  facet normal {} {} {}", nx, ny, nz);
+      stl.push_str("    outer loop\n");
+      for v in [a, b, c] {
+        let _ = writeln!(stl, "      vertex {} {} {}", v.x, v.y, v.z);
+      }
+      stl.push_str("    endloop\n  endfacet\n");
+    }
+    stl.push_str("endsolid surface\n");
+    stl
+  }
+}
+
+/// The plane through three vertices as a function of (x, y), or `None` if the triangle is
+/// degenerate in plan.
+pub(crate) fn triangle_plane(
+  a: Point3D,
+  b: Point3D,
+  c: Point3D,
+) -> Option<impl Fn(f64, f64) -> f64> {
+  let det = (b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y);
+  if det.abs() < f64::EPSILON {
+    return None;
+  }
+  let slope_x = ((b.z - a.z) * (c.y - a.y) - (c.z - a.z) * (b.y - a.y)) / det;
+  let slope_y = ((c.z - a.z) * (b.x - a.x) - (b.z - a.z) * (c.x - a.x)) / det;
+  Some(move |x: f64, y: f64| a.z + slope_x * (x - a.x) + slope_y * (y - a.y))
 }
 
 impl ContourInput {
+  /// Default bound for [`ContourInput::validate`]: the elevation spread may be at most this
+  /// many times the larger side of the contour's xy extent.
+  pub const DEFAULT_MAX_RELIEF_RATIO: f64 = 100.0;
+
+  /// Create a contour from raw survey triples: x and y are pixel positions and z is the
+  /// elevation in `unit`. Every point becomes a point of interest; there are no lines.
+  pub fn from_points_3d(id: String, page_id: String, points: Vec<Point3D>, unit: Unit) -> Self {
+    Self {
+      id,
+      name: None,
+      page_id,
+      lines: vec![],
+      points_of_interest: points
+        .into_iter()
+        .map(|p| ContourPointOfInterestInput::new(p.x, p.y, p.z, unit))
+        .collect(),
+    }
+  }
+
   /// Convert contour elevations to pixel values using the given scale.
   ///
   /// Each contour line/POI elevation is converted from its unit to the scale's unit,
@@ -135,7 +609,7 @@ impl ContourInput {
       let elevation_in_scale_unit = if line.unit == scale_unit {
         line.elevation
       } else {
-        line.unit.convert(line.elevation as f32, &scale_unit) as f64
+        line.unit.convert(line.elevation, &scale_unit)
       };
       let elevation_px = elevation_in_scale_unit * ratio;
       for p in &line.points {
@@ -147,7 +621,7 @@ impl ContourInput {
       let elevation_in_scale_unit = if poi.unit == scale_unit {
         poi.elevation
       } else {
-        poi.unit.convert(poi.elevation as f32, &scale_unit) as f64
+        poi.unit.convert(poi.elevation, &scale_unit)
       };
       let elevation_px = elevation_in_scale_unit * ratio;
       points.push(Point3D::new(poi.point.x, poi.point.y, elevation_px));
@@ -156,43 +630,67 @@ impl ContourInput {
     Ok(points)
   }
 
-  /// Convert contour input to a triangulated 3D surface mesh.
+  /// Check that the elevations are plausible for the contour's footprint.
+  ///
+  /// Elevations and the xy extent are compared in pixels, so the check does not depend on
+  /// units. Near-degenerate inputs (a single point, all points on one spot) are left to the
+  /// triangulation errors.
   ///
   /// # Errors
   ///
-  /// Returns [`TakeoffError::SurfaceMeshTooFewPoints`] if there are fewer than 3 points.
-  /// Returns [`TakeoffError::SurfaceMeshCollinearPoints`] if all points are collinear.
-  pub fn to_surface_mesh(&self, scale: &Scale) -> TakeoffResult<SurfaceMesh> {
+  /// Returns [`TakeoffError::ContourElevationOutOfRange`] if an elevation is not finite or
+  /// the elevation spread exceeds `max_relief_ratio` times the larger side of the extent.
+  pub fn validate(&self, scale: &Scale, max_relief_ratio: f64) -> TakeoffResult<()> {
     let points = self.get_points_with_scale(scale)?;
-    let vertices = SurfaceMesh::deduplicate_points(&points);
-
-    if vertices.len() < 3 {
-      return Err(TakeoffError::SurfaceMeshTooFewPoints {
-        count: vertices.len(),
-      });
-    }
-
-    let delaunator_points: Vec<delaunator::Point> = vertices
+    let Some(((min_x, min_y), (max_x, max_y))) = self.bounding_box() else {
+      return Ok(());
+    };
+    let (min_z, max_z) = points
       .iter()
-      .map(|p| delaunator::Point { x: p.x, y: p.y })
-      .collect();
-
-    let result = triangulate(&delaunator_points);
-
-    if result.triangles.is_empty() {
-      return Err(TakeoffError::SurfaceMeshCollinearPoints);
+      .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| {
+        (lo.min(p.z), hi.max(p.z))
+      });
+    let extent = (max_x - min_x).max(max_y - min_y);
+    let finite = points.iter().all(|p| p.z.is_finite());
+    if !finite || (extent > 0.0 && max_z - min_z > max_relief_ratio * extent) {
+      let ratio = scale.ratio()?;
+      return Err(TakeoffError::ContourElevationOutOfRange {
+        min: min_z / ratio,
+        max: max_z / ratio,
+      });
     }
+    Ok(())
+  }
 
-    let triangles: Vec<[u32; 3]> = result
-      .triangles
-      .chunks_exact(3)
-      .map(|chunk| [chunk[0] as u32, chunk[1] as u32, chunk[2] as u32])
-      .collect();
+  /// Convert contour input to a triangulated 3D surface mesh.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::ContourElevationOutOfRange`] if the elevations fail
+  /// [`ContourInput::validate`] with the default bound.
+  /// Returns [`TakeoffError::SurfaceMeshTooFewPoints`] if there are fewer than 3 points.
+  /// Returns [`TakeoffError::SurfaceMeshCollinearPoints`] if all points are collinear.
+  pub fn to_surface_mesh(&self, scale: &Scale) -> TakeoffResult<SurfaceMesh> {
+    self.to_surface_mesh_with_tolerances(scale, MeshTolerances::default())
+  }
 
-    Ok(SurfaceMesh {
-      vertices,
-      triangles,
-    })
+  /// Like [`ContourInput::to_surface_mesh`], deduplicating points with
+  /// `tolerances.vertex_epsilon` and storing `tolerances` on the mesh for later sampling.
+  ///
+  /// # Errors
+  ///
+  /// Same as [`ContourInput::to_surface_mesh`], and [`TakeoffError::InvalidInput`] if
+  /// `tolerances` fail [`MeshTolerances::validate`].
+  pub fn to_surface_mesh_with_tolerances(
+    &self,
+    scale: &Scale,
+    tolerances: MeshTolerances,
+  ) -> TakeoffResult<SurfaceMesh> {
+    tolerances.validate()?;
+    self.validate(scale, Self::DEFAULT_MAX_RELIEF_RATIO)?;
+    let points = self.get_points_with_scale(scale)?;
+    let vertices = SurfaceMesh::deduplicate_points(&points, tolerances.vertex_epsilon);
+    SurfaceMesh::triangulate(vertices, tolerances)
   }
 
   fn get_geometry_collection(&self) -> GeometryCollection {
@@ -225,6 +723,27 @@ impl ContourInput {
     }
     None
   }
+
+  /// Get the contour bounding box in real-world `unit`, e.g. to show "site is 420 ft × 310 ft".
+  ///
+  /// The pixel bounds from [`ContourInput::bounding_box`] are divided by the scale ratio and
+  /// converted to `unit`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::EmptyGeometry`] if the contour has no points, or an
+  /// invalid-scale error if the scale is invalid.
+  pub fn bounding_box_in_unit(
+    &self,
+    scale: &Scale,
+    unit: Unit,
+  ) -> TakeoffResult<((f64, f64), (f64, f64))> {
+    let ((min_x, min_y), (max_x, max_y)) = self
+      .bounding_box()
+      .ok_or_else(|| TakeoffError::empty_geometry("contour has no points"))?;
+    let real = |pixels: f64| scale.pixels_to_real(pixels, unit);
+    Ok(((real(min_x)?, real(min_y)?), (real(max_x)?, real(max_y)?)))
+  }
 }
 
 #[cfg(test)]
@@ -246,6 +765,33 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_from_points_3d() {
+    let input = ContourInput::from_points_3d(
+      "c1".to_string(),
+      "p1".to_string(),
+      vec![Point3D::new(0.0, 0.0, 10.0), Point3D::new(5.0, 2.0, 12.5)],
+      Unit::Feet,
+    );
+    assert_eq!(input.page_id, "p1");
+    assert!(input.lines.is_empty());
+    assert_eq!(
+      input.points_of_interest,
+      vec![
+        ContourPointOfInterestInput::new(0.0, 0.0, 10.0, Unit::Feet),
+        ContourPointOfInterestInput::new(5.0, 2.0, 12.5, Unit::Feet),
+      ]
+    );
+    assert_eq!(
+      ContourLineInput::new(vec![Point::new(1.0, 1.0)], 3.0, Unit::Meters),
+      ContourLineInput {
+        elevation: 3.0,
+        points: vec![Point::new(1.0, 1.0)],
+        unit: Unit::Meters,
+      }
+    );
+  }
+
   #[test]
   fn test_get_points_with_scale() {
     let input = ContourInput {
@@ -308,7 +854,7 @@ mod tests {
     // 1 meter = ~3.28084 feet, ratio = 120 px/ft
     let points = input.get_points_with_scale(&scale).unwrap();
     assert_eq!(points.len(), 1);
-    let expected_z = Unit::Meters.convert(1.0, &Unit::Feet) as f64 * 120.0;
+    let expected_z = Unit::Meters.convert(1.0, &Unit::Feet) * 120.0;
     assert!(
       (points[0].z - expected_z).abs() < 1.0,
       "expected ~{}, got {}",
@@ -343,6 +889,17 @@ mod tests {
     assert_eq!(mesh.vertices.len(), 5);
     assert!(!mesh.triangles.is_empty());
 
+    for vertex_epsilon in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+      let tolerances = MeshTolerances {
+        vertex_epsilon,
+        ..MeshTolerances::default()
+      };
+      assert!(matches!(
+        input.to_surface_mesh_with_tolerances(&identity_scale(), tolerances),
+        Err(TakeoffError::InvalidInput { .. })
+      ));
+    }
+
     assert!(
       mesh
         .triangles
@@ -420,6 +977,90 @@ mod tests {
     assert_eq!(mesh.vertices.len(), 4);
   }
 
+  /// Reference O(n²) dedup the grid-bucketed version must match.
+  fn naive_deduplicate(points: &[Point3D]) -> Vec<Point3D> {
+    let mut seen: Vec<Point3D> = Vec::new();
+    for p in points {
+      let is_dup = seen.iter().any(|s| {
+        (s.x - p.x).abs() < SurfaceMesh::VERTEX_EPSILON
+          && (s.y - p.y).abs() < SurfaceMesh::VERTEX_EPSILON
+      });
+      if !is_dup {
+        seen.push(*p);
+      }
+    }
+    seen
+  }
+
+  #[test]
+  fn test_deduplicate_points_matches_naive() {
+    let eps = SurfaceMesh::VERTEX_EPSILON;
+    let points = vec![
+      Point3D::new(0.0, 0.0, 1.0),
+      Point3D::new(0.0, 0.0, 2.0),
+      Point3D::new(eps * 0.5, -eps * 0.5, 3.0),
+      Point3D::new(eps * 1.5, 0.0, 4.0),
+      Point3D::new(-eps * 0.9, eps * 0.9, 5.0),
+      Point3D::new(10.0, 10.0, 6.0),
+      Point3D::new(10.0 + eps * 0.2, 10.0, 7.0),
+      Point3D::new(-3.5, 2.25, 8.0),
+    ];
+    assert_eq!(
+      SurfaceMesh::deduplicate_points(&points, SurfaceMesh::VERTEX_EPSILON),
+      naive_deduplicate(&points)
+    );
+  }
+
+  #[test]
+  fn test_z_at_merged_vertex_returns_kept_z() {
+    let eps = SurfaceMesh::VERTEX_EPSILON;
+    let input = ContourInput {
+      id: "1".to_string(),
+      name: None,
+      page_id: "1".to_string(),
+      lines: vec![
+        ContourLineInput {
+          elevation: 10.0,
+          points: vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0)],
+          unit: Unit::Feet,
+        },
+        ContourLineInput {
+          elevation: 20.0,
+          points: vec![Point::new(eps * 0.5, eps * 0.5), Point::new(0.0, 10.0)],
+          unit: Unit::Feet,
+        },
+      ],
+      points_of_interest: vec![],
+    };
+    let mesh = input.to_surface_mesh(&identity_scale()).unwrap();
+    assert_eq!(mesh.vertices.len(), 3);
+    // Querying the dropped duplicate snaps to the first (kept) vertex.
+    assert_eq!(mesh.z_at(eps * 0.5, eps * 0.5), Some(10.0));
+    assert_eq!(mesh.z_at(0.0, 0.0), Some(10.0));
+  }
+
+  #[test]
+  fn test_deduplicate_points_large_input() {
+    // 10k distinct points plus a duplicate of each.
+    let points: Vec<Point3D> = (0..20_000)
+      .map(|i| {
+        let k = (i % 10_000) as f64;
+        Point3D::new(k % 100.0, (k / 100.0).floor(), i as f64)
+      })
+      .collect();
+    let deduped = SurfaceMesh::deduplicate_points(&points, SurfaceMesh::VERTEX_EPSILON);
+    assert_eq!(deduped.len(), 10_000);
+
+    // Cell indices past the i64 range saturate instead of overflowing.
+    let far = [
+      Point3D::new(20_000.0, 0.0, 1.0),
+      Point3D::new(20_000.0, 0.0, 2.0),
+      Point3D::new(20_001.0, 0.0, 3.0),
+    ];
+    assert_eq!(SurfaceMesh::deduplicate_points(&far, 1e-15).len(), 2);
+    assert_eq!(deduped, naive_deduplicate(&points[..10_000]));
+  }
+
   #[test]
   fn test_z_at_at_vertex() {
     let input = ContourInput {
@@ -448,6 +1089,85 @@ mod tests {
     assert_eq!(mesh.z_at(10.0, 10.0), Some(10.0));
   }
 
+  #[test]
+  fn test_validate_flags_implausible_elevation_spread() {
+    let mut input = ContourInput {
+      id: "1".to_string(),
+      name: None,
+      page_id: "1".to_string(),
+      lines: vec![ContourLineInput {
+        elevation: 10.0,
+        points: vec![
+          Point::new(0.0, 0.0),
+          Point::new(10.0, 0.0),
+          Point::new(10.0, 10.0),
+          Point::new(0.0, 10.0),
+        ],
+        unit: Unit::Feet,
+      }],
+      points_of_interest: vec![ContourPointOfInterestInput {
+        elevation: 1e9,
+        point: Point::new(5.0, 5.0),
+        unit: Unit::Feet,
+      }],
+    };
+    let err = input.to_surface_mesh(&identity_scale()).unwrap_err();
+    assert_eq!(
+      err,
+      TakeoffError::ContourElevationOutOfRange {
+        min: 10.0,
+        max: 1e9
+      }
+    );
+    // A looser bound accepts the same input.
+    assert!(input.validate(&identity_scale(), 1e9).is_ok());
+
+    input.points_of_interest[0].elevation = 15.0;
+    assert!(
+      input
+        .validate(&identity_scale(), ContourInput::DEFAULT_MAX_RELIEF_RATIO)
+        .is_ok()
+    );
+    input.points_of_interest[0].elevation = f64::NAN;
+    assert!(input.to_surface_mesh(&identity_scale()).is_err());
+  }
+
+  #[test]
+  fn test_bounding_box_in_unit() {
+    let mut input = ContourInput {
+      id: "1".to_string(),
+      name: None,
+      page_id: "1".to_string(),
+      lines: vec![ContourLineInput {
+        elevation: 10.0,
+        points: vec![Point::new(10.0, 20.0), Point::new(4210.0, 3120.0)],
+        unit: Unit::Feet,
+      }],
+      points_of_interest: vec![],
+    };
+    // 10 px per foot.
+    let scale = Scale::Default {
+      id: "s1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 10.0,
+        real_distance: 1.0,
+        unit: Unit::Feet,
+      },
+    };
+    let ((min_x, min_y), (max_x, max_y)) = input.bounding_box_in_unit(&scale, Unit::Feet).unwrap();
+    assert!((max_x - min_x - 420.0).abs() < 1e-9);
+    assert!((max_y - min_y - 310.0).abs() < 1e-9);
+    let ((_, _), (max_x_in, _)) = input.bounding_box_in_unit(&scale, Unit::Inches).unwrap();
+    assert!((max_x_in - 421.0 * 12.0).abs() < 1e-9);
+
+    input.lines.clear();
+    assert!(matches!(
+      input.bounding_box_in_unit(&scale, Unit::Feet),
+      Err(TakeoffError::EmptyGeometry { .. })
+    ));
+  }
+
   #[test]
   fn test_to_surface_mesh_with_scale() {
     let input = ContourInput {
@@ -508,6 +1228,7 @@ mod tests {
         Point3D::new(5.0, 10.0, 5.0),
       ],
       triangles: vec![[0, 1, 2]],
+      tolerances: MeshTolerances::default(),
     };
     let z = mesh.z_at(5.0, 5.0).unwrap();
     assert!((z - 5.0).abs() < 1e-6, "expected ~5.0, got {}", z);
@@ -530,12 +1251,226 @@ mod tests {
         Point3D::new(5.0, 10.0, 0.0),
       ],
       triangles: vec![[0, 1, 2]],
+      tolerances: MeshTolerances::default(),
     };
     assert_eq!(mesh.z_at(-1.0, -1.0), None);
     assert_eq!(mesh.z_at(100.0, 100.0), None);
     assert_eq!(mesh.z_at(5.0, -1.0), None);
   }
 
+  /// Regular `n` x `n` grid mesh with unit spacing; each cell is split along its
+  /// (i, j) -> (i + 1, j + 1) diagonal.
+  fn grid_mesh(n: u32, z: impl Fn(u32, u32) -> f64) -> SurfaceMesh {
+    let mut vertices = Vec::new();
+    for j in 0..n {
+      for i in 0..n {
+        vertices.push(Point3D::new(i as f64, j as f64, z(i, j)));
+      }
+    }
+    let mut triangles = Vec::new();
+    for j in 0..n - 1 {
+      for i in 0..n - 1 {
+        let a = j * n + i;
+        let b = a + 1;
+        let c = a + n + 1;
+        let d = a + n;
+        triangles.push([a, b, c]);
+        triangles.push([a, c, d]);
+      }
+    }
+    SurfaceMesh {
+      vertices,
+      triangles,
+      tolerances: MeshTolerances::default(),
+    }
+  }
+
+  #[test]
+  fn test_clip_to_polygon() {
+    // z = i + j on a 5 x 5 grid covering [0, 4] x [0, 4].
+    let mesh = grid_mesh(5, |i, j| (i + j) as f64);
+    let mesh_area = |mesh: &SurfaceMesh| -> f64 {
+      mesh
+        .triangles
+        .iter()
+        .map(|tri| {
+          let [a, b, c] = tri.map(|i| mesh.vertices[i as usize]);
+          Triangle::from([(a.x, a.y), (b.x, b.y), (c.x, c.y)]).unsigned_area()
+        })
+        .sum()
+    };
+
+    // Aligned with grid lines: whole triangles are kept and unused vertices dropped.
+    let inner = mesh.clip_to_polygon(&[
+      Point::new(1.0, 1.0),
+      Point::new(3.0, 1.0),
+      Point::new(3.0, 3.0),
+      Point::new(1.0, 3.0),
+    ]);
+    assert_eq!(inner.triangles.len(), 8);
+    assert_eq!(inner.vertices.len(), 9);
+    assert!((mesh_area(&inner) - 4.0).abs() < 1e-9);
+
+    // A diamond crossing triangles: clipped pieces keep the original surface.
+    let diamond = mesh.clip_to_polygon(&[
+      Point::new(2.0, 0.5),
+      Point::new(3.5, 2.0),
+      Point::new(2.0, 3.5),
+      Point::new(0.5, 2.0),
+    ]);
+    assert!((mesh_area(&diamond) - 4.5).abs() < 1e-9);
+    for v in &diamond.vertices {
+      assert!((v.z - (v.x + v.y)).abs() < 1e-9);
+    }
+    assert!((diamond.z_at(2.0, 2.5).unwrap() - 4.5).abs() < 1e-9);
+    assert_eq!(diamond.z_at(0.2, 0.2), None);
+
+    let outside = mesh.clip_to_polygon(&[
+      Point::new(10.0, 10.0),
+      Point::new(11.0, 10.0),
+      Point::new(11.0, 11.0),
+    ]);
+    assert!(outside.triangles.is_empty() && outside.vertices.is_empty());
+  }
+
+  #[test]
+  fn test_to_obj() {
+    let mesh = grid_mesh(3, |i, j| (i + j) as f64);
+    let obj = mesh.to_obj();
+
+    let vertices: Vec<Vec<f64>> = obj
+      .lines()
+      .filter_map(|line| line.strip_prefix("v "))
+      .map(|rest| rest.split(' ').map(|s| s.parse().unwrap()).collect())
+      .collect();
+    let faces: Vec<Vec<u32>> = obj
+      .lines()
+      .filter_map(|line| line.strip_prefix("f "))
+      .map(|rest| rest.split(' ').map(|s| s.parse().unwrap()).collect())
+      .collect();
+    assert_eq!(vertices.len(), 9);
+    assert_eq!(vertices[8], vec![2.0, 2.0, 4.0]);
+    assert_eq!(faces.len(), mesh.triangles.len());
+    for (face, tri) in faces.iter().zip(&mesh.triangles) {
+      assert_eq!(*face, tri.iter().map(|i| i + 1).collect::<Vec<_>>());
+    }
+  }
+
+  #[test]
+  fn test_to_stl_ascii() {
+    let mesh = grid_mesh(3, |_, _| 1.0);
+    let stl = mesh.to_stl_ascii();
+
+    assert!(stl.starts_with("solid surface\n"));
+    assert!(stl.ends_with("endsolid surface\n"));
+    let normals: Vec<&str> = stl
+      .lines()
+      .filter_map(|line| line.trim().strip_prefix("facet normal "))
+      .collect();
+    assert_eq!(normals.len(), mesh.triangles.len());
+    // The grid is flat and wound counter-clockwise, so every normal points up.
+    assert!(normals.iter().all(|n| *n == "0 0 1"));
+    let vertex_lines = stl
+      .lines()
+      .filter(|line| line.trim().starts_with("vertex "))
+      .count();
+    assert_eq!(vertex_lines, 3 * mesh.triangles.len());
+  }
+
+  fn mean_and_variance(mesh: &SurfaceMesh) -> (f64, f64) {
+    let n = mesh.vertices.len() as f64;
+    let mean = mesh.vertices.iter().map(|v| v.z).sum::<f64>() / n;
+    let variance = mesh
+      .vertices
+      .iter()
+      .map(|v| (v.z - mean).powi(2))
+      .sum::<f64>()
+      / n;
+    (mean, variance)
+  }
+
+  #[test]
+  fn test_smoothed_reduces_variance_preserves_mean() {
+    // Boundary at 10, interior checkerboard of 11 / 9 (mean exactly 10).
+    let mesh = grid_mesh(4, |i, j| {
+      let interior = (1..3).contains(&i) && (1..3).contains(&j);
+      if !interior {
+        10.0
+      } else if (i + j) % 2 == 0 {
+        11.0
+      } else {
+        9.0
+      }
+    });
+    let (mean_before, variance_before) = mean_and_variance(&mesh);
+
+    let smoothed = mesh.smoothed(5, 0.5);
+    let (mean_after, variance_after) = mean_and_variance(&smoothed);
+
+    assert!(
+      variance_after < variance_before,
+      "variance should drop: {} -> {}",
+      variance_before,
+      variance_after
+    );
+    assert!(
+      (mean_after - mean_before).abs() < 0.05,
+      "mean should be preserved: {} -> {}",
+      mean_before,
+      mean_after
+    );
+    assert_eq!(smoothed.triangles, mesh.triangles);
+    for (before, after) in mesh.vertices.iter().zip(&smoothed.vertices) {
+      assert_eq!((before.x, before.y), (after.x, after.y));
+      let on_boundary = before.x == 0.0 || before.y == 0.0 || before.x == 3.0 || before.y == 3.0;
+      if on_boundary {
+        assert_eq!(before.z, after.z, "boundary vertices must not move");
+      }
+    }
+  }
+
+  #[test]
+  fn test_drape_length() {
+    // Plane rising 3 per 4 across x: a run of 4 along x is 5 long over the terrain.
+    let mesh = grid_mesh(5, |i, _| i as f64 * 0.75);
+    let run = [Point::new(0.0, 2.0), Point::new(4.0, 2.0)];
+    assert!((mesh.drape_length(&run) - 5.0).abs() < 1e-9);
+
+    // The segment leaving the mesh falls back to its planimetric length.
+    let off_mesh = [
+      Point::new(0.0, 2.0),
+      Point::new(4.0, 2.0),
+      Point::new(10.0, 2.0),
+    ];
+    assert!((mesh.drape_length(&off_mesh) - 11.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_drape_slopes_on_linear_ramp() {
+    // Rises 0.75 per 1 along x: a 75% grade uphill, -75% back down, flat across y.
+    let mesh = grid_mesh(5, |i, _| i as f64 * 0.75);
+    let path = [
+      Point::new(0.0, 1.0),
+      Point::new(1.5, 1.0),
+      Point::new(4.0, 1.0),
+      Point::new(2.0, 1.0),
+      Point::new(2.0, 3.0),
+      Point::new(9.0, 3.0),
+    ];
+    let slopes = mesh.drape_slopes(&path);
+    assert_eq!(slopes.len(), 5);
+    for (slope, expected) in slopes[..4].iter().zip([75.0, 75.0, -75.0, 0.0]) {
+      assert!((slope.unwrap() - expected).abs() < 1e-9);
+    }
+    assert_eq!(slopes[4], None);
+  }
+
+  #[test]
+  fn test_smoothed_zero_iterations_is_identity() {
+    let mesh = grid_mesh(4, |i, j| (i * j) as f64);
+    assert_eq!(mesh.smoothed(0, 0.5), mesh);
+  }
+
   #[test]
   fn test_z_at_on_edge() {
     let mesh = SurfaceMesh {
@@ -545,6 +1480,7 @@ mod tests {
         Point3D::new(5.0, 10.0, 5.0),
       ],
       triangles: vec![[0, 1, 2]],
+      tolerances: MeshTolerances::default(),
     };
     let z = mesh.z_at(5.0, 0.0).unwrap();
     assert!(
@@ -553,4 +1489,94 @@ mod tests {
       z
     );
   }
+
+  /// Triangles as sorted index triples in sorted order, to compare triangulations.
+  fn normalized_triangles(mesh: &SurfaceMesh) -> Vec<[u32; 3]> {
+    let mut triangles: Vec<[u32; 3]> = mesh
+      .triangles
+      .iter()
+      .map(|tri| {
+        let mut tri = *tri;
+        tri.sort_unstable();
+        tri
+      })
+      .collect();
+    triangles.sort_unstable();
+    triangles
+  }
+
+  #[test]
+  fn test_insert_point_matches_full_rebuild() {
+    // Deterministic pseudo-random points in general position.
+    let mut seed: u64 = 42;
+    let mut next = || {
+      seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+      (seed >> 11) as f64 / (1u64 << 53) as f64 * 100.0
+    };
+    let mut points: Vec<Point3D> = (0..40)
+      .map(|_| {
+        let (x, y) = (next(), next());
+        Point3D::new(x, y, 0.3 * x - 0.2 * y)
+      })
+      .collect();
+    // Off the hull of the first 20 points, exercising the full-rebuild fallback.
+    points.push(Point3D::new(150.0, -20.0, 7.0));
+    points.push(Point3D::new(40.0, 60.0, 1.0));
+
+    let tolerances = MeshTolerances::default();
+    let mut mesh = SurfaceMesh::triangulate(points[..20].to_vec(), tolerances).unwrap();
+    for p in &points[20..] {
+      mesh.insert_point(*p).unwrap();
+    }
+    let rebuilt = SurfaceMesh::triangulate(points.clone(), tolerances).unwrap();
+    assert_eq!(mesh.vertices, rebuilt.vertices);
+    assert_eq!(normalized_triangles(&mesh), normalized_triangles(&rebuilt));
+
+    // A duplicate of an existing vertex is ignored.
+    mesh.insert_point(Point3D::new(40.0, 60.0, 99.0)).unwrap();
+    assert_eq!(mesh.vertices.len(), points.len());
+    assert_eq!(mesh.z_at(40.0, 60.0), Some(1.0));
+  }
+
+  #[test]
+  fn test_insert_point_off_mesh_errors_leave_mesh_unchanged() {
+    let mut mesh = SurfaceMesh {
+      vertices: vec![Point3D::new(0.0, 0.0, 0.0), Point3D::new(1.0, 1.0, 0.0)],
+      triangles: vec![],
+      tolerances: MeshTolerances::default(),
+    };
+    let before = mesh.clone();
+    assert!(matches!(
+      mesh.insert_point(Point3D::new(2.0, 2.0, 0.0)),
+      Err(TakeoffError::SurfaceMeshCollinearPoints)
+    ));
+    assert_eq!(mesh, before);
+    mesh.insert_point(Point3D::new(1.0, 0.0, 3.0)).unwrap();
+    assert_eq!(mesh.triangles.len(), 1);
+  }
+
+  #[test]
+  fn test_z_at_large_coordinates_with_tolerances() {
+    // At 2^23 one ulp is 2^-29, so a query one ulp below the bottom edge is outside by more
+    // than the default barycentric tolerance.
+    let origin = 8_388_608.0;
+    let mut mesh = SurfaceMesh {
+      vertices: vec![
+        Point3D::new(origin, origin, 0.0),
+        Point3D::new(origin + 1.0, origin, 10.0),
+        Point3D::new(origin, origin + 1.0, 0.0),
+      ],
+      triangles: vec![[0, 1, 2]],
+      tolerances: MeshTolerances::default(),
+    };
+    let (x, y) = (origin + 0.5, origin - 2.0_f64.powi(-29));
+    assert_eq!(mesh.z_at(x, y), None);
+
+    mesh.tolerances.barycentric_epsilon = 1e-6;
+    let z = mesh.z_at(x, y).unwrap();
+    assert!((z - 5.0).abs() < 1e-6, "expected ~5.0, got {}", z);
+    assert_eq!(mesh.smoothed(1, 0.5).tolerances, mesh.tolerances);
+  }
 }
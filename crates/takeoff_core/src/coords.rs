@@ -67,6 +67,18 @@ impl Point3D {
   pub fn new(x: f64, y: f64, z: f64) -> Self {
     Self { x, y, z }
   }
+
+  /// The planimetric position of the point; `z` is dropped.
+  pub fn to_geo_point(&self) -> GeoPoint<f64> {
+    GeoPoint::new(self.x, self.y)
+  }
+}
+
+impl From<Point3D> for delaunator::Point {
+  /// Drops `z`; the triangulation only uses the planimetric position.
+  fn from(p: Point3D) -> Self {
+    delaunator::Point { x: p.x, y: p.y }
+  }
 }
 
 impl DistanceTrait<Point3D> for Point3D {
@@ -107,4 +119,15 @@ mod tests {
     );
     assert_eq!(Euclidean.distance(&start, &end).round(), 70.0);
   }
+
+  #[test]
+  fn test_point3d_conversions_keep_xy() {
+    let point = Point3D::new(0.1 + 0.2, -1e-300, 42.0);
+
+    let geo_point = point.to_geo_point();
+    assert_eq!((geo_point.x(), geo_point.y()), (point.x, point.y));
+
+    let delaunator_point: delaunator::Point = point.into();
+    assert_eq!((delaunator_point.x, delaunator_point.y), (point.x, point.y));
+  }
 }
@@ -47,6 +47,16 @@ pub enum TakeoffError {
     unit: String,
   },
 
+  /// An argument is outside the range an operation accepts.
+  ///
+  /// This error is returned when:
+  /// - A scaling factor (e.g. a waste factor) is negative or not finite
+  #[error("invalid input: {message}")]
+  InvalidInput {
+    /// Human-readable message describing why the input is invalid
+    message: String,
+  },
+
   // Contour Errors
   /// Too few points for triangulation (need at least 3).
   #[error("too few points for triangulation: {count} (need at least 3)")]
@@ -55,6 +65,16 @@ pub enum TakeoffError {
   #[error("all points are collinear; cannot create triangulated surface")]
   SurfaceMeshCollinearPoints,
 
+  /// Contour elevations span an implausible range for the contour's xy extent.
+  ///
+  /// This error is returned when the elevation spread exceeds the sanity bound passed to
+  /// `ContourInput::validate`, typically because of a mistyped elevation (e.g. a stray
+  /// point of interest at 1e9). `min` and `max` are in the scale's unit.
+  #[error(
+    "contour elevations range from {min} to {max}, which is implausible for the contour's extent"
+  )]
+  ContourElevationOutOfRange { min: f64, max: f64 },
+
   /// No scale found for the contour's page.
   #[error("no scale found for contour {contour_id} on page")]
   ContourMissingScale { contour_id: String },
@@ -73,6 +93,19 @@ pub enum TakeoffError {
     resource: String,
   },
 
+  /// An error annotated with what was being computed when it occurred, e.g.
+  /// `"group g1 → measurement m1 → invalid scale: ..."`.
+  ///
+  /// Created with [`TakeoffError::with_context`]; [`TakeoffError::root_cause`] returns the
+  /// innermost error.
+  #[error("{context} → {source}")]
+  Chained {
+    /// What was being computed, e.g. `"measurement m1"`
+    context: String,
+    /// The underlying error
+    source: Box<TakeoffError>,
+  },
+
   // Catchall Error
   #[error("an unknown error occurred: {message}")]
   UnknownError { message: String },
@@ -98,6 +131,13 @@ impl TakeoffError {
     Self::UnknownUnit { unit: unit.into() }
   }
 
+  /// Create an `InvalidInput` error with a message.
+  pub fn invalid_input(message: impl Into<String>) -> Self {
+    Self::InvalidInput {
+      message: message.into(),
+    }
+  }
+
   /// Create a `ContourMissingScale` error.
   pub fn contour_missing_scale(contour_id: impl Into<String>) -> Self {
     Self::ContourMissingScale {
@@ -105,6 +145,23 @@ impl TakeoffError {
     }
   }
 
+  /// Wrap this error with the context it occurred in.
+  pub fn with_context(self, context: impl Into<String>) -> Self {
+    Self::Chained {
+      context: context.into(),
+      source: Box::new(self),
+    }
+  }
+
+  /// The innermost error of a chain built with [`TakeoffError::with_context`].
+  pub fn root_cause(&self) -> &TakeoffError {
+    let mut error = self;
+    while let Self::Chained { source, .. } = error {
+      error = source;
+    }
+    error
+  }
+
   /// Create a `PoisonError` error for a poisoned mutex lock.
   pub fn poison_error(resource: impl Into<String>) -> Self {
     Self::PoisonError {
@@ -119,12 +176,16 @@ impl From<TakeoffError> for NapiError {
       TakeoffError::EmptyGeometry { message } => NapiError::new(Status::InvalidArg, message),
       TakeoffError::InvalidScale { message } => NapiError::new(Status::InvalidArg, message),
       TakeoffError::UnknownUnit { unit } => NapiError::new(Status::InvalidArg, unit),
+      TakeoffError::InvalidInput { message } => NapiError::new(Status::InvalidArg, message),
       TakeoffError::SurfaceMeshTooFewPoints { .. } => {
         NapiError::new(Status::InvalidArg, error.to_string())
       }
       TakeoffError::SurfaceMeshCollinearPoints => {
         NapiError::new(Status::InvalidArg, error.to_string())
       }
+      TakeoffError::ContourElevationOutOfRange { .. } => {
+        NapiError::new(Status::InvalidArg, error.to_string())
+      }
       TakeoffError::ContourMissingScale { .. } => {
         NapiError::new(Status::InvalidArg, error.to_string())
       }
@@ -132,6 +193,10 @@ impl From<TakeoffError> for NapiError {
         Status::GenericFailure,
         format!("mutex lock poisoned: {}", resource),
       ),
+      TakeoffError::Chained { .. } => {
+        let status = NapiError::from(error.root_cause().clone()).status;
+        NapiError::new(status, error.to_string())
+      }
       TakeoffError::UnknownError { message } => NapiError::new(Status::InvalidArg, message),
     }
   }
@@ -145,10 +210,11 @@ impl From<NapiError> for TakeoffError {
   }
 }
 
-impl<T> From<std::sync::PoisonError<T>> for TakeoffError {
-  fn from(_error: std::sync::PoisonError<T>) -> Self {
-    TakeoffError::PoisonError {
-      resource: "unknown".to_string(),
+impl From<anyhow::Error> for TakeoffError {
+  fn from(error: anyhow::Error) -> Self {
+    TakeoffError::UnknownError {
+      // `{:#}` keeps the whole context chain, e.g. "outer: inner"
+      message: format!("{:#}", error),
     }
   }
 }
@@ -176,12 +242,52 @@ mod tests {
     assert!(serialized.contains("unknownUnit"));
     assert!(serialized.contains("kilometers"));
 
+    let err = TakeoffError::invalid_input("factor must not be negative");
+    let serialized = serde_json::to_string(&err).unwrap();
+    assert!(serialized.contains("invalidInput"));
+    assert!(serialized.contains("factor must not be negative"));
+
     let err = TakeoffError::poison_error("scale");
     let serialized = serde_json::to_string(&err).unwrap();
     assert!(serialized.contains("poisonError"));
     assert!(serialized.contains("scale"));
   }
 
+  #[test]
+  fn test_chained_error() {
+    let err = TakeoffError::invalid_scale("scale ratio cannot be zero")
+      .with_context("measurement m1")
+      .with_context("group g1");
+    assert_eq!(
+      err.to_string(),
+      "group g1 → measurement m1 → invalid scale: scale ratio cannot be zero"
+    );
+    assert_eq!(
+      err.root_cause(),
+      &TakeoffError::invalid_scale("scale ratio cannot be zero")
+    );
+
+    let serialized = serde_json::to_string(&err).unwrap();
+    assert!(serialized.contains("chained"));
+    let deserialized: TakeoffError = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, err);
+
+    assert_eq!(NapiError::from(err).status, Status::InvalidArg);
+  }
+
+  #[test]
+  fn test_from_anyhow_keeps_context_chain() {
+    let err: TakeoffError = anyhow::anyhow!("file not found")
+      .context("loading contour")
+      .into();
+    assert_eq!(
+      err,
+      TakeoffError::UnknownError {
+        message: "loading contour: file not found".to_string()
+      }
+    );
+  }
+
   #[test]
   fn test_error_deserialization() {
     let json = r#"{"type":"emptyGeometry","message":"polygon has fewer than 3 points"}"#;
@@ -230,6 +336,13 @@ mod tests {
     assert!(display.contains("all points are collinear"));
     assert!(display.contains("cannot create triangulated surface"));
 
+    let err = TakeoffError::ContourElevationOutOfRange {
+      min: 10.0,
+      max: 1e9,
+    };
+    let display = format!("{}", err);
+    assert!(display.contains("contour elevations range from 10 to 1000000000"));
+
     let err = TakeoffError::poison_error("scale");
     let display = format!("{}", err);
     assert!(display.contains("mutex lock poisoned"));
@@ -1,3 +1,4 @@
+use crate::unit::Unit;
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
 
@@ -7,6 +8,8 @@ pub enum MeasurementType {
   Area,
   Linear,
   Count,
+  /// Earthwork volume from contour cut/fill computations.
+  Volume,
 }
 
 #[napi(object)]
@@ -16,4 +19,23 @@ pub struct Group {
   pub name: Option<String>,
   //   pub measurements: Vec<Measurement>,
   pub measurement_type: MeasurementType,
+  /// Display color of the group, e.g. `"#ff0000"`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub color: Option<String>,
+  /// Unit the group's totals are displayed in, overriding the caller's choice.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub unit_override: Option<Unit>,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_group_deserializes_without_display_fields() {
+    let group: Group =
+      serde_json::from_str(r#"{"id":"1","name":null,"measurement_type":"Area"}"#).unwrap();
+    assert_eq!(group.color, None);
+    assert_eq!(group.unit_override, None);
+  }
 }
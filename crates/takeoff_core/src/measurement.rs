@@ -1,8 +1,16 @@
 use crate::coords::{DistanceTrait, Point};
 use crate::error::{TakeoffError, TakeoffResult};
-use geo::{Area, Centroid, Coord, CoordsIter, Geometry, LineString, Polygon as GeoPolygon, Rect};
+use crate::group::MeasurementType;
+use crate::page::{CoordinateSystem, Page};
+use geo::{
+  Area, Centroid, ConvexHull, Coord, CoordsIter, GeodesicArea, Geometry, Intersects, Line,
+  LineString, MultiPoint, Polygon as GeoPolygon, Rect,
+};
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use uom::si::f64::{Area as AreaQuantity, Length, Volume};
 
 #[napi(discriminant = "type")]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -12,28 +20,81 @@ pub enum Measurement {
     page_id: String,
     group_id: String,
     points: (Point,),
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<HashMap<String, String>>,
   },
+  /// `points` is the open ring: the first point should not be repeated at the end.
+  /// A duplicated closing vertex is tolerated and ignored.
   Polygon {
     id: String,
     page_id: String,
     group_id: String,
     points: Vec<Point>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<HashMap<String, String>>,
   },
   Polyline {
     id: String,
     page_id: String,
     group_id: String,
     points: Vec<Point>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<HashMap<String, String>>,
   },
+  /// `points` are two opposite corners of the unrotated rectangle. `rotation` turns it
+  /// counter-clockwise (radians) about its center; `None` is axis-aligned, which keeps
+  /// two-corner data saved before rotation support valid.
   Rectangle {
     id: String,
     page_id: String,
     group_id: String,
     points: (Point, Point),
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rotation: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<HashMap<String, String>>,
   },
 }
 
+/// How a measurement's geometry changed between two versions (see [`Measurement::diff`]).
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MeasurementDiff {
+  /// The measurement kind changed (e.g. polygon to polyline); point lists are not compared.
+  pub kind_changed: bool,
+  /// Indices into the new points of points that did not exist before.
+  pub added: Vec<u32>,
+  /// Indices into the old points of points that no longer exist.
+  pub removed: Vec<u32>,
+  /// Indices into the new points of points that exist in both versions but moved.
+  pub moved: Vec<u32>,
+}
+
+/// Shape metrics used to flag suspicious traces (see [`Measurement::shape_stats`]).
+///
+/// Edge lengths are in pixels; the ratios are scale-free.
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShapeStats {
+  pub min_edge: f64,
+  pub max_edge: f64,
+  /// Closed shapes count their closing edge; polylines do not.
+  pub edge_count: u32,
+  /// Long side over short side of the minimum-area bounding rectangle, so always at least 1.
+  /// Infinite when all vertices are collinear.
+  pub aspect_ratio: f64,
+  /// `4π·area / perimeter²`: 1 for a circle, about 0.785 for a square, near 0 for slivers.
+  /// Always 0 for polylines.
+  pub compactness: f64,
+}
+
 impl Measurement {
+  /// Coordinates closer than this are treated as the same position by [`Measurement::geometry_eq`].
+  const GEOMETRY_EPSILON: f64 = 1e-6;
+
+  /// Grid step, in pixels, that [`Measurement::geometry_hash`] rounds coordinates to.
+  pub const GEOMETRY_HASH_STEP: f64 = 0.01;
+
   /// Validate that the measurement has valid geometry.
   ///
   /// # Errors
@@ -41,10 +102,11 @@ impl Measurement {
   /// Returns [`TakeoffError::EmptyGeometry`] if:
   /// - Polygon has fewer than 3 points
   /// - Polyline has fewer than 2 points
-  /// - Rectangle has invalid or identical corner points
+  /// - Rectangle has invalid or identical corner points, or a non-finite rotation
   pub fn validate(&self) -> TakeoffResult<()> {
     match self {
       Measurement::Polygon { points, .. } => {
+        let points = open_ring(points);
         if points.len() < 3 {
           return Err(TakeoffError::empty_geometry(format!(
             "polygon must have at least 3 points, got {}",
@@ -62,13 +124,20 @@ impl Measurement {
         }
         Ok(())
       }
-      Measurement::Rectangle { points, .. } => {
+      Measurement::Rectangle {
+        points, rotation, ..
+      } => {
         let (p1, p2) = points;
         if (p1.x - p2.x).abs() < f64::EPSILON && (p1.y - p2.y).abs() < f64::EPSILON {
           return Err(TakeoffError::empty_geometry(
             "rectangle corners must be distinct points",
           ));
         }
+        if rotation.is_some_and(|angle| !angle.is_finite()) {
+          return Err(TakeoffError::empty_geometry(
+            "rectangle rotation must be finite",
+          ));
+        }
         Ok(())
       }
       Measurement::Count { .. } => Ok(()), // Count always has valid geometry (single point)
@@ -103,6 +172,66 @@ impl Measurement {
     }
   }
 
+  /// Get the metadata (arbitrary key/value labels such as cost code or phase) of the measurement
+  pub fn metadata(&self) -> Option<&HashMap<String, String>> {
+    match self {
+      Measurement::Count { metadata, .. } => metadata.as_ref(),
+      Measurement::Polygon { metadata, .. } => metadata.as_ref(),
+      Measurement::Polyline { metadata, .. } => metadata.as_ref(),
+      Measurement::Rectangle { metadata, .. } => metadata.as_ref(),
+    }
+  }
+
+  /// Replace the metadata of the measurement
+  pub fn set_metadata(&mut self, value: Option<HashMap<String, String>>) {
+    match self {
+      Measurement::Count { metadata, .. } => *metadata = value,
+      Measurement::Polygon { metadata, .. } => *metadata = value,
+      Measurement::Polyline { metadata, .. } => *metadata = value,
+      Measurement::Rectangle { metadata, .. } => *metadata = value,
+    }
+  }
+
+  /// Move the measurement to another group
+  pub fn set_group_id(&mut self, value: String) {
+    match self {
+      Measurement::Count { group_id, .. } => *group_id = value,
+      Measurement::Polygon { group_id, .. } => *group_id = value,
+      Measurement::Polyline { group_id, .. } => *group_id = value,
+      Measurement::Rectangle { group_id, .. } => *group_id = value,
+    }
+  }
+
+  /// The kind of quantity the measurement takes off, matching the group types.
+  ///
+  /// Polygons and rectangles are areas, polylines are linear and counts are counts.
+  pub fn kind(&self) -> MeasurementType {
+    match self {
+      Measurement::Polygon { .. } | Measurement::Rectangle { .. } => MeasurementType::Area,
+      Measurement::Polyline { .. } => MeasurementType::Linear,
+      Measurement::Count { .. } => MeasurementType::Count,
+    }
+  }
+
+  /// Whether the measurement encloses an area (polygons and rectangles).
+  pub fn is_areal(&self) -> bool {
+    self.kind() == MeasurementType::Area
+  }
+
+  /// Whether the measurement is taken off as a length: polylines, and rectangles, which are
+  /// often drawn to take off a perimeter.
+  pub fn is_linear(&self) -> bool {
+    matches!(
+      self,
+      Measurement::Polyline { .. } | Measurement::Rectangle { .. }
+    )
+  }
+
+  /// Whether the measurement is a count.
+  pub fn is_count(&self) -> bool {
+    self.kind() == MeasurementType::Count
+  }
+
   /// Convert the measurement to a polygon.
   ///
   /// # Errors
@@ -114,15 +243,28 @@ impl Measurement {
     self.validate()?;
     match self {
       Measurement::Polygon { points, .. } => {
-        let points: Vec<Coord<f64>> = points.iter().map(|p| (*p).into()).collect();
+        let points: Vec<Coord<f64>> = open_ring(points).iter().map(|p| (*p).into()).collect();
         Ok(GeoPolygon::new(LineString::from(points), vec![]))
       }
-      Measurement::Rectangle { points, .. } => {
+      Measurement::Rectangle {
+        points,
+        rotation: None,
+        ..
+      } => {
         let start: Coord<f64> = points.0.into();
         let end: Coord<f64> = points.1.into();
         let rect = Rect::new(start, end);
         Ok(rect.to_polygon())
       }
+      Measurement::Rectangle {
+        points, rotation, ..
+      } => {
+        let corners: Vec<Coord<f64>> = rectangle_corners(*points, *rotation)
+          .into_iter()
+          .map(Coord::from)
+          .collect();
+        Ok(GeoPolygon::new(LineString::from(corners), vec![]))
+      }
       _ => Err(TakeoffError::empty_geometry(
         "measurement cannot be converted to polygon",
       )),
@@ -239,45 +381,581 @@ impl Measurement {
         page_id,
         group_id,
         points: (_,),
+        metadata,
       } => Measurement::Count {
         id,
         page_id,
         group_id,
         points: (new_centroid,),
+        metadata,
       },
       Measurement::Polygon {
         id,
         page_id,
         group_id,
         points,
+        metadata,
       } => Measurement::Polygon {
         id,
         page_id,
         group_id,
         points: points.into_iter().map(translate).collect(),
+        metadata,
       },
       Measurement::Polyline {
         id,
         page_id,
         group_id,
         points,
+        metadata,
       } => Measurement::Polyline {
         id,
         page_id,
         group_id,
         points: points.into_iter().map(translate).collect(),
+        metadata,
       },
       Measurement::Rectangle {
         id,
         page_id,
         group_id,
         points: (p1, p2),
+        rotation,
+        metadata,
       } => Measurement::Rectangle {
         id,
         page_id,
         group_id,
         points: (translate(p1), translate(p2)),
+        rotation,
+        metadata,
+      },
+    })
+  }
+
+  /// Returns a copy of the measurement with its points replaced, keeping kind, ids, metadata
+  /// and rectangle rotation.
+  ///
+  /// Counts take exactly one point and rectangles exactly two corners.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::EmptyGeometry`] if the point count does not fit the kind or the
+  /// new geometry is invalid.
+  pub fn with_points(&self, new_points: Vec<Point>) -> TakeoffResult<Measurement> {
+    let mut measurement = self.clone();
+    let wrong_count = |kind: &str, expected: &str| {
+      TakeoffError::empty_geometry(format!(
+        "{kind} takes exactly {expected}, got {}",
+        new_points.len()
+      ))
+    };
+    match &mut measurement {
+      Measurement::Count { points, .. } => match new_points[..] {
+        [p] => *points = (p,),
+        _ => return Err(wrong_count("count", "1 point")),
+      },
+      Measurement::Rectangle { points, .. } => match new_points[..] {
+        [a, b] => *points = (a, b),
+        _ => return Err(wrong_count("rectangle", "2 corners")),
+      },
+      Measurement::Polygon { points, .. } | Measurement::Polyline { points, .. } => {
+        *points = new_points;
+      }
+    }
+    measurement.validate()?;
+    Ok(measurement)
+  }
+
+  /// Returns this measurement as a polygon: a polyline is closed, a rectangle expands to its
+  /// four (rotated) corners and a polygon is copied.
+  ///
+  /// Page, group and metadata are kept; the id is kept unless `new_id` is given.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::EmptyGeometry`] for counts, or if the result is not a valid
+  /// polygon (e.g. a polyline with fewer than 3 points).
+  pub fn as_polygon(&self, new_id: Option<String>) -> TakeoffResult<Measurement> {
+    let points = match self {
+      Measurement::Count { .. } => {
+        return Err(TakeoffError::empty_geometry(
+          "a count cannot be converted to a polygon",
+        ));
+      }
+      Measurement::Polyline { points, .. } => open_ring(points).to_vec(),
+      _ => self.all_points(),
+    };
+    let polygon = Measurement::Polygon {
+      id: new_id.unwrap_or_else(|| self.id().to_string()),
+      page_id: self.page_id().to_string(),
+      group_id: self.group_id().to_string(),
+      points,
+      metadata: self.metadata().cloned(),
+    };
+    polygon.validate()?;
+    Ok(polygon)
+  }
+
+  /// Returns this measurement as a polyline: the outline of a polygon or rectangle, closed by
+  /// repeating its first vertex so the length equals the perimeter. A polyline is copied.
+  ///
+  /// Page, group and metadata are kept; the id is kept unless `new_id` is given.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::EmptyGeometry`] for counts, or if the result is not a valid
+  /// polyline.
+  pub fn as_polyline(&self, new_id: Option<String>) -> TakeoffResult<Measurement> {
+    let points = match self {
+      Measurement::Count { .. } => {
+        return Err(TakeoffError::empty_geometry(
+          "a count cannot be converted to a polyline",
+        ));
+      }
+      Measurement::Polyline { points, .. } => points.clone(),
+      _ => {
+        let mut ring = self.all_points();
+        ring.extend(ring.first().copied());
+        ring
+      }
+    };
+    let polyline = Measurement::Polyline {
+      id: new_id.unwrap_or_else(|| self.id().to_string()),
+      page_id: self.page_id().to_string(),
+      group_id: self.group_id().to_string(),
+      points,
+      metadata: self.metadata().cloned(),
+    };
+    polyline.validate()?;
+    Ok(polyline)
+  }
+
+  /// The editable vertices of a polygon (open ring) or polyline.
+  fn editable_points(&self) -> TakeoffResult<Vec<Point>> {
+    match self {
+      Measurement::Polygon { points, .. } => Ok(open_ring(points).to_vec()),
+      Measurement::Polyline { points, .. } => Ok(points.clone()),
+      _ => Err(TakeoffError::empty_geometry(
+        "only polygon and polyline vertices can be edited",
+      )),
+    }
+  }
+
+  /// Returns a copy of a polygon or polyline with `point` inserted before the vertex at `index`
+  /// (`index` equal to the vertex count appends).
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::EmptyGeometry`] for other kinds, an out-of-range index or invalid
+  /// resulting geometry.
+  pub fn insert_vertex(&self, index: usize, point: Point) -> TakeoffResult<Measurement> {
+    let mut points = self.editable_points()?;
+    if index > points.len() {
+      return Err(TakeoffError::empty_geometry(format!(
+        "vertex index {index} is out of range (0..={})",
+        points.len()
+      )));
+    }
+    points.insert(index, point);
+    self.with_points(points)
+  }
+
+  /// Returns a copy of a polygon or polyline without the vertex at `index`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::EmptyGeometry`] for other kinds, an out-of-range index, or if the
+  /// polygon would drop below 3 points or the polyline below 2.
+  pub fn remove_vertex(&self, index: usize) -> TakeoffResult<Measurement> {
+    let mut points = self.editable_points()?;
+    if index >= points.len() {
+      return Err(TakeoffError::empty_geometry(format!(
+        "vertex index {index} is out of range (0..{})",
+        points.len()
+      )));
+    }
+    points.remove(index);
+    self.with_points(points)
+  }
+
+  /// Calculate the diagonal length in pixels, e.g. for bracing drawn as a rectangle.
+  ///
+  /// For rectangles this is the distance between the two stored corners (rotation does not
+  /// change it). For polygons it is the longest distance between two non-adjacent vertices.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::EmptyGeometry`] if the geometry is invalid, the measurement is a
+  /// polyline or count, or the polygon is a triangle (which has no diagonals).
+  pub fn diagonal_length(&self) -> TakeoffResult<f64> {
+    self.validate()?;
+    match self {
+      Measurement::Rectangle { points, .. } => Ok(points.0.distance_to(&points.1)),
+      Measurement::Polygon { points, .. } => {
+        let points = open_ring(points);
+        let n = points.len();
+        (0..n)
+          .flat_map(|i| {
+            (i + 2..n)
+              .filter(move |&j| !(i == 0 && j == n - 1))
+              .map(move |j| (i, j))
+          })
+          .map(|(i, j)| points[i].distance_to(&points[j]))
+          .reduce(f64::max)
+          .ok_or_else(|| TakeoffError::empty_geometry("a triangle has no diagonals"))
+      }
+      _ => Err(TakeoffError::empty_geometry(
+        "only rectangles and polygons have a diagonal",
+      )),
+    }
+  }
+
+  /// Sum of both corner-to-corner diagonals in pixels, e.g. for the cross bracing of a bay.
+  ///
+  /// Rectangles yield twice their diagonal; four-vertex polygons the two diagonals between
+  /// opposite vertices.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::EmptyGeometry`] if the geometry is invalid or the measurement is
+  /// not a rectangle or a four-vertex polygon.
+  pub fn sum_of_diagonals(&self) -> TakeoffResult<f64> {
+    self.validate()?;
+    match self {
+      Measurement::Rectangle { points, .. } => Ok(2.0 * points.0.distance_to(&points.1)),
+      Measurement::Polygon { points, .. } => match open_ring(points) {
+        [a, b, c, d] => Ok(a.distance_to(c) + b.distance_to(d)),
+        _ => Err(TakeoffError::empty_geometry(
+          "only four-vertex polygons have a pair of diagonals",
+        )),
+      },
+      _ => Err(TakeoffError::empty_geometry(
+        "only rectangles and quadrilaterals have a pair of diagonals",
+      )),
+    }
+  }
+
+  /// Smallest circle enclosing the measurement, as `(center, radius)` in pixels.
+  ///
+  /// Uses the iterative form of Welzl's algorithm over the measurement's vertices (rectangle
+  /// corners include rotation). A count yields its point with radius 0.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::EmptyGeometry`] if the geometry is invalid.
+  pub fn min_enclosing_circle(&self) -> TakeoffResult<(Point, f64)> {
+    self.validate()?;
+    let points = self.all_points();
+    let contains = |(center, radius): (Point, f64), p: &Point| {
+      center.distance_to(p) <= radius + Self::GEOMETRY_EPSILON
+    };
+    let diameter = |a: Point, b: Point| {
+      let center = Point::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0);
+      (center, center.distance_to(&a))
+    };
+
+    let mut circle = (points[0], 0.0);
+    for i in 1..points.len() {
+      if contains(circle, &points[i]) {
+        continue;
+      }
+      circle = (points[i], 0.0);
+      for j in 0..i {
+        if contains(circle, &points[j]) {
+          continue;
+        }
+        circle = diameter(points[i], points[j]);
+        for k in 0..j {
+          if !contains(circle, &points[k]) {
+            circle = circumcircle(points[i], points[j], points[k]);
+          }
+        }
+      }
+    }
+    Ok(circle)
+  }
+
+  /// Edge lengths, aspect ratio and compactness, e.g. to warn about a "room" traced as a
+  /// one-pixel-wide sliver.
+  ///
+  /// The aspect ratio comes from the minimum-area rectangle enclosing the vertices (found by
+  /// trying each convex hull edge direction), so rotated slivers are caught too.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::EmptyGeometry`] if the geometry is invalid or the measurement is a
+  /// count, which has no edges.
+  pub fn shape_stats(&self) -> TakeoffResult<ShapeStats> {
+    self.validate()?;
+    let points = self.all_points();
+    if self.is_count() {
+      return Err(TakeoffError::empty_geometry("a count has no edges"));
+    }
+    let closed = self.is_areal();
+    let edge_count = if closed {
+      points.len()
+    } else {
+      points.len() - 1
+    };
+    let edges: Vec<f64> = (0..edge_count)
+      .map(|i| points[i].distance_to(&points[(i + 1) % points.len()]))
+      .collect();
+    let perimeter: f64 = edges.iter().sum();
+    let area = if closed { self.pixel_area()? } else { 0.0 };
+    let compactness = if perimeter > 0.0 {
+      4.0 * std::f64::consts::PI * area / (perimeter * perimeter)
+    } else {
+      0.0
+    };
+
+    let hull =
+      MultiPoint::from(points.iter().map(|p| Coord::from(*p)).collect::<Vec<_>>()).convex_hull();
+    let hull: Vec<Coord> = hull.exterior().0.clone();
+    let (long, short) = hull
+      .windows(2)
+      .filter_map(|edge| {
+        let (dx, dy) = (edge[1].x - edge[0].x, edge[1].y - edge[0].y);
+        let norm = dx.hypot(dy);
+        (norm > 0.0).then(|| (dx / norm, dy / norm))
+      })
+      .map(|(ux, uy)| {
+        let extent = |project: &dyn Fn(&Coord) -> f64| {
+          let (min, max) = hull
+            .iter()
+            .map(project)
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+              (lo.min(v), hi.max(v))
+            });
+          max - min
+        };
+        let along = extent(&|c| c.x * ux + c.y * uy);
+        let across = extent(&|c| c.y * ux - c.x * uy);
+        (along.max(across), along.min(across))
+      })
+      .min_by(|a, b| (a.0 * a.1).total_cmp(&(b.0 * b.1)))
+      .unwrap_or((0.0, 0.0));
+    let aspect_ratio = if short > Self::GEOMETRY_EPSILON {
+      long / short
+    } else if long > 0.0 {
+      f64::INFINITY
+    } else {
+      1.0
+    };
+
+    Ok(ShapeStats {
+      min_edge: edges.iter().copied().fold(f64::INFINITY, f64::min),
+      max_edge: edges.iter().copied().fold(0.0, f64::max),
+      edge_count: edge_count as u32,
+      aspect_ratio,
+      compactness,
+    })
+  }
+
+  /// Offset a polygon or rectangle outward (positive `distance`) or inward (negative), in
+  /// pixels, e.g. from a room's inside face to the outside of its walls.
+  ///
+  /// Every edge moves by `distance` along its normal and neighbouring edges are extended to
+  /// meet (mitered joins), so corners stay sharp. The result is a counter-clockwise polygon
+  /// keeping the id, page, group and metadata. Repeated consecutive vertices are dropped
+  /// first, since a zero-length edge has no normal.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::EmptyGeometry`] if the geometry is invalid, the measurement is a
+  /// polyline or count, or an inward offset is so large that the ring collapses or
+  /// self-intersects, and [`TakeoffError::InvalidInput`] if fewer than 3 distinct vertices
+  /// remain.
+  pub fn offset_polygon(&self, distance: f64) -> TakeoffResult<Measurement> {
+    let (id, page_id, group_id, metadata) = match self {
+      Measurement::Polygon {
+        id,
+        page_id,
+        group_id,
+        metadata,
+        ..
+      }
+      | Measurement::Rectangle {
+        id,
+        page_id,
+        group_id,
+        metadata,
+        ..
+      } => (id, page_id, group_id, metadata),
+      _ => {
+        return Err(TakeoffError::empty_geometry(
+          "only polygons and rectangles can be offset",
+        ));
+      }
+    };
+    let mut ring: Vec<Point> = self.all_points();
+    if self.pixel_signed_area()? < 0.0 {
+      ring.reverse();
+    }
+    ring.dedup_by(|b, a| Self::points_close(a, b));
+    while ring.len() > 1 && Self::points_close(&ring[0], &ring[ring.len() - 1]) {
+      ring.pop();
+    }
+    if ring.len() < 3 {
+      return Err(TakeoffError::invalid_input(
+        "a polygon needs at least 3 distinct vertices to be offset",
+      ));
+    }
+
+    let n = ring.len();
+    // Outward unit normal of the edge from `ring[i]` to `ring[i + 1]` on a counter-clockwise ring.
+    let normal = |i: usize| {
+      let (a, b) = (ring[i], ring[(i + 1) % n]);
+      let length = a.distance_to(&b);
+      ((b.y - a.y) / length, (a.x - b.x) / length)
+    };
+    let mut offset = Vec::with_capacity(n);
+    for (i, vertex) in ring.iter().enumerate() {
+      let (n1, n2) = (normal((i + n - 1) % n), normal(i));
+      let denominator = 1.0 + n1.0 * n2.0 + n1.1 * n2.1;
+      if denominator.abs() < f64::EPSILON {
+        return Err(TakeoffError::empty_geometry(
+          "cannot offset a polygon that doubles back on itself",
+        ));
+      }
+      let scale = distance / denominator;
+      offset.push(Point::new(
+        vertex.x + (n1.0 + n2.0) * scale,
+        vertex.y + (n1.1 + n2.1) * scale,
+      ));
+    }
+
+    // A collapsing offset flips edges around or makes them cross.
+    let edge = |points: &[Point], i: usize| {
+      Line::new(Coord::from(points[i]), Coord::from(points[(i + 1) % n]))
+    };
+    let flipped = (0..n).any(|i| {
+      let (before, after) = (edge(&ring, i), edge(&offset, i));
+      before.dx() * after.dx() + before.dy() * after.dy() <= 0.0
+    });
+    let crossing = (0..n).any(|i| {
+      (i + 2..n)
+        .filter(|&j| !(i == 0 && j == n - 1))
+        .any(|j| edge(&offset, i).intersects(&edge(&offset, j)))
+    });
+    if flipped || crossing {
+      return Err(TakeoffError::empty_geometry(format!(
+        "offsetting by {distance} collapses the polygon"
+      )));
+    }
+
+    Ok(Measurement::Polygon {
+      id: id.clone(),
+      page_id: page_id.clone(),
+      group_id: group_id.clone(),
+      points: offset,
+      metadata: metadata.clone(),
+    })
+  }
+
+  /// Split a polyline into two polylines that share the vertex at `index`.
+  ///
+  /// The halves keep the page, group and metadata of the original and take the ids in
+  /// `new_ids`. Their lengths add up to the original length.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::EmptyGeometry`] if the measurement is not a valid polyline or
+  /// `index` is not an interior vertex (either half would have fewer than 2 points).
+  pub fn split_polyline_at(
+    &self,
+    index: usize,
+    new_ids: (String, String),
+  ) -> TakeoffResult<(Measurement, Measurement)> {
+    self.validate()?;
+    let Measurement::Polyline {
+      page_id,
+      group_id,
+      points,
+      metadata,
+      ..
+    } = self
+    else {
+      return Err(TakeoffError::empty_geometry("only polylines can be split"));
+    };
+    if index == 0 || index >= points.len() - 1 {
+      return Err(TakeoffError::empty_geometry(format!(
+        "split index must be an interior vertex (1..{}), got {index}",
+        points.len() - 1
+      )));
+    }
+    let half = |id: String, points: &[Point]| Measurement::Polyline {
+      id,
+      page_id: page_id.clone(),
+      group_id: group_id.clone(),
+      points: points.to_vec(),
+      metadata: metadata.clone(),
+    };
+    Ok((
+      half(new_ids.0, &points[..=index]),
+      half(new_ids.1, &points[index..]),
+    ))
+  }
+
+  /// Join two polylines whose endpoints meet into one continuous polyline.
+  ///
+  /// The endpoints are matched in order end-to-start, end-to-end, start-to-end, start-to-start,
+  /// reversing a polyline where needed; the first pair within `tolerance` (pixels) wins and its
+  /// vertex appears once in the result, at `self`'s position. The result keeps `self`'s page,
+  /// group and metadata.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::EmptyGeometry`] if either measurement is not a valid polyline or
+  /// no endpoints meet within `tolerance`.
+  pub fn join_polyline(
+    &self,
+    other: &Measurement,
+    tolerance: f64,
+    new_id: String,
+  ) -> TakeoffResult<Measurement> {
+    self.validate()?;
+    other.validate()?;
+    let (
+      Measurement::Polyline {
+        page_id,
+        group_id,
+        points: a,
+        metadata,
+        ..
       },
+      Measurement::Polyline { points: b, .. },
+    ) = (self, other)
+    else {
+      return Err(TakeoffError::empty_geometry("only polylines can be joined"));
+    };
+
+    let meets = |p: &Point, q: &Point| p.distance_to(q) <= tolerance;
+    let (a_first, a_last) = (&a[0], &a[a.len() - 1]);
+    let (b_first, b_last) = (&b[0], &b[b.len() - 1]);
+    let points: Vec<Point> = if meets(a_last, b_first) {
+      a.iter().chain(&b[1..]).copied().collect()
+    } else if meets(a_last, b_last) {
+      a.iter()
+        .chain(b[..b.len() - 1].iter().rev())
+        .copied()
+        .collect()
+    } else if meets(a_first, b_last) {
+      b[..b.len() - 1].iter().chain(a).copied().collect()
+    } else if meets(a_first, b_first) {
+      b[1..].iter().rev().chain(a).copied().collect()
+    } else {
+      return Err(TakeoffError::empty_geometry(format!(
+        "polyline endpoints do not meet within {tolerance}"
+      )));
+    };
+
+    Ok(Measurement::Polyline {
+      id: new_id,
+      page_id: page_id.clone(),
+      group_id: group_id.clone(),
+      points,
+      metadata: metadata.clone(),
     })
   }
 
@@ -289,6 +967,76 @@ impl Measurement {
     Ok(polygon.unsigned_area())
   }
 
+  /// Area on the WGS84 ellipsoid in square meters, for measurements whose points are
+  /// longitude/latitude degrees (x = longitude, y = latitude).
+  ///
+  /// Only meaningful on pages marked [`CoordinateSystem::LonLat`](crate::page::CoordinateSystem);
+  /// pixel measurements keep using the planar [`pixel_area`](Self::pixel_area) and their
+  /// scale. Applying this to pixel coordinates would silently produce nonsense, so points
+  /// outside ±180° longitude or ±90° latitude are rejected.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::EmptyGeometry`] if the geometry is invalid or has no area, and
+  /// [`TakeoffError::InvalidInput`] if a point is not a valid longitude/latitude.
+  pub fn geodesic_area(&self) -> TakeoffResult<f64> {
+    let polygon = self.to_polygon()?;
+    if let Some(point) = polygon
+      .exterior()
+      .coords()
+      .find(|c| !(-180.0..=180.0).contains(&c.x) || !(-90.0..=90.0).contains(&c.y))
+    {
+      return Err(TakeoffError::invalid_input(format!(
+        "({}, {}) is not a longitude/latitude in degrees",
+        point.x, point.y
+      )));
+    }
+    Ok(polygon.geodesic_area_unsigned())
+  }
+
+  /// Calculate the signed area of the polygon
+  ///
+  /// Positive for counter-clockwise exterior rings, negative for clockwise ones.
+  /// Returns an error if the geometry is invalid.
+  pub fn pixel_signed_area(&self) -> TakeoffResult<f64> {
+    let polygon = self.to_polygon()?;
+    Ok(polygon.signed_area())
+  }
+
+  /// Calculate the signed area with the shoelace formula, without going through geo.
+  ///
+  /// Sums `x[i] * y[i + 1] - x[i + 1] * y[i]` over the closed exterior ring and halves it, so
+  /// counter-clockwise rings are positive. Serves as an independent cross-check of
+  /// [`pixel_signed_area`](Self::pixel_signed_area).
+  ///
+  /// Returns an error if the geometry is invalid or has no area (polylines, counts).
+  pub fn shoelace_area(&self) -> TakeoffResult<f64> {
+    let polygon = self.to_polygon()?;
+    let twice_area: f64 = polygon
+      .exterior()
+      .0
+      .windows(2)
+      .map(|edge| edge[0].x * edge[1].y - edge[1].x * edge[0].y)
+      .sum();
+    Ok(twice_area / 2.0)
+  }
+
+  /// Force a consistent ring orientation: exterior rings counter-clockwise.
+  ///
+  /// Only polygons carry a winding; rectangles are always built counter-clockwise and other
+  /// measurements are left untouched. Holes, once supported, should be wound clockwise.
+  pub fn normalize_winding(&mut self) {
+    if let Measurement::Polygon { points, .. } = self {
+      let polygon = GeoPolygon::new(
+        LineString::from(points.iter().map(|p| Coord::from(*p)).collect::<Vec<_>>()),
+        vec![],
+      );
+      if polygon.signed_area() < 0.0 {
+        points.reverse();
+      }
+    }
+  }
+
   /// Calculate the perimeter/length of the measurement
   ///
   /// Returns an error if the geometry is invalid.
@@ -296,6 +1044,7 @@ impl Measurement {
     self.validate()?;
     match self {
       Measurement::Polygon { points, .. } => {
+        let points = open_ring(points);
         let mut perimeter = 0.0;
         for i in 0..points.len() {
           let j = (i + 1) % points.len();
@@ -328,37 +1077,264 @@ impl Measurement {
       Measurement::Count { .. } => Ok(0.0),
     }
   }
-}
 
-#[cfg(test)]
-mod tests {
-  use super::*;
+  /// The vertices of the measurement, in order, whatever the variant.
+  ///
+  /// Counts yield their single point, polygons their open ring (no repeated closing vertex) and
+  /// rectangles their four corners with rotation applied, counter-clockwise.
+  pub fn all_points(&self) -> Vec<Point> {
+    match self {
+      Measurement::Count { points, .. } => vec![points.0],
+      Measurement::Polygon { points, .. } => open_ring(points).to_vec(),
+      Measurement::Polyline { points, .. } => points.clone(),
+      Measurement::Rectangle {
+        points, rotation, ..
+      } => rectangle_corners(*points, *rotation).to_vec(),
+    }
+  }
 
-  const CENTROID_EPSILON: f64 = 1e-10;
+  /// Pixel bounding box `((min_x, min_y), (max_x, max_y))` of the vertices (see
+  /// [`all_points`](Self::all_points)), or `None` if there are none.
+  pub fn bounding_box(&self) -> Option<((f64, f64), (f64, f64))> {
+    let points = self.all_points();
+    let first = points.first()?;
+    Some(points.iter().fold(
+      ((first.x, first.y), (first.x, first.y)),
+      |((min_x, min_y), (max_x, max_y)), p| {
+        (
+          (min_x.min(p.x), min_y.min(p.y)),
+          (max_x.max(p.x), max_y.max(p.y)),
+        )
+      },
+    ))
+  }
 
-  #[test]
-  fn test_with_centroid_at_rectangle() {
-    let m = Measurement::Rectangle {
-      id: "1".to_string(),
-      page_id: "1".to_string(),
-      group_id: "1".to_string(),
-      points: (Point::new(0.0, 0.0), Point::new(100.0, 50.0)),
+  /// Whether every vertex lies on the page, i.e. within `[0, width] x [0, height]` of
+  /// [`Page::dimensions`]. A trace wandering off the page usually means a bad import.
+  ///
+  /// Pages without declared dimensions, and lon/lat pages whose coordinates are not pixels,
+  /// cannot be judged and return `true`.
+  pub fn is_within_page(&self, page: &Page) -> bool {
+    if page.coordinate_system == Some(CoordinateSystem::LonLat) {
+      return true;
+    }
+    let Some((width, height)) = page.dimensions() else {
+      return true;
     };
-    let area_before = m.pixel_area().unwrap();
-    let perimeter_before = m.pixel_perimeter().unwrap();
-    let new_centroid = Point::new(10.0, 20.0);
-    let repositioned = m.with_centroid_at(new_centroid).unwrap();
-    let got = repositioned.get_centroid().unwrap();
-    assert!(
-      (got.x - new_centroid.x).abs() < CENTROID_EPSILON
-        && (got.y - new_centroid.y).abs() < CENTROID_EPSILON,
-      "centroid should equal requested point"
-    );
-    assert_eq!(repositioned.pixel_area().unwrap(), area_before);
-    assert_eq!(repositioned.pixel_perimeter().unwrap(), perimeter_before);
+    self
+      .all_points()
+      .iter()
+      .all(|p| (0.0..=width).contains(&p.x) && (0.0..=height).contains(&p.y))
   }
 
-  #[test]
+  fn points_close(a: &Point, b: &Point) -> bool {
+    (a.x - b.x).abs() <= Self::GEOMETRY_EPSILON && (a.y - b.y).abs() <= Self::GEOMETRY_EPSILON
+  }
+
+  /// Compare the geometry of two measurements, ignoring id, page, group and metadata.
+  ///
+  /// Coordinates within a small epsilon are considered equal, so sub-pixel float noise from
+  /// round trips does not register as an edit.
+  pub fn geometry_eq(&self, other: &Measurement) -> bool {
+    if std::mem::discriminant(self) != std::mem::discriminant(other) {
+      return false;
+    }
+    let (a, b) = (self.all_points(), other.all_points());
+    a.len() == b.len() && a.iter().zip(&b).all(|(p, q)| Self::points_close(p, q))
+  }
+
+  /// Hash of the measurement type and its vertices rounded to [`Self::GEOMETRY_HASH_STEP`]
+  /// (0.01 px), ignoring id, page, group and metadata.
+  ///
+  /// Two measurements with the same vertices in the same order hash equally, so re-imported
+  /// copies can be found cheaply. Points that straddle a rounding boundary may hash
+  /// differently even though they are closer than the step; compare candidates with
+  /// [`Measurement::geometry_eq`] when that matters. The value is only stable within a process.
+  pub fn geometry_hash(&self) -> u64 {
+    let quantize = |value: f64| (value / Self::GEOMETRY_HASH_STEP).round() as i64;
+    let mut hasher = DefaultHasher::new();
+    std::mem::discriminant(self).hash(&mut hasher);
+    for point in self.all_points() {
+      (quantize(point.x), quantize(point.y)).hash(&mut hasher);
+    }
+    hasher.finish()
+  }
+
+  /// Describe how the geometry changed from `self` to `other`.
+  ///
+  /// Returns `None` if the geometry is equal per [`Measurement::geometry_eq`]. Points are
+  /// aligned in order; unmatched points between two aligned ones are reported as moved
+  /// pairwise, and any surplus as added or removed.
+  pub fn diff(&self, other: &Measurement) -> Option<MeasurementDiff> {
+    if self.geometry_eq(other) {
+      return None;
+    }
+    if std::mem::discriminant(self) != std::mem::discriminant(other) {
+      return Some(MeasurementDiff {
+        kind_changed: true,
+        added: vec![],
+        removed: vec![],
+        moved: vec![],
+      });
+    }
+
+    let (old, new) = (self.all_points(), other.all_points());
+    let (n, m) = (old.len(), new.len());
+    // Longest common subsequence of (approximately) unchanged points.
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+      for j in (0..m).rev() {
+        lcs[i][j] = if Self::points_close(&old[i], &new[j]) {
+          lcs[i + 1][j + 1] + 1
+        } else {
+          lcs[i + 1][j].max(lcs[i][j + 1])
+        };
+      }
+    }
+
+    let mut diff = MeasurementDiff {
+      kind_changed: false,
+      added: vec![],
+      removed: vec![],
+      moved: vec![],
+    };
+    let (mut gap_old, mut gap_new) = (Vec::new(), Vec::new());
+    let flush = |diff: &mut MeasurementDiff, gap_old: &mut Vec<usize>, gap_new: &mut Vec<usize>| {
+      let paired = gap_old.len().min(gap_new.len());
+      diff
+        .moved
+        .extend(gap_new[..paired].iter().map(|&j| j as u32));
+      diff
+        .removed
+        .extend(gap_old[paired..].iter().map(|&i| i as u32));
+      diff
+        .added
+        .extend(gap_new[paired..].iter().map(|&j| j as u32));
+      gap_old.clear();
+      gap_new.clear();
+    };
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+      if i < n
+        && j < m
+        && Self::points_close(&old[i], &new[j])
+        && lcs[i][j] == lcs[i + 1][j + 1] + 1
+      {
+        flush(&mut diff, &mut gap_old, &mut gap_new);
+        i += 1;
+        j += 1;
+      } else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+        gap_old.push(i);
+        i += 1;
+      } else {
+        gap_new.push(j);
+        j += 1;
+      }
+    }
+    flush(&mut diff, &mut gap_old, &mut gap_new);
+    Some(diff)
+  }
+}
+
+/// Volume of a constant cross-section swept along a run, e.g. a strip footing or a trench:
+/// `length * profile_area`.
+///
+/// # Errors
+///
+/// Returns [`TakeoffError::InvalidInput`] if `profile_area` is negative or not finite.
+pub fn linear_volume(length: Length, profile_area: AreaQuantity) -> TakeoffResult<Volume> {
+  if !profile_area.value.is_finite() || profile_area.value < 0.0 {
+    return Err(TakeoffError::invalid_input(format!(
+      "profile area must be a non-negative number, got {} m²",
+      profile_area.value
+    )));
+  }
+  Ok(length * profile_area)
+}
+
+/// Circle through three points. Collinear points fall back to the circle on the farthest pair.
+fn circumcircle(a: Point, b: Point, c: Point) -> (Point, f64) {
+  let (bx, by) = (b.x - a.x, b.y - a.y);
+  let (cx, cy) = (c.x - a.x, c.y - a.y);
+  let d = 2.0 * (bx * cy - by * cx);
+  if d.abs() < f64::EPSILON {
+    let (p, q) = [(a, b), (a, c), (b, c)]
+      .into_iter()
+      .max_by(|(p, q), (r, s)| p.distance_to(q).total_cmp(&r.distance_to(s)))
+      .unwrap_or((a, b));
+    let center = Point::new((p.x + q.x) / 2.0, (p.y + q.y) / 2.0);
+    return (center, center.distance_to(&p));
+  }
+  let (b2, c2) = (bx * bx + by * by, cx * cx + cy * cy);
+  let center = Point::new(a.x + (cy * b2 - by * c2) / d, a.y + (bx * c2 - cx * b2) / d);
+  (center, center.distance_to(&a))
+}
+
+/// Strip a duplicated closing vertex so polygon points form an open ring.
+pub(crate) fn open_ring(points: &[Point]) -> &[Point] {
+  match points {
+    [first, rest @ .., last] if !rest.is_empty() && first == last => &points[..points.len() - 1],
+    _ => points,
+  }
+}
+
+/// The four corners of a rectangle measurement, counter-clockwise from its minimum corner
+/// before rotation, turned by `rotation` radians about the center.
+pub(crate) fn rectangle_corners(points: (Point, Point), rotation: Option<f64>) -> [Point; 4] {
+  let (a, b) = points;
+  let (min_x, max_x) = (a.x.min(b.x), a.x.max(b.x));
+  let (min_y, max_y) = (a.y.min(b.y), a.y.max(b.y));
+  let corners = [
+    Point::new(min_x, min_y),
+    Point::new(max_x, min_y),
+    Point::new(max_x, max_y),
+    Point::new(min_x, max_y),
+  ];
+  let Some(angle) = rotation else {
+    return corners;
+  };
+  let center = Point::new((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+  let (sin, cos) = angle.sin_cos();
+  corners.map(|p| {
+    let (dx, dy) = (p.x - center.x, p.y - center.y);
+    Point::new(
+      center.x + dx * cos - dy * sin,
+      center.y + dx * sin + dy * cos,
+    )
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const CENTROID_EPSILON: f64 = 1e-10;
+
+  #[test]
+  fn test_with_centroid_at_rectangle() {
+    let m = Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(100.0, 50.0)),
+      rotation: None,
+      metadata: None,
+    };
+    let area_before = m.pixel_area().unwrap();
+    let perimeter_before = m.pixel_perimeter().unwrap();
+    let new_centroid = Point::new(10.0, 20.0);
+    let repositioned = m.with_centroid_at(new_centroid).unwrap();
+    let got = repositioned.get_centroid().unwrap();
+    assert!(
+      (got.x - new_centroid.x).abs() < CENTROID_EPSILON
+        && (got.y - new_centroid.y).abs() < CENTROID_EPSILON,
+      "centroid should equal requested point"
+    );
+    assert_eq!(repositioned.pixel_area().unwrap(), area_before);
+    assert_eq!(repositioned.pixel_perimeter().unwrap(), perimeter_before);
+  }
+
+  #[test]
   fn test_with_centroid_at_polygon() {
     let m = Measurement::Polygon {
       id: "1".to_string(),
@@ -370,6 +1346,7 @@ mod tests {
         Point::new(10.0, 10.0),
         Point::new(0.0, 10.0),
       ],
+      metadata: None,
     };
     let area_before = m.pixel_area().unwrap();
     let perimeter_before = m.pixel_perimeter().unwrap();
@@ -391,6 +1368,7 @@ mod tests {
       page_id: "1".to_string(),
       group_id: "1".to_string(),
       points: vec![Point::new(0.0, 0.0), Point::new(4.0, 0.0)],
+      metadata: None,
     };
     let length_before = m.pixel_perimeter().unwrap();
     let new_centroid = Point::new(100.0, 200.0);
@@ -410,6 +1388,7 @@ mod tests {
       page_id: "1".to_string(),
       group_id: "1".to_string(),
       points: (Point::new(7.0, 8.0),),
+      metadata: None,
     };
     let new_centroid = Point::new(1.0, 2.0);
     let repositioned = m.with_centroid_at(new_centroid).unwrap();
@@ -433,6 +1412,7 @@ mod tests {
       page_id: "1".to_string(),
       group_id: "1".to_string(),
       points: vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)],
+      metadata: None,
     };
     assert!(matches!(
       m.with_centroid_at(Point::new(0.0, 0.0)),
@@ -447,6 +1427,7 @@ mod tests {
       page_id: "1".to_string(),
       group_id: "1".to_string(),
       points: vec![Point::new(0.0, 0.0)],
+      metadata: None,
     };
     assert!(matches!(
       m.with_centroid_at(Point::new(0.0, 0.0)),
@@ -461,6 +1442,8 @@ mod tests {
       page_id: "1".to_string(),
       group_id: "1".to_string(),
       points: (Point::new(0.0, 0.0), Point::new(0.0, 0.0)),
+      rotation: None,
+      metadata: None,
     };
     assert!(matches!(
       m.with_centroid_at(Point::new(0.0, 0.0)),
@@ -468,6 +1451,235 @@ mod tests {
     ));
   }
 
+  #[test]
+  fn test_normalize_winding_flips_clockwise_polygon() {
+    let mut m = Measurement::Polygon {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![
+        Point::new(0.0, 0.0),
+        Point::new(0.0, 10.0),
+        Point::new(10.0, 10.0),
+        Point::new(10.0, 0.0),
+      ],
+      metadata: None,
+    };
+    assert_eq!(m.pixel_signed_area().unwrap(), -100.0);
+
+    m.normalize_winding();
+    assert_eq!(m.pixel_signed_area().unwrap(), 100.0);
+    assert_eq!(m.pixel_area().unwrap(), 100.0);
+
+    // Already counter-clockwise polygons are unchanged.
+    let before = m.clone();
+    m.normalize_winding();
+    assert_eq!(m, before);
+  }
+
+  #[test]
+  fn test_closed_ring_matches_open_ring() {
+    let open = vec![
+      Point::new(0.0, 0.0),
+      Point::new(10.0, 0.0),
+      Point::new(10.0, 5.0),
+      Point::new(0.0, 5.0),
+    ];
+    let mut closed = open.clone();
+    closed.push(open[0]);
+    let polygon = |points: Vec<Point>| Measurement::Polygon {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points,
+      metadata: None,
+    };
+
+    let (open, closed) = (polygon(open), polygon(closed));
+    assert_eq!(closed.pixel_perimeter().unwrap(), 30.0);
+    assert_eq!(
+      closed.pixel_perimeter().unwrap(),
+      open.pixel_perimeter().unwrap()
+    );
+    assert_eq!(closed.pixel_area().unwrap(), open.pixel_area().unwrap());
+    assert_eq!(closed.to_polygon().unwrap(), open.to_polygon().unwrap());
+
+    // A closed triangle of only two distinct points is not a polygon.
+    let degenerate = polygon(vec![
+      Point::new(0.0, 0.0),
+      Point::new(1.0, 0.0),
+      Point::new(0.0, 0.0),
+    ]);
+    assert!(degenerate.validate().is_err());
+  }
+
+  fn polyline(points: Vec<Point>) -> Measurement {
+    Measurement::Polyline {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points,
+      metadata: None,
+    }
+  }
+
+  #[test]
+  fn test_geometry_eq_ignores_float_noise_and_id() {
+    let a = polyline(vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0)]);
+    let mut b = polyline(vec![Point::new(0.0, 1e-9), Point::new(10.0 + 1e-9, 0.0)]);
+    if let Measurement::Polyline { id, .. } = &mut b {
+      *id = "2".to_string();
+    }
+    assert!(a.geometry_eq(&b));
+    assert_eq!(a.diff(&b), None);
+
+    let moved = polyline(vec![Point::new(0.0, 0.0), Point::new(10.0, 0.5)]);
+    assert!(!a.geometry_eq(&moved));
+  }
+
+  #[test]
+  fn test_as_polygon_and_as_polyline() {
+    let rectangle = Measurement::Rectangle {
+      id: "r".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(4.0, 3.0)),
+      rotation: None,
+      metadata: None,
+    };
+    let polygon = rectangle.as_polygon(None).unwrap();
+    assert_eq!(polygon.id(), "r");
+    assert!(matches!(&polygon, Measurement::Polygon { points, .. } if points.len() == 4));
+    assert!((polygon.to_polygon().unwrap().unsigned_area() - 12.0).abs() < 1e-9);
+
+    let outline = polygon.as_polyline(Some("o".to_string())).unwrap();
+    assert_eq!(outline.id(), "o");
+    assert!((outline.pixel_perimeter().unwrap() - 14.0).abs() < 1e-9);
+
+    let closed = outline.as_polygon(None).unwrap();
+    assert!(matches!(&closed, Measurement::Polygon { points, .. } if points.len() == 4));
+
+    let count = Measurement::Count {
+      id: "c".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0),),
+      metadata: None,
+    };
+    assert!(count.as_polygon(None).is_err());
+    assert!(count.as_polyline(None).is_err());
+    assert!(
+      polyline(vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)])
+        .as_polygon(None)
+        .is_err()
+    );
+  }
+
+  #[test]
+  fn test_geometry_hash() {
+    let a = polyline(vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0)]);
+    let mut b = polyline(vec![Point::new(0.001, 0.0), Point::new(10.0, 0.002)]);
+    if let Measurement::Polyline { id, metadata, .. } = &mut b {
+      *id = "2".to_string();
+      *metadata = Some(HashMap::from([("k".to_string(), "v".to_string())]));
+    }
+    assert_eq!(a.geometry_hash(), b.geometry_hash());
+
+    let moved = polyline(vec![Point::new(0.0, 0.0), Point::new(10.0, 0.05)]);
+    assert_ne!(a.geometry_hash(), moved.geometry_hash());
+
+    let reversed = polyline(vec![Point::new(10.0, 0.0), Point::new(0.0, 0.0)]);
+    assert_ne!(a.geometry_hash(), reversed.geometry_hash());
+
+    // Same vertices as a closed polygon ring, different type.
+    let polygon = Measurement::Polygon {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0)],
+      metadata: None,
+    };
+    assert_ne!(a.geometry_hash(), polygon.geometry_hash());
+  }
+
+  #[test]
+  fn test_diff_reports_added_removed_and_moved_points() {
+    let before = polyline(vec![
+      Point::new(0.0, 0.0),
+      Point::new(5.0, 0.0),
+      Point::new(10.0, 0.0),
+    ]);
+
+    let inserted = polyline(vec![
+      Point::new(0.0, 0.0),
+      Point::new(5.0, 0.0),
+      Point::new(7.0, 1.0),
+      Point::new(10.0, 0.0),
+    ]);
+    let diff = before.diff(&inserted).unwrap();
+    assert_eq!(
+      (diff.added, diff.removed, diff.moved),
+      (vec![2], vec![], vec![])
+    );
+
+    let removed = polyline(vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0)]);
+    let diff = before.diff(&removed).unwrap();
+    assert_eq!(
+      (diff.added, diff.removed, diff.moved),
+      (vec![], vec![1], vec![])
+    );
+
+    let moved = polyline(vec![
+      Point::new(0.0, 0.0),
+      Point::new(5.0, 2.0),
+      Point::new(10.0, 0.0),
+    ]);
+    let diff = before.diff(&moved).unwrap();
+    assert_eq!(
+      (diff.added, diff.removed, diff.moved),
+      (vec![], vec![], vec![1])
+    );
+
+    let count = Measurement::Count {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0),),
+      metadata: None,
+    };
+    assert!(before.diff(&count).unwrap().kind_changed);
+  }
+
+  #[test]
+  fn test_metadata_serde_round_trip() {
+    let mut m = Measurement::Polygon {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![
+        Point::new(0.0, 0.0),
+        Point::new(10.0, 0.0),
+        Point::new(10.0, 10.0),
+      ],
+      metadata: None,
+    };
+    let json = serde_json::to_string(&m).unwrap();
+    assert!(!json.contains("metadata"));
+    assert_eq!(serde_json::from_str::<Measurement>(&json).unwrap(), m);
+
+    m.set_metadata(Some(HashMap::from([(
+      "cost_code".to_string(),
+      "03-300".to_string(),
+    )])));
+    let json = serde_json::to_string(&m).unwrap();
+    let round_trip: Measurement = serde_json::from_str(&json).unwrap();
+    assert_eq!(round_trip, m);
+    assert_eq!(
+      round_trip.metadata().and_then(|m| m.get("cost_code")),
+      Some(&"03-300".to_string())
+    );
+  }
+
   #[test]
   fn test_pixel_area() {
     let measurement = Measurement::Rectangle {
@@ -475,6 +1687,8 @@ mod tests {
       page_id: "1".to_string(),
       group_id: "1".to_string(),
       points: (Point::new(0.0, 0.0), Point::new(100.0, 50.0)),
+      rotation: None,
+      metadata: None,
     };
     assert!(measurement.pixel_area().unwrap() == 5000.0);
   }
@@ -485,10 +1699,693 @@ mod tests {
       page_id: "1".to_string(),
       group_id: "1".to_string(),
       points: (Point::new(0.0, 0.0), Point::new(100.0, 50.0)),
+      rotation: None,
+      metadata: None,
     };
     assert!(measurement.pixel_perimeter().unwrap() == 300.0);
   }
 
+  #[test]
+  fn test_rotated_rectangle() {
+    let measurement = Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(100.0, 50.0)),
+      rotation: Some(std::f64::consts::FRAC_PI_2),
+      metadata: None,
+    };
+    assert!((measurement.pixel_area().unwrap() - 5000.0).abs() < 1e-9);
+    assert!((measurement.pixel_perimeter().unwrap() - 300.0).abs() < 1e-9);
+
+    // A quarter turn about (50, 25) stands the rectangle on its end.
+    let polygon = measurement.to_polygon().unwrap();
+    let expected = [(75.0, -25.0), (75.0, 75.0), (25.0, 75.0), (25.0, -25.0)];
+    for (coord, (x, y)) in polygon.exterior().coords().zip(expected) {
+      assert!((coord.x - x).abs() < 1e-9 && (coord.y - y).abs() < 1e-9);
+    }
+
+    let json = r#"{"Rectangle":{"id":"1","page_id":"1","group_id":"1","points":[{"x":0.0,"y":0.0},{"x":1.0,"y":1.0}]}}"#;
+    let legacy: Measurement = serde_json::from_str(json).unwrap();
+    assert!(matches!(
+      legacy,
+      Measurement::Rectangle { rotation: None, .. }
+    ));
+  }
+
+  #[test]
+  fn test_shoelace_area_matches_geo() {
+    let polygon = |points: Vec<Point>| Measurement::Polygon {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points,
+      metadata: None,
+    };
+    let shapes = [
+      polygon(vec![
+        Point::new(0.0, 0.0),
+        Point::new(4.0, 0.0),
+        Point::new(0.0, 3.0),
+      ]),
+      // Clockwise: negative, like geo's signed area.
+      polygon(vec![
+        Point::new(0.0, 0.0),
+        Point::new(0.0, 2.0),
+        Point::new(2.0, 2.0),
+        Point::new(2.0, 0.0),
+      ]),
+      // Concave L-shape.
+      polygon(vec![
+        Point::new(0.0, 0.0),
+        Point::new(3.0, 0.0),
+        Point::new(3.0, 1.0),
+        Point::new(1.0, 1.0),
+        Point::new(1.0, 3.0),
+        Point::new(0.0, 3.0),
+      ]),
+      Measurement::Rectangle {
+        id: "1".to_string(),
+        page_id: "1".to_string(),
+        group_id: "1".to_string(),
+        points: (Point::new(1.0, 2.0), Point::new(7.5, 4.25)),
+        rotation: Some(0.3),
+        metadata: None,
+      },
+    ];
+    for shape in shapes {
+      let shoelace = shape.shoelace_area().unwrap();
+      assert!((shoelace - shape.pixel_signed_area().unwrap()).abs() < 1e-9);
+      assert!((shoelace.abs() - shape.pixel_area().unwrap()).abs() < 1e-9);
+    }
+  }
+
+  #[test]
+  fn test_diagonal_length() {
+    let rectangle = Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(3.0, 4.0)),
+      rotation: Some(1.0),
+      metadata: None,
+    };
+    assert_eq!(rectangle.diagonal_length().unwrap(), 5.0);
+
+    let polygon = |points: Vec<Point>| Measurement::Polygon {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points,
+      metadata: None,
+    };
+    // The longest vertex distance here is an edge, not a diagonal.
+    let trapezoid = polygon(vec![
+      Point::new(0.0, 0.0),
+      Point::new(10.0, 0.0),
+      Point::new(9.0, 1.0),
+      Point::new(1.0, 1.0),
+    ]);
+    assert!((trapezoid.diagonal_length().unwrap() - 82.0_f64.sqrt()).abs() < 1e-12);
+
+    let triangle = polygon(vec![
+      Point::new(0.0, 0.0),
+      Point::new(1.0, 0.0),
+      Point::new(0.0, 1.0),
+    ]);
+    assert!(triangle.diagonal_length().is_err());
+  }
+
+  #[test]
+  fn test_bounding_box() {
+    let polyline = Measurement::Polyline {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![
+        Point::new(3.0, -1.0),
+        Point::new(-2.0, 4.0),
+        Point::new(5.0, 2.0),
+      ],
+      metadata: None,
+    };
+    assert_eq!(polyline.bounding_box(), Some(((-2.0, -1.0), (5.0, 4.0))));
+    let empty = Measurement::Polyline {
+      id: "2".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![],
+      metadata: None,
+    };
+    assert_eq!(empty.bounding_box(), None);
+  }
+
+  #[test]
+  fn test_is_within_page() {
+    let mut page = Page {
+      id: "1".to_string(),
+      name: None,
+      width: None,
+      height: None,
+      viewport: None,
+      coordinate_system: None,
+    };
+    let line = |end: Point| Measurement::Polyline {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(0.0, 0.0), end],
+      metadata: None,
+    };
+    let inside = line(Point::new(100.0, 50.0));
+    let outside = line(Point::new(100.0, 60.0));
+    assert!(outside.is_within_page(&page));
+
+    page.viewport = Some(crate::page::PageViewport {
+      width: 100.0,
+      height: 50.0,
+    });
+    assert!(inside.is_within_page(&page));
+    assert!(!outside.is_within_page(&page));
+
+    // Declared width/height take precedence over the viewport.
+    (page.width, page.height) = (Some(200.0), Some(200.0));
+    assert!(outside.is_within_page(&page));
+
+    // A rotated rectangle's corners swing off a page its unrotated corners fit on.
+    let rotated = Measurement::Rectangle {
+      id: "2".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(200.0, 200.0)),
+      rotation: Some(std::f64::consts::FRAC_PI_4),
+      metadata: None,
+    };
+    assert!(!rotated.is_within_page(&page));
+  }
+
+  #[test]
+  fn test_sum_of_diagonals() {
+    let rectangle = Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(3.0, 4.0)),
+      rotation: None,
+      metadata: None,
+    };
+    assert_eq!(rectangle.sum_of_diagonals().unwrap(), 10.0);
+
+    let polygon = |points: Vec<Point>| Measurement::Polygon {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points,
+      metadata: None,
+    };
+    // Kite with diagonals 4 (horizontal) and 6 (vertical); a closing vertex is ignored.
+    let kite = polygon(vec![
+      Point::new(0.0, 0.0),
+      Point::new(2.0, -2.0),
+      Point::new(4.0, 0.0),
+      Point::new(2.0, 4.0),
+      Point::new(0.0, 0.0),
+    ]);
+    assert!((kite.sum_of_diagonals().unwrap() - 10.0).abs() < 1e-12);
+
+    let triangle = polygon(vec![
+      Point::new(0.0, 0.0),
+      Point::new(1.0, 0.0),
+      Point::new(0.0, 1.0),
+    ]);
+    assert!(triangle.sum_of_diagonals().is_err());
+  }
+
+  #[test]
+  fn test_min_enclosing_circle() {
+    let square = Measurement::Polygon {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![
+        Point::new(0.0, 0.0),
+        Point::new(2.0, 0.0),
+        Point::new(2.0, 2.0),
+        Point::new(0.0, 2.0),
+      ],
+      metadata: None,
+    };
+    let (center, radius) = square.min_enclosing_circle().unwrap();
+    assert!(center.distance_to(&Point::new(1.0, 1.0)) < 1e-12);
+    assert!((radius - 2.0_f64.sqrt()).abs() < 1e-12);
+
+    // Obtuse triangle: the circle is on the longest side, not the circumcircle.
+    let triangle = Measurement::Polygon {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![
+        Point::new(0.0, 0.0),
+        Point::new(10.0, 0.0),
+        Point::new(5.0, 1.0),
+      ],
+      metadata: None,
+    };
+    let (center, radius) = triangle.min_enclosing_circle().unwrap();
+    assert!(center.distance_to(&Point::new(5.0, 0.0)) < 1e-12);
+    assert!((radius - 5.0).abs() < 1e-12);
+
+    let count = Measurement::Count {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(3.0, 4.0),),
+      metadata: None,
+    };
+    assert_eq!(
+      count.min_enclosing_circle().unwrap(),
+      (Point::new(3.0, 4.0), 0.0)
+    );
+  }
+
+  #[test]
+  fn test_geodesic_area() {
+    // One degree square at the equator: about 12,308 km² (110.6 km by 111.3 km).
+    let square = Measurement::Polygon {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![
+        Point::new(0.0, 0.0),
+        Point::new(1.0, 0.0),
+        Point::new(1.0, 1.0),
+        Point::new(0.0, 1.0),
+      ],
+      metadata: None,
+    };
+    let area = square.geodesic_area().unwrap();
+    assert!((area / 1e6 - 12_308.0).abs() < 10.0);
+
+    // Pixel coordinates are rejected rather than read as degrees.
+    let pixels = Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(400.0, 300.0)),
+      rotation: None,
+      metadata: None,
+    };
+    assert!(matches!(
+      pixels.geodesic_area(),
+      Err(TakeoffError::InvalidInput { .. })
+    ));
+  }
+
+  #[test]
+  fn test_kind_predicates() {
+    let polyline = Measurement::Polyline {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)],
+      metadata: None,
+    };
+    let rectangle = Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(1.0, 1.0)),
+      rotation: None,
+      metadata: None,
+    };
+    let count = Measurement::Count {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0),),
+      metadata: None,
+    };
+
+    assert_eq!(polyline.kind(), MeasurementType::Linear);
+    assert_eq!(rectangle.kind(), MeasurementType::Area);
+    assert_eq!(count.kind(), MeasurementType::Count);
+    assert!(!polyline.is_areal() && polyline.is_linear() && !polyline.is_count());
+    assert!(rectangle.is_areal() && rectangle.is_linear() && !rectangle.is_count());
+    assert!(!count.is_areal() && !count.is_linear() && count.is_count());
+  }
+
+  #[test]
+  fn test_shape_stats() {
+    let square = Measurement::Polygon {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![
+        Point::new(0.0, 0.0),
+        Point::new(2.0, 0.0),
+        Point::new(2.0, 2.0),
+        Point::new(0.0, 2.0),
+      ],
+      metadata: None,
+    };
+    let stats = square.shape_stats().unwrap();
+    assert_eq!(stats.edge_count, 4);
+    assert_eq!((stats.min_edge, stats.max_edge), (2.0, 2.0));
+    assert!((stats.aspect_ratio - 1.0).abs() < 1e-12);
+    assert!((stats.compactness - std::f64::consts::FRAC_PI_4).abs() < 1e-12);
+
+    // A rotated 100 x 1 sliver is caught even though its bounding box is nearly square.
+    let sliver = Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(100.0, 1.0)),
+      rotation: Some(std::f64::consts::FRAC_PI_4),
+      metadata: None,
+    };
+    let stats = sliver.shape_stats().unwrap();
+    assert!((stats.aspect_ratio - 100.0).abs() < 1e-9);
+    assert!((stats.min_edge - 1.0).abs() < 1e-9);
+    assert!(stats.compactness < 0.04);
+
+    let polyline = Measurement::Polyline {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![
+        Point::new(0.0, 0.0),
+        Point::new(3.0, 0.0),
+        Point::new(6.0, 0.0),
+      ],
+      metadata: None,
+    };
+    let stats = polyline.shape_stats().unwrap();
+    assert_eq!(stats.edge_count, 2);
+    assert_eq!(stats.compactness, 0.0);
+    assert_eq!(stats.aspect_ratio, f64::INFINITY);
+
+    let count = Measurement::Count {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(3.0, 4.0),),
+      metadata: None,
+    };
+    assert!(count.shape_stats().is_err());
+  }
+
+  #[test]
+  fn test_offset_polygon() {
+    let rectangle = Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(10.0, 4.0)),
+      rotation: None,
+      metadata: None,
+    };
+    let outside = rectangle.offset_polygon(1.0).unwrap();
+    assert!((outside.pixel_area().unwrap() - 12.0 * 6.0).abs() < 1e-9);
+    let inside = rectangle.offset_polygon(-1.0).unwrap();
+    assert!((inside.pixel_area().unwrap() - 8.0 * 2.0).abs() < 1e-9);
+
+    // Clockwise L-shape: the reflex corner is mitered too.
+    let l_shape = Measurement::Polygon {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![
+        Point::new(0.0, 0.0),
+        Point::new(0.0, 4.0),
+        Point::new(2.0, 4.0),
+        Point::new(2.0, 2.0),
+        Point::new(4.0, 2.0),
+        Point::new(4.0, 0.0),
+      ],
+      metadata: None,
+    };
+    let outside = l_shape.offset_polygon(1.0).unwrap();
+    assert!((outside.pixel_area().unwrap() - (6.0 * 6.0 - 2.0 * 2.0)).abs() < 1e-9);
+    assert!(outside.pixel_signed_area().unwrap() > 0.0);
+
+    assert!(matches!(
+      rectangle.offset_polygon(-2.5),
+      Err(TakeoffError::EmptyGeometry { .. })
+    ));
+
+    // Repeated vertices (including a closing one) would give a zero-length edge.
+    let repeated = Measurement::Polygon {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![
+        Point::new(0.0, 0.0),
+        Point::new(10.0, 0.0),
+        Point::new(10.0, 0.0),
+        Point::new(10.0, 4.0),
+        Point::new(0.0, 4.0),
+        Point::new(0.0, 0.0),
+      ],
+      metadata: None,
+    };
+    let outside = repeated.offset_polygon(1.0).unwrap();
+    assert_eq!(outside.all_points().len(), 4);
+    assert!((outside.pixel_area().unwrap() - 12.0 * 6.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_with_points() {
+    let polygon = Measurement::Polygon {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![
+        Point::new(0.0, 0.0),
+        Point::new(1.0, 0.0),
+        Point::new(1.0, 1.0),
+      ],
+      metadata: None,
+    };
+    let moved = polygon
+      .with_points(vec![
+        Point::new(0.0, 0.0),
+        Point::new(2.0, 0.0),
+        Point::new(2.0, 2.0),
+      ])
+      .unwrap();
+    assert_eq!(moved.id(), "1");
+    assert_eq!(moved.pixel_area().unwrap(), 2.0);
+    assert!(matches!(
+      polygon.with_points(vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)]),
+      Err(TakeoffError::EmptyGeometry { .. })
+    ));
+
+    let rectangle = Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(1.0, 1.0)),
+      rotation: Some(0.5),
+      metadata: None,
+    };
+    assert!(rectangle.with_points(vec![Point::new(0.0, 0.0)]).is_err());
+    assert!(matches!(
+      rectangle.with_points(vec![Point::new(0.0, 0.0), Point::new(3.0, 1.0)]),
+      Ok(Measurement::Rectangle {
+        rotation: Some(_),
+        ..
+      })
+    ));
+  }
+
+  #[test]
+  fn test_insert_and_remove_vertex() {
+    let polygon = Measurement::Polygon {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![
+        Point::new(0.0, 0.0),
+        Point::new(2.0, 0.0),
+        Point::new(2.0, 2.0),
+        Point::new(0.0, 0.0),
+      ],
+      metadata: None,
+    };
+    let square = polygon.insert_vertex(3, Point::new(0.0, 2.0)).unwrap();
+    assert_eq!(square.pixel_area().unwrap(), 4.0);
+    assert_eq!(square.remove_vertex(3).unwrap().pixel_area().unwrap(), 2.0);
+    assert!(polygon.insert_vertex(4, Point::new(0.0, 2.0)).is_err());
+
+    // Removing would leave a polygon with 2 points.
+    assert!(matches!(
+      polygon.remove_vertex(0),
+      Err(TakeoffError::EmptyGeometry { .. })
+    ));
+    assert!(square.remove_vertex(4).is_err());
+
+    let polyline = Measurement::Polyline {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)],
+      metadata: None,
+    };
+    assert!(matches!(
+      polyline.remove_vertex(1),
+      Err(TakeoffError::EmptyGeometry { .. })
+    ));
+
+    let count = Measurement::Count {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0),),
+      metadata: None,
+    };
+    assert!(count.insert_vertex(0, Point::new(1.0, 1.0)).is_err());
+  }
+
+  #[test]
+  fn test_linear_volume() {
+    use uom::si::{area::square_foot, length::foot, volume::cubic_meter};
+
+    // 100 ft of 2 ft x 1 ft footing.
+    let volume = linear_volume(
+      Length::new::<foot>(100.0),
+      AreaQuantity::new::<square_foot>(2.0),
+    )
+    .unwrap();
+    // uom's cubic foot is rounded, so compare in cubic meters (1 ft = 0.3048 m exactly).
+    let expected = 200.0 * 0.3048_f64.powi(3);
+    assert!((volume.get::<cubic_meter>() - expected).abs() <= expected * 1e-12);
+    assert!(matches!(
+      linear_volume(
+        Length::new::<foot>(100.0),
+        AreaQuantity::new::<square_foot>(-1.0)
+      ),
+      Err(TakeoffError::InvalidInput { .. })
+    ));
+  }
+
+  #[test]
+  fn test_all_points() {
+    let count = Measurement::Count {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(1.0, 2.0),),
+      metadata: None,
+    };
+    assert_eq!(count.all_points(), vec![Point::new(1.0, 2.0)]);
+
+    let rectangle = Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(2.0, 1.0), Point::new(0.0, 0.0)),
+      rotation: None,
+      metadata: None,
+    };
+    assert_eq!(
+      rectangle.all_points(),
+      vec![
+        Point::new(0.0, 0.0),
+        Point::new(2.0, 0.0),
+        Point::new(2.0, 1.0),
+        Point::new(0.0, 1.0),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_split_polyline_at() {
+    let polyline = Measurement::Polyline {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![
+        Point::new(0.0, 0.0),
+        Point::new(3.0, 0.0),
+        Point::new(3.0, 4.0),
+        Point::new(6.0, 4.0),
+      ],
+      metadata: None,
+    };
+    let ids = || ("a".to_string(), "b".to_string());
+    let (first, second) = polyline.split_polyline_at(2, ids()).unwrap();
+    assert_eq!(first.id(), "a");
+    assert_eq!(second.id(), "b");
+    assert_eq!(first.pixel_perimeter().unwrap(), 7.0);
+    assert_eq!(second.pixel_perimeter().unwrap(), 3.0);
+
+    assert!(polyline.split_polyline_at(0, ids()).is_err());
+    assert!(polyline.split_polyline_at(3, ids()).is_err());
+    let polygon = Measurement::Polygon {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![
+        Point::new(0.0, 0.0),
+        Point::new(1.0, 0.0),
+        Point::new(1.0, 1.0),
+      ],
+      metadata: None,
+    };
+    assert!(matches!(
+      polygon.split_polyline_at(1, ids()),
+      Err(TakeoffError::EmptyGeometry { .. })
+    ));
+  }
+
+  #[test]
+  fn test_join_polyline() {
+    let line = |points: Vec<Point>| Measurement::Polyline {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points,
+      metadata: None,
+    };
+    let a = line(vec![Point::new(0.0, 0.0), Point::new(2.0, 0.0)]);
+    let points = |m: Measurement| match m {
+      Measurement::Polyline { points, .. } => points,
+      _ => unreachable!(),
+    };
+
+    // End to start, within tolerance.
+    let b = line(vec![Point::new(2.0, 0.01), Point::new(2.0, 3.0)]);
+    let joined = a.join_polyline(&b, 0.1, "j".to_string()).unwrap();
+    assert_eq!(joined.id(), "j");
+    assert_eq!(
+      points(joined),
+      vec![
+        Point::new(0.0, 0.0),
+        Point::new(2.0, 0.0),
+        Point::new(2.0, 3.0)
+      ]
+    );
+
+    // Start to start: `self` is reversed onto the end of `other`.
+    let c = line(vec![Point::new(0.0, 0.0), Point::new(0.0, -1.0)]);
+    let joined = a.join_polyline(&c, 0.1, "j".to_string()).unwrap();
+    assert_eq!(
+      points(joined.clone()),
+      vec![
+        Point::new(0.0, -1.0),
+        Point::new(0.0, 0.0),
+        Point::new(2.0, 0.0)
+      ]
+    );
+    assert_eq!(joined.pixel_perimeter().unwrap(), 3.0);
+
+    let far = line(vec![Point::new(5.0, 5.0), Point::new(6.0, 5.0)]);
+    assert!(matches!(
+      a.join_polyline(&far, 0.1, "j".to_string()),
+      Err(TakeoffError::EmptyGeometry { .. })
+    ));
+  }
+
   #[test]
   fn test_pixel_perimeter_polyline() {
     let measurement = Measurement::Polyline {
@@ -496,6 +2393,7 @@ mod tests {
       page_id: "1".to_string(),
       group_id: "1".to_string(),
       points: vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)],
+      metadata: None,
     };
     assert!(measurement.pixel_perimeter().unwrap() == 1.0);
   }
@@ -507,6 +2405,7 @@ mod tests {
       page_id: "1".to_string(),
       group_id: "1".to_string(),
       points: vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)], // Only 2 points
+      metadata: None,
     };
     assert!(matches!(
       measurement.validate(),
@@ -525,6 +2424,7 @@ mod tests {
       page_id: "1".to_string(),
       group_id: "1".to_string(),
       points: vec![Point::new(0.0, 0.0)], // Only 1 point
+      metadata: None,
     };
     assert!(matches!(
       measurement.validate(),
@@ -539,6 +2439,8 @@ mod tests {
       page_id: "1".to_string(),
       group_id: "1".to_string(),
       points: (Point::new(0.0, 0.0), Point::new(0.0, 0.0)), // Same point
+      rotation: None,
+      metadata: None,
     };
     assert!(matches!(
       measurement.validate(),
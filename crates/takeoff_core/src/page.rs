@@ -8,6 +8,18 @@ pub struct PageViewport {
   pub height: f64,
 }
 
+/// How the coordinates of measurements on a page are to be read.
+#[napi(string_enum)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CoordinateSystem {
+  /// Blueprint pixels, converted to real units with the page's scales (planar math).
+  #[default]
+  Pixels,
+  /// Longitude/latitude degrees (x = longitude, y = latitude) from a georeferenced PDF.
+  /// Areas come from `Measurement::geodesic_area`; pixel scales do not apply.
+  LonLat,
+}
+
 #[napi(object)]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Page {
@@ -16,4 +28,21 @@ pub struct Page {
   pub width: Option<f64>,
   pub height: Option<f64>,
   pub viewport: Option<PageViewport>,
+  /// `None` means [`CoordinateSystem::Pixels`].
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub coordinate_system: Option<CoordinateSystem>,
+}
+
+impl Page {
+  /// The page's width and height: `width`/`height` when both are declared, otherwise the
+  /// viewport's. `None` if the page declares neither.
+  pub fn dimensions(&self) -> Option<(f64, f64)> {
+    match (self.width, self.height) {
+      (Some(width), Some(height)) => Some((width, height)),
+      _ => self
+        .viewport
+        .as_ref()
+        .map(|viewport| (viewport.width, viewport.height)),
+    }
+  }
 }
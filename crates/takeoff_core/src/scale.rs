@@ -1,5 +1,5 @@
 use crate::coords::Point;
-use crate::error::TakeoffError;
+use crate::error::{TakeoffError, TakeoffResult};
 use crate::unit::Unit;
 use geo::Contains;
 use geo::{Coord, Geometry, Polygon as GeoPolygon, Rect};
@@ -18,9 +18,21 @@ impl ScaleDefinition {
   /// Validate that the scale definition has valid values.
   ///
   /// Returns an error if:
-  /// - `pixel_distance` is zero or negative
-  /// - `real_distance` is zero or negative
+  /// - `pixel_distance` is zero, negative or not finite
+  /// - `real_distance` is zero, negative or not finite
   pub fn validate(&self) -> Result<(), TakeoffError> {
+    if !self.pixel_distance.is_finite() {
+      return Err(TakeoffError::invalid_scale(format!(
+        "pixel_distance must be finite, got {}",
+        self.pixel_distance
+      )));
+    }
+    if !self.real_distance.is_finite() {
+      return Err(TakeoffError::invalid_scale(format!(
+        "real_distance must be finite, got {}",
+        self.real_distance
+      )));
+    }
     if self.pixel_distance <= 0.0 {
       return Err(TakeoffError::invalid_scale(format!(
         "pixel_distance must be positive, got {}",
@@ -36,13 +48,23 @@ impl ScaleDefinition {
     Ok(())
   }
 
-  /// Calculate the scale ratio (pixel_distance / real_distance).
+  /// Calculate the scale ratio (pixel_distance / real_distance), i.e. pixels per real unit.
   ///
-  /// Returns an error if the scale is invalid (zero or negative distances).
+  /// This is the single source of truth for the ratio; [`Scale::ratio`] delegates here.
+  /// Returns an error if the scale is invalid (zero, negative or non-finite distances).
   pub fn ratio(&self) -> Result<f64, TakeoffError> {
     self.validate()?;
     Ok(self.pixel_distance / self.real_distance)
   }
+
+  /// Calculate the inverse ratio (real_distance / pixel_distance), i.e. real units per pixel.
+  ///
+  /// Multiply pixel lengths by this to get real lengths. Returns the same errors as
+  /// [`ratio`](Self::ratio).
+  pub fn inverse_ratio(&self) -> Result<f64, TakeoffError> {
+    self.validate()?;
+    Ok(self.real_distance / self.pixel_distance)
+  }
 }
 
 #[napi(discriminant = "type")]
@@ -62,6 +84,9 @@ pub enum Scale {
 }
 
 impl Scale {
+  /// Tolerance used by [`Scale::equivalent_to`] for distances and bounding box coordinates.
+  pub const EQUIVALENCE_EPSILON: f64 = 1e-9;
+
   pub fn id(&self) -> String {
     match self {
       Scale::Area { id, .. } => id.clone(),
@@ -118,6 +143,14 @@ impl Scale {
     }
   }
 
+  /// Calculate the inverse scale ratio (real_distance / pixel_distance).
+  pub fn inverse_ratio(&self) -> Result<f64, TakeoffError> {
+    match self {
+      Scale::Area { scale, .. } => scale.inverse_ratio(),
+      Scale::Default { scale, .. } => scale.inverse_ratio(),
+    }
+  }
+
   /// Validate that the scale has valid values.
   ///
   /// Returns an error if the scale definition is invalid.
@@ -127,6 +160,201 @@ impl Scale {
       Scale::Default { scale, .. } => scale.validate(),
     }
   }
+
+  /// Express the same scale in another unit.
+  ///
+  /// `real_distance` is converted into `unit` and `pixel_distance` is kept, so the number of
+  /// pixels per physical length is unchanged; only the ratio's unit changes.
+  pub fn with_unit(&self, unit: Unit) -> Scale {
+    let mut scale = self.clone();
+    let definition = match &mut scale {
+      Scale::Area { scale, .. } => scale,
+      Scale::Default { scale, .. } => scale,
+    };
+    definition.real_distance = definition.unit.convert(definition.real_distance, &unit);
+    definition.unit = unit;
+    scale
+  }
+
+  /// Whether two scales measure the same way, ignoring `id` and `page_id`.
+  ///
+  /// The kinds and units must match; distances and (for area scales) bounding box corners are
+  /// compared within [`Scale::EQUIVALENCE_EPSILON`].
+  pub fn equivalent_to(&self, other: &Scale) -> bool {
+    let close = |a: f64, b: f64| (a - b).abs() <= Self::EQUIVALENCE_EPSILON;
+    let same_definition = |a: &ScaleDefinition, b: &ScaleDefinition| {
+      a.unit == b.unit
+        && close(a.pixel_distance, b.pixel_distance)
+        && close(a.real_distance, b.real_distance)
+    };
+    let same_point = |a: &Point, b: &Point| close(a.x, b.x) && close(a.y, b.y);
+    match (self, other) {
+      (Scale::Default { scale: a, .. }, Scale::Default { scale: b, .. }) => same_definition(a, b),
+      (
+        Scale::Area {
+          scale: a,
+          bounding_box: box_a,
+          ..
+        },
+        Scale::Area {
+          scale: b,
+          bounding_box: box_b,
+          ..
+        },
+      ) => {
+        same_definition(a, b) && same_point(&box_a.0, &box_b.0) && same_point(&box_a.1, &box_b.1)
+      }
+      _ => false,
+    }
+  }
+
+  /// Convert a pixel distance to a real distance in `unit`, e.g. for a live "340 px = 34 ft"
+  /// calibration preview.
+  ///
+  /// Returns an error if the scale is invalid.
+  pub fn pixels_to_real(&self, pixels: f64, unit: Unit) -> TakeoffResult<f64> {
+    let real = pixels * self.inverse_ratio()?;
+    Ok(self.get_unit().convert(real, &unit))
+  }
+
+  /// Convert a real distance in `unit` to a pixel distance. Inverse of
+  /// [`pixels_to_real`](Self::pixels_to_real).
+  ///
+  /// Returns an error if the scale is invalid.
+  pub fn real_to_pixels(&self, value: f64, unit: Unit) -> TakeoffResult<f64> {
+    let real = unit.convert(value, &self.get_unit());
+    Ok(real * self.ratio()?)
+  }
+
+  /// Pick a round scale-bar length that fits in `max_pixels`, e.g. "50 px = 5 ft".
+  ///
+  /// Returns the largest real length of the form 1, 2 or 5 × 10ⁿ in the scale's unit whose
+  /// pixel width is at most `max_pixels`, together with that width and the unit.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the scale is invalid, and [`TakeoffError::InvalidInput`] if
+  /// `max_pixels` is not a positive finite number.
+  pub fn nice_bar(&self, max_pixels: f64) -> TakeoffResult<(f64, f64, Unit)> {
+    if !max_pixels.is_finite() || max_pixels <= 0.0 {
+      return Err(TakeoffError::invalid_input(format!(
+        "max_pixels must be a positive number, got {max_pixels}"
+      )));
+    }
+    let ratio = self.ratio()?;
+    let max_real = max_pixels / ratio;
+    let magnitude = 10_f64.powi(max_real.log10().floor() as i32);
+    // The slack keeps e.g. a 1000 px budget at exactly 100 ft from rounding down to 50 ft.
+    let real = [5.0, 2.0, 1.0]
+      .into_iter()
+      .map(|step| step * magnitude)
+      .find(|length| *length <= max_real * (1.0 + 1e-9))
+      .unwrap_or(magnitude);
+    Ok((real, (real * ratio).min(max_pixels), self.get_unit()))
+  }
+
+  /// Start building a scale with validation. See [`ScaleBuilder`].
+  pub fn builder() -> ScaleBuilder {
+    ScaleBuilder::default()
+  }
+}
+
+/// Convert a pixel distance to a real distance in `unit` using `scale`.
+/// See [`Scale::pixels_to_real`].
+#[napi]
+pub fn pixels_to_real(scale: Scale, pixels: f64, unit: Unit) -> napi::Result<f64> {
+  Ok(scale.pixels_to_real(pixels, unit)?)
+}
+
+/// Convert a real distance in `unit` to a pixel distance using `scale`.
+/// See [`Scale::real_to_pixels`].
+#[napi]
+pub fn real_to_pixels(scale: Scale, value: f64, unit: Unit) -> napi::Result<f64> {
+  Ok(scale.real_to_pixels(value, unit)?)
+}
+
+/// Round scale-bar length (real length, pixel width, unit) fitting in `max_pixels`.
+/// See [`Scale::nice_bar`].
+#[napi]
+pub fn nice_scale_bar(scale: Scale, max_pixels: f64) -> napi::Result<(f64, f64, Unit)> {
+  Ok(scale.nice_bar(max_pixels)?)
+}
+
+/// Fluent builder for [`Scale`] that validates on [`ScaleBuilder::build`].
+///
+/// Builds a `Scale::Area` when a bounding box is set, otherwise a `Scale::Default`.
+/// A random id is generated when none is given.
+#[derive(Debug, Clone, Default)]
+pub struct ScaleBuilder {
+  id: Option<String>,
+  page_id: Option<String>,
+  pixel_distance: Option<f64>,
+  real_distance: Option<f64>,
+  unit: Option<Unit>,
+  bounding_box: Option<(Point, Point)>,
+}
+
+impl ScaleBuilder {
+  pub fn id(mut self, id: impl Into<String>) -> Self {
+    self.id = Some(id.into());
+    self
+  }
+
+  pub fn page_id(mut self, page_id: impl Into<String>) -> Self {
+    self.page_id = Some(page_id.into());
+    self
+  }
+
+  pub fn pixel_distance(mut self, pixel_distance: f64) -> Self {
+    self.pixel_distance = Some(pixel_distance);
+    self
+  }
+
+  pub fn real_distance(mut self, real_distance: f64) -> Self {
+    self.real_distance = Some(real_distance);
+    self
+  }
+
+  pub fn unit(mut self, unit: Unit) -> Self {
+    self.unit = Some(unit);
+    self
+  }
+
+  /// Restrict the scale to a region of the page, making it a `Scale::Area`.
+  pub fn bounding_box(mut self, start: Point, end: Point) -> Self {
+    self.bounding_box = Some((start, end));
+    self
+  }
+
+  /// Build the scale.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::InvalidScale`] if `page_id`, a distance or the unit is missing,
+  /// or if a distance is not a positive finite number.
+  pub fn build(self) -> TakeoffResult<Scale> {
+    let missing = |field: &str| TakeoffError::invalid_scale(format!("{field} is required"));
+    let page_id = self.page_id.ok_or_else(|| missing("page_id"))?;
+    let scale = ScaleDefinition {
+      pixel_distance: self
+        .pixel_distance
+        .ok_or_else(|| missing("pixel_distance"))?,
+      real_distance: self.real_distance.ok_or_else(|| missing("real_distance"))?,
+      unit: self.unit.ok_or_else(|| missing("unit"))?,
+    };
+    scale.validate()?;
+
+    let id = self.id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    Ok(match self.bounding_box {
+      Some(bounding_box) => Scale::Area {
+        id,
+        page_id,
+        scale,
+        bounding_box,
+      },
+      None => Scale::Default { id, page_id, scale },
+    })
+  }
 }
 
 #[cfg(test)]
@@ -145,6 +373,37 @@ mod tests {
     assert_eq!(scale_def.ratio().unwrap(), 10.0);
   }
 
+  #[test]
+  fn test_inverse_ratio_and_non_finite_distances() {
+    let scale_def = ScaleDefinition {
+      pixel_distance: 100.0,
+      real_distance: 10.0,
+      unit: Unit::Feet,
+    };
+    assert_eq!(scale_def.inverse_ratio().unwrap(), 0.1);
+
+    for (pixel_distance, real_distance) in [
+      (f64::NAN, 10.0),
+      (f64::INFINITY, 10.0),
+      (100.0, f64::NAN),
+      (100.0, f64::INFINITY),
+    ] {
+      let scale_def = ScaleDefinition {
+        pixel_distance,
+        real_distance,
+        unit: Unit::Feet,
+      };
+      assert!(matches!(
+        scale_def.ratio(),
+        Err(TakeoffError::InvalidScale { .. })
+      ));
+      assert!(matches!(
+        scale_def.inverse_ratio(),
+        Err(TakeoffError::InvalidScale { .. })
+      ));
+    }
+  }
+
   #[test]
   fn test_zero_pixel_distance_error() {
     let scale_def = ScaleDefinition {
@@ -219,6 +478,177 @@ mod tests {
     assert_eq!(scale.ratio().unwrap(), 120.0);
   }
 
+  #[test]
+  fn test_equivalent_to_ignores_ids() {
+    let definition = ScaleDefinition {
+      pixel_distance: 10.0,
+      real_distance: 1.0,
+      unit: Unit::Feet,
+    };
+    let a = Scale::Default {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      scale: definition,
+    };
+    let b = Scale::Default {
+      id: "2".to_string(),
+      page_id: "2".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 10.0 + 1e-12,
+        ..definition
+      },
+    };
+    assert!(a.equivalent_to(&b));
+    assert!(!a.equivalent_to(&a.with_unit(Unit::Meters)));
+    assert!(!a.equivalent_to(&Scale::Default {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 12.0,
+        ..definition
+      },
+    }));
+
+    let area = |id: &str, max: f64| Scale::Area {
+      id: id.to_string(),
+      page_id: "1".to_string(),
+      scale: definition,
+      bounding_box: (Point::new(0.0, 0.0), Point::new(max, max)),
+    };
+    assert!(area("1", 10.0).equivalent_to(&area("2", 10.0)));
+    assert!(!area("1", 10.0).equivalent_to(&area("1", 20.0)));
+    assert!(!area("1", 10.0).equivalent_to(&a));
+  }
+
+  #[test]
+  fn test_pixels_to_real_and_back() {
+    // 10 px per foot.
+    let scale = Scale::Default {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 10.0,
+        real_distance: 1.0,
+        unit: Unit::Feet,
+      },
+    };
+    assert!((scale.pixels_to_real(340.0, Unit::Feet).unwrap() - 34.0).abs() < 1e-9);
+    assert!((scale.pixels_to_real(340.0, Unit::Inches).unwrap() - 408.0).abs() < 1e-9);
+    assert!((scale.real_to_pixels(34.0, Unit::Feet).unwrap() - 340.0).abs() < 1e-9);
+    assert!((scale.real_to_pixels(1.0, Unit::Meters).unwrap() - 10.0 / 0.3048).abs() < 1e-9);
+
+    let invalid = Scale::Default {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 0.0,
+        real_distance: 1.0,
+        unit: Unit::Feet,
+      },
+    };
+    assert!(invalid.pixels_to_real(1.0, Unit::Feet).is_err());
+    assert!(pixels_to_real(invalid, 1.0, Unit::Feet).is_err());
+  }
+
+  #[test]
+  fn test_nice_bar() {
+    // 10 px per foot.
+    let scale = Scale::Default {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 10.0,
+        real_distance: 1.0,
+        unit: Unit::Feet,
+      },
+    };
+    // 53 px is 5.3 ft: a round 5 ft bar rather than the full width.
+    assert_eq!(scale.nice_bar(53.0).unwrap(), (5.0, 50.0, Unit::Feet));
+    // 47 px is 4.7 ft, which cannot fit 5 ft.
+    assert_eq!(scale.nice_bar(47.0).unwrap(), (2.0, 20.0, Unit::Feet));
+    assert_eq!(scale.nice_bar(1000.0).unwrap(), (100.0, 1000.0, Unit::Feet));
+    let (real, pixels, _) = scale.nice_bar(3.0).unwrap();
+    assert!((real - 0.2).abs() < 1e-12 && (pixels - 2.0).abs() < 1e-9);
+    assert!(scale.nice_bar(0.0).is_err());
+    assert!(nice_scale_bar(scale, f64::NAN).is_err());
+  }
+
+  #[test]
+  fn test_scale_with_unit_keeps_physical_ratio() {
+    let feet = Scale::Default {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 1.0,
+        real_distance: 100.0,
+        unit: Unit::Feet,
+      },
+    };
+    let meters = feet.with_unit(Unit::Meters);
+    assert_eq!(meters.get_unit(), Unit::Meters);
+    assert_eq!(meters.id(), feet.id());
+
+    // Pixels per meter must match: px/ft divided by meters per foot.
+    let px_per_meter_from_feet = feet.ratio().unwrap() / 0.3048;
+    assert!((meters.ratio().unwrap() - px_per_meter_from_feet).abs() < 1e-12);
+    assert!((meters.with_unit(Unit::Feet).ratio().unwrap() - feet.ratio().unwrap()).abs() < 1e-12);
+  }
+
+  #[test]
+  fn test_scale_builder() {
+    let scale = Scale::builder()
+      .id("1")
+      .page_id("p1")
+      .pixel_distance(120.0)
+      .real_distance(1.0)
+      .unit(Unit::Feet)
+      .build()
+      .unwrap();
+    assert_eq!(
+      scale,
+      Scale::Default {
+        id: "1".to_string(),
+        page_id: "p1".to_string(),
+        scale: ScaleDefinition {
+          pixel_distance: 120.0,
+          real_distance: 1.0,
+          unit: Unit::Feet,
+        },
+      }
+    );
+
+    let area = Scale::builder()
+      .page_id("p1")
+      .pixel_distance(1.0)
+      .real_distance(1.0)
+      .unit(Unit::Meters)
+      .bounding_box(Point::new(0.0, 0.0), Point::new(10.0, 10.0))
+      .build()
+      .unwrap();
+    assert!(matches!(area, Scale::Area { .. }));
+    assert!(!area.id().is_empty());
+  }
+
+  #[test]
+  fn test_scale_builder_errors() {
+    let base = Scale::builder()
+      .page_id("p1")
+      .pixel_distance(100.0)
+      .real_distance(10.0);
+    assert!(matches!(
+      base.clone().build(),
+      Err(TakeoffError::InvalidScale { .. })
+    ));
+    assert!(matches!(
+      base.clone().unit(Unit::Feet).real_distance(0.0).build(),
+      Err(TakeoffError::InvalidScale { .. })
+    ));
+    assert!(matches!(
+      base.unit(Unit::Feet).pixel_distance(f64::INFINITY).build(),
+      Err(TakeoffError::InvalidScale { .. })
+    ));
+  }
+
   #[test]
   fn test_scale_validation() {
     let valid_scale = Scale::Default {
@@ -13,4 +13,17 @@ pub struct StateOptions {
   pub groups: Vec<Group>,
   pub measurements: Vec<Measurement>,
   pub scales: Vec<Scale>,
+  /// Defer computing scales and group totals for these initial values until a getter first
+  /// needs them, memoizing the result.
+  ///
+  /// Cheaper for read-only reports that only touch a few groups, at the cost of the first
+  /// access being slower and computation errors surfacing there instead of at load. Later
+  /// edits are computed eagerly either way. Defaults to eager.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub lazy: Option<bool>,
+  /// Scale for measurements on pages without an applicable scale, e.g. a 1:1 "pixels as
+  /// units" scale for rough checks. Its `page_id` is ignored. Values computed with it are
+  /// uncalibrated estimates; see `MeasurementWrapper::is_using_fallback_scale`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub default_scale: Option<Scale>,
 }
@@ -1,12 +1,21 @@
 use crate::error::TakeoffError;
+use crate::utils::round_sig_figs;
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
-use uom::fmt::DisplayStyle::Abbreviation;
-use uom::si::area::{square_centimeter, square_foot, square_inch, square_meter, square_yard};
-use uom::si::f32::{Area, Length, Volume};
+use std::str::FromStr;
+use uom::si::area::{square_centimeter, square_foot, square_inch, square_meter};
+use uom::si::f64::{Area, Length, Volume};
 use uom::si::length::{centimeter, foot, inch, meter, yard};
-use uom::si::volume::{cubic_centimeter, cubic_foot, cubic_inch, cubic_meter, cubic_yard};
+use uom::si::volume::cubic_meter;
+
+// uom's square yards are rounded to 7 significant figures, so yard areas go through square
+// feet, which are exact (1 ft = 0.3048 m).
+const SQUARE_FEET_PER_SQUARE_YARD: f64 = 9.0;
+
 /// Measurement units supported by the system
+///
+/// Quantities are held as `uom::si::f64` values: `f32` only carries ~7 significant digits,
+/// which is not enough to keep large-site areas (e.g. 100,000 ft²) within a 0.01% tolerance.
 #[napi(string_enum)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Unit {
@@ -20,7 +29,7 @@ pub enum Unit {
 }
 
 impl Unit {
-  pub fn convert_length_to_unit(&self, length: Length) -> f32 {
+  pub fn convert_length_to_unit(&self, length: Length) -> f64 {
     match self {
       Unit::Yards => length.get::<yard>(),
       Unit::Feet => length.get::<foot>(),
@@ -29,7 +38,7 @@ impl Unit {
       Unit::Centimeters => length.get::<centimeter>(),
     }
   }
-  pub fn get_unit(&self, value: f32) -> Length {
+  pub fn get_unit(&self, value: f64) -> Length {
     match self {
       Unit::Yards => Length::new::<yard>(value),
       Unit::Feet => Length::new::<foot>(value),
@@ -39,9 +48,9 @@ impl Unit {
     }
   }
 
-  pub fn convert_area_to_unit(&self, area: Area) -> f32 {
+  pub fn convert_area_to_unit(&self, area: Area) -> f64 {
     match self {
-      Unit::Yards => area.get::<square_yard>(),
+      Unit::Yards => area.get::<square_foot>() / SQUARE_FEET_PER_SQUARE_YARD,
       Unit::Feet => area.get::<square_foot>(),
       Unit::Inches => area.get::<square_inch>(),
       Unit::Meters => area.get::<square_meter>(),
@@ -49,9 +58,9 @@ impl Unit {
     }
   }
 
-  pub fn get_area_unit(&self, value: f32) -> Area {
+  pub fn get_area_unit(&self, value: f64) -> Area {
     match self {
-      Unit::Yards => Area::new::<square_yard>(value),
+      Unit::Yards => Area::new::<square_foot>(value * SQUARE_FEET_PER_SQUARE_YARD),
       Unit::Feet => Area::new::<square_foot>(value),
       Unit::Inches => Area::new::<square_inch>(value),
       Unit::Meters => Area::new::<square_meter>(value),
@@ -59,28 +68,26 @@ impl Unit {
     }
   }
 
-  pub fn convert_volume_to_unit(&self, volume: Volume) -> f32 {
-    match self {
-      Unit::Yards => volume.get::<cubic_yard>(),
-      Unit::Feet => volume.get::<cubic_foot>(),
-      Unit::Inches => volume.get::<cubic_inch>(),
-      Unit::Meters => volume.get::<cubic_meter>(),
-      Unit::Centimeters => volume.get::<cubic_centimeter>(),
-    }
+  /// Cubic meters in one cubic unit, from the exact length of the unit; uom rounds its
+  /// cubic feet, inches and yards to 7 significant figures.
+  fn cubic_meters_per_unit(&self) -> f64 {
+    self.get_unit(1.0).get::<meter>().powi(3)
   }
 
-  pub fn get_volume_unit(&self, value: f32) -> Volume {
-    match self {
-      Unit::Yards => Volume::new::<cubic_yard>(value),
-      Unit::Feet => Volume::new::<cubic_foot>(value),
-      Unit::Inches => Volume::new::<cubic_inch>(value),
-      Unit::Meters => Volume::new::<cubic_meter>(value),
-      Unit::Centimeters => Volume::new::<cubic_centimeter>(value),
-    }
+  pub fn convert_volume_to_unit(&self, volume: Volume) -> f64 {
+    volume.get::<cubic_meter>() / self.cubic_meters_per_unit()
+  }
+
+  pub fn get_volume_unit(&self, value: f64) -> Volume {
+    Volume::new::<cubic_meter>(value * self.cubic_meters_per_unit())
   }
 
   /// Convert a value from one unit to another
-  pub fn convert(&self, value: f32, to: &Unit) -> f32 {
+  pub fn convert(&self, value: f64, to: &Unit) -> f64 {
+    // Same-unit conversions are exact instead of round-tripping through base units.
+    if self == to {
+      return value;
+    }
     let from = self.get_unit(value);
 
     match to {
@@ -92,11 +99,15 @@ impl Unit {
     }
   }
 
-  pub fn convert_area(&self, value: f32, to: &Unit) -> f32 {
+  pub fn convert_area(&self, value: f64, to: &Unit) -> f64 {
+    // Same-unit conversions are exact instead of round-tripping through base units.
+    if self == to {
+      return value;
+    }
     let from = self.get_area_unit(value);
 
     match to {
-      Unit::Yards => from.get::<square_yard>(),
+      Unit::Yards => from.get::<square_foot>() / SQUARE_FEET_PER_SQUARE_YARD,
       Unit::Feet => from.get::<square_foot>(),
       Unit::Inches => from.get::<square_inch>(),
       Unit::Meters => from.get::<square_meter>(),
@@ -104,16 +115,12 @@ impl Unit {
     }
   }
 
-  pub fn convert_volume(&self, value: f32, to: &Unit) -> f32 {
-    let from = self.get_volume_unit(value);
-
-    match to {
-      Unit::Yards => from.get::<cubic_yard>(),
-      Unit::Feet => from.get::<cubic_foot>(),
-      Unit::Inches => from.get::<cubic_inch>(),
-      Unit::Meters => from.get::<cubic_meter>(),
-      Unit::Centimeters => from.get::<cubic_centimeter>(),
+  pub fn convert_volume(&self, value: f64, to: &Unit) -> f64 {
+    // Same-unit conversions are exact instead of round-tripping through base units.
+    if self == to {
+      return value;
     }
+    to.convert_volume_to_unit(self.get_volume_unit(value))
   }
 
   /// Get the display string for this unit
@@ -136,12 +143,16 @@ impl Unit {
       Unit::Centimeters => "Centimeters",
     }
   }
+}
+
+impl FromStr for Unit {
+  type Err = TakeoffError;
 
   /// Parse a unit string into a Unit enum.
   ///
   /// Returns an error if the string is not recognized as a supported unit.
   /// Case-insensitive matching is performed.
-  pub fn from_str(s: &str) -> Result<Unit, TakeoffError> {
+  fn from_str(s: &str) -> Result<Unit, TakeoffError> {
     match s.to_lowercase().as_str() {
       "yards" | "yard" | "yd" => Ok(Unit::Yards),
       "feet" | "foot" | "ft" => Ok(Unit::Feet),
@@ -158,14 +169,14 @@ pub struct UnitUtils;
 
 impl UnitUtils {
   /// Convert a value from one unit to another
-  pub fn convert(value: f32, from: Unit, to: Unit) -> f32 {
+  pub fn convert(value: f64, from: Unit, to: Unit) -> f64 {
     from.convert(value, &to)
   }
-  pub fn convert_area(value: f32, from: Unit, to: Unit) -> f32 {
+  pub fn convert_area(value: f64, from: Unit, to: Unit) -> f64 {
     from.convert_area(value, &to)
   }
 
-  pub fn convert_volume(value: f32, from: Unit, to: Unit) -> f32 {
+  pub fn convert_volume(value: f64, from: Unit, to: Unit) -> f64 {
     from.convert_volume(value, &to)
   }
 
@@ -192,120 +203,76 @@ impl UnitUtils {
 }
 
 pub enum UnitFormatter {
-  Length { unit: Unit, value: f32 },
-  Area { unit: Unit, value: f32 },
-  Volume { unit: Unit, value: f32 },
+  Length { unit: Unit, value: f64 },
+  Area { unit: Unit, value: f64 },
+  Volume { unit: Unit, value: f64 },
 }
 
 impl UnitFormatter {
+  /// Decimal places shown by [`format`](Self::format).
+  const DISPLAY_DECIMALS: usize = 3;
+
+  /// Format the value with its unit symbol, e.g. `"538.196 ft²"`.
+  ///
+  /// The value is rounded to three decimal places and trailing zeros are trimmed, so float
+  /// noise from unit conversions (`0.9999999999999998`) is shown as `1`.
   pub fn format(&self) -> String {
-    match self {
-      UnitFormatter::Area {
-        unit: Unit::Yards,
-        value,
-      } => Unit::Yards
-        .get_area_unit(*value)
-        .into_format_args(square_yard, Abbreviation)
-        .to_string(),
-      UnitFormatter::Area {
-        unit: Unit::Feet,
-        value,
-      } => Unit::Feet
-        .get_area_unit(*value)
-        .into_format_args(square_foot, Abbreviation)
-        .to_string(),
-      UnitFormatter::Area {
-        unit: Unit::Inches,
-        value,
-      } => Unit::Inches
-        .get_area_unit(*value)
-        .into_format_args(square_inch, Abbreviation)
-        .to_string(),
-      UnitFormatter::Area {
-        unit: Unit::Meters,
-        value,
-      } => Unit::Meters
-        .get_area_unit(*value)
-        .into_format_args(square_meter, Abbreviation)
-        .to_string(),
-      UnitFormatter::Area {
-        unit: Unit::Centimeters,
-        value,
-      } => Unit::Centimeters
-        .get_area_unit(*value)
-        .into_format_args(square_centimeter, Abbreviation)
-        .to_string(),
-      UnitFormatter::Length {
-        unit: Unit::Yards,
-        value,
-      } => Unit::Yards
-        .get_unit(*value)
-        .into_format_args(yard, Abbreviation)
-        .to_string(),
-      UnitFormatter::Length {
-        unit: Unit::Feet,
-        value,
-      } => Unit::Feet
-        .get_unit(*value)
-        .into_format_args(foot, Abbreviation)
-        .to_string(),
-      UnitFormatter::Length {
-        unit: Unit::Inches,
-        value,
-      } => Unit::Inches
-        .get_unit(*value)
-        .into_format_args(inch, Abbreviation)
-        .to_string(),
-      UnitFormatter::Length {
-        unit: Unit::Meters,
-        value,
-      } => Unit::Meters
-        .get_unit(*value)
-        .into_format_args(meter, Abbreviation)
-        .to_string(),
-      UnitFormatter::Length {
-        unit: Unit::Centimeters,
-        value,
-      } => Unit::Centimeters
-        .get_unit(*value)
-        .into_format_args(centimeter, Abbreviation)
-        .to_string(),
-      UnitFormatter::Volume {
-        unit: Unit::Yards,
-        value,
-      } => Unit::Yards
-        .get_volume_unit(*value)
-        .into_format_args(cubic_yard, Abbreviation)
-        .to_string(),
-      UnitFormatter::Volume {
-        unit: Unit::Feet,
-        value,
-      } => Unit::Feet
-        .get_volume_unit(*value)
-        .into_format_args(cubic_foot, Abbreviation)
-        .to_string(),
-      UnitFormatter::Volume {
-        unit: Unit::Inches,
-        value,
-      } => Unit::Inches
-        .get_volume_unit(*value)
-        .into_format_args(cubic_inch, Abbreviation)
-        .to_string(),
-      UnitFormatter::Volume {
-        unit: Unit::Meters,
-        value,
-      } => Unit::Meters
-        .get_volume_unit(*value)
-        .into_format_args(cubic_meter, Abbreviation)
-        .to_string(),
-      UnitFormatter::Volume {
-        unit: Unit::Centimeters,
-        value,
-      } => Unit::Centimeters
-        .get_volume_unit(*value)
-        .into_format_args(cubic_centimeter, Abbreviation)
-        .to_string(),
+    let (unit, value, power) = match self {
+      UnitFormatter::Length { unit, value } => (unit, value, ""),
+      UnitFormatter::Area { unit, value } => (unit, value, "²"),
+      UnitFormatter::Volume { unit, value } => (unit, value, "³"),
+    };
+    let fixed = format!("{:.*}", Self::DISPLAY_DECIMALS, value);
+    let trimmed = fixed.trim_end_matches('0').trim_end_matches('.');
+    let number = if trimmed == "-0" { "0" } else { trimmed };
+    format!("{number} {}{power}", unit.display())
+  }
+
+  /// Format a length as architectural feet and inches, e.g. `12'-6 1/16"`.
+  ///
+  /// The length is rounded to the nearest 1/16" first, so inches that round up to 12 carry
+  /// into the feet (`11'-11 31/32"` becomes `12'-0"`). Fractions are reduced (`8/16` is `1/2`).
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::InvalidInput`] for areas and volumes, which have no
+  /// feet-and-inches form.
+  pub fn format_feet_inches(&self) -> Result<String, TakeoffError> {
+    let (unit, value) = match self {
+      UnitFormatter::Length { unit, value } => (unit, *value),
+      UnitFormatter::Area { .. } => {
+        return Err(TakeoffError::invalid_input(
+          "feet-and-inches requires a length, got an area",
+        ));
+      }
+      UnitFormatter::Volume { .. } => {
+        return Err(TakeoffError::invalid_input(
+          "feet-and-inches requires a length, got a volume",
+        ));
+      }
+    };
+
+    const SIXTEENTHS_PER_INCH: i64 = 16;
+    const SIXTEENTHS_PER_FOOT: i64 = 12 * SIXTEENTHS_PER_INCH;
+    let sixteenths =
+      (unit.convert(value, &Unit::Inches) * SIXTEENTHS_PER_INCH as f64).round() as i64;
+    let sign = if sixteenths < 0 { "-" } else { "" };
+    let sixteenths = sixteenths.abs();
+
+    let feet = sixteenths / SIXTEENTHS_PER_FOOT;
+    let inches = sixteenths % SIXTEENTHS_PER_FOOT / SIXTEENTHS_PER_INCH;
+    let mut numerator = sixteenths % SIXTEENTHS_PER_INCH;
+    let mut denominator = SIXTEENTHS_PER_INCH;
+    if numerator == 0 {
+      return Ok(format!("{sign}{feet}'-{inches}\""));
     }
+    while numerator % 2 == 0 {
+      numerator /= 2;
+      denominator /= 2;
+    }
+    Ok(format!(
+      "{sign}{feet}'-{inches} {numerator}/{denominator}\""
+    ))
   }
 }
 
@@ -336,6 +303,10 @@ pub enum UnitValueItemType {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UnitValue {
   value: UnitValueItem,
+  /// Unit to use when the caller does not name one (see `get_value_in`), e.g. a group's
+  /// `unit_override`.
+  #[serde(default)]
+  preferred_unit: Option<Unit>,
 }
 
 #[napi]
@@ -345,18 +316,21 @@ impl UnitValue {
     match magnitude {
       UnitValueItemType::Area => Self {
         value: UnitValueItem::Area {
-          value: unit.get_area_unit(value as f32),
+          value: unit.get_area_unit(value),
         },
+        preferred_unit: None,
       },
       UnitValueItemType::Length => Self {
         value: UnitValueItem::Length {
-          value: unit.get_unit(value as f32),
+          value: unit.get_unit(value),
         },
+        preferred_unit: None,
       },
       UnitValueItemType::Volume => Self {
         value: UnitValueItem::Volume {
-          value: unit.get_volume_unit(value as f32),
+          value: unit.get_volume_unit(value),
         },
+        preferred_unit: None,
       },
     }
   }
@@ -364,19 +338,42 @@ impl UnitValue {
   pub fn from_area(value: Area) -> Self {
     Self {
       value: UnitValueItem::Area { value },
+      preferred_unit: None,
     }
   }
   pub fn from_length(value: Length) -> Self {
     Self {
       value: UnitValueItem::Length { value },
+      preferred_unit: None,
     }
   }
   pub fn from_volume(value: Volume) -> Self {
     Self {
       value: UnitValueItem::Volume { value },
+      preferred_unit: None,
     }
   }
 
+  /// The same value with `unit` as its preferred unit (see `get_value_in`).
+  pub fn with_preferred_unit(mut self, unit: Option<Unit>) -> Self {
+    self.preferred_unit = unit;
+    self
+  }
+
+  /// The unit used by `get_value_in` when no unit is given, if any.
+  #[napi(getter)]
+  pub fn preferred_unit(&self) -> Option<Unit> {
+    self.preferred_unit
+  }
+
+  /// The value in `unit`, or else in the preferred unit.
+  ///
+  /// Returns `None` if neither is set.
+  #[napi]
+  pub fn get_value_in(&self, unit: Option<Unit>) -> Option<f64> {
+    Some(self.get_converted_value(unit.or(self.preferred_unit)?))
+  }
+
   #[napi]
   pub fn display(&self, unit: Unit) -> String {
     match self.value {
@@ -398,13 +395,82 @@ impl UnitValue {
     }
   }
 
+  /// Multiply the value by `factor`, keeping its magnitude, e.g. `1.1` for a 10% waste factor.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::InvalidInput`] if `factor` is negative or not finite.
+  pub fn with_factor(&self, factor: f64) -> Result<UnitValue, TakeoffError> {
+    if !factor.is_finite() || factor < 0.0 {
+      return Err(TakeoffError::invalid_input(format!(
+        "factor must be a non-negative number, got {factor}"
+      )));
+    }
+    let value = match self.value {
+      UnitValueItem::Area { value } => UnitValueItem::Area {
+        value: value * factor,
+      },
+      UnitValueItem::Length { value } => UnitValueItem::Length {
+        value: value * factor,
+      },
+      UnitValueItem::Volume { value } => UnitValueItem::Volume {
+        value: value * factor,
+      },
+    };
+    Ok(Self {
+      value,
+      preferred_unit: self.preferred_unit,
+    })
+  }
+
+  /// Display the value in two units, e.g. `"50 m² (538.196 ft²)"` for reports.
+  ///
+  /// Both parts are formatted like [`display`](Self::display), in the value's own magnitude,
+  /// so an area can never be shown as a length.
+  #[napi]
+  pub fn format_dual(&self, primary: Unit, secondary: Unit) -> String {
+    format!("{} ({})", self.display(primary), self.display(secondary))
+  }
+
   #[napi]
   pub fn get_converted_value(&self, to: Unit) -> f64 {
     match self.value {
-      UnitValueItem::Area { value } => to.convert_area_to_unit(value) as f64,
-      UnitValueItem::Length { value } => to.convert_length_to_unit(value) as f64,
-      UnitValueItem::Volume { value } => to.convert_volume_to_unit(value) as f64,
+      UnitValueItem::Area { value } => to.convert_area_to_unit(value),
+      UnitValueItem::Length { value } => to.convert_length_to_unit(value),
+      UnitValueItem::Volume { value } => to.convert_volume_to_unit(value),
+    }
+  }
+
+  /// The value in `unit`, rounded to `figs` significant figures (see [`round_sig_figs`]).
+  ///
+  /// Rounding happens after conversion, so the figures are those shown in `unit`.
+  #[napi]
+  pub fn to_sig_figs(&self, figs: u32, unit: Unit) -> f64 {
+    round_sig_figs(self.get_converted_value(unit), figs)
+  }
+
+  /// Whether two values are equal up to float noise, e.g. to tell whether a total changed.
+  ///
+  /// Both values are compared in meters and are equal when they differ by at most
+  /// `relative_tolerance` of the larger one, or by an absolute `1e-10` near zero, as in the
+  /// golden accuracy tests. Values of different magnitudes (an area and a length) are never
+  /// equal.
+  #[napi]
+  pub fn approx_eq(&self, other: &UnitValue, relative_tolerance: f64) -> bool {
+    const ABSOLUTE_EPSILON: f64 = 1e-10;
+    let same_magnitude = matches!(
+      (self.value, other.value),
+      (UnitValueItem::Area { .. }, UnitValueItem::Area { .. })
+        | (UnitValueItem::Length { .. }, UnitValueItem::Length { .. })
+        | (UnitValueItem::Volume { .. }, UnitValueItem::Volume { .. })
+    );
+    if !same_magnitude {
+      return false;
     }
+    let a = self.get_converted_value(Unit::Meters);
+    let b = other.get_converted_value(Unit::Meters);
+    let diff = (a - b).abs();
+    diff <= ABSOLUTE_EPSILON || diff <= relative_tolerance * a.abs().max(b.abs())
   }
 }
 
@@ -424,19 +490,78 @@ mod tests {
       value: 1.0,
     };
     assert_eq!(formatter.format(), "1 m²");
+    let formatter = UnitFormatter::Volume {
+      unit: Unit::Feet,
+      value: 2.50049,
+    };
+    assert_eq!(formatter.format(), "2.5 ft³");
+  }
+
+  #[test]
+  fn test_format_feet_inches() {
+    let length = |unit, value| UnitFormatter::Length { unit, value };
+    assert_eq!(
+      length(Unit::Inches, 150.0625).format_feet_inches().unwrap(),
+      "12'-6 1/16\""
+    );
+    assert_eq!(
+      length(Unit::Feet, 12.5).format_feet_inches().unwrap(),
+      "12'-6\""
+    );
+    assert_eq!(
+      length(Unit::Inches, 3.5).format_feet_inches().unwrap(),
+      "0'-3 1/2\""
+    );
+    // Rounds to the nearest 1/16" and carries into the feet.
+    assert_eq!(
+      length(Unit::Inches, 143.99).format_feet_inches().unwrap(),
+      "12'-0\""
+    );
+    assert_eq!(
+      length(Unit::Meters, 1.0).format_feet_inches().unwrap(),
+      "3'-3 3/8\""
+    );
+    assert_eq!(
+      length(Unit::Feet, -1.25).format_feet_inches().unwrap(),
+      "-1'-3\""
+    );
+    assert!(matches!(
+      UnitFormatter::Area {
+        unit: Unit::Feet,
+        value: 1.0
+      }
+      .format_feet_inches(),
+      Err(TakeoffError::InvalidInput { .. })
+    ));
+    assert!(matches!(
+      UnitFormatter::Volume {
+        unit: Unit::Feet,
+        value: 1.0
+      }
+      .format_feet_inches(),
+      Err(TakeoffError::InvalidInput { .. })
+    ));
   }
 
   #[test]
   fn test_convert() {
     let result = Unit::Yards.convert(1.0, &Unit::Feet);
-    println!("result: {}", result);
-    assert_eq!(result, 3.0);
+    assert!((result - 3.0).abs() < 1e-12);
+    assert_eq!(Unit::Feet.convert(100.0, &Unit::Feet), 100.0);
   }
 
   #[test]
   fn test_convert_area() {
     let result = UnitUtils::convert_area(1.0, Unit::Meters, Unit::Feet);
-    assert_eq!(result, 10.76391);
+    assert!((result - 10.763910416709722).abs() < 1e-12);
+  }
+
+  #[test]
+  fn test_large_area_keeps_f64_precision() {
+    // f32 spacing near 1e5 is ~0.008, so this value would not survive an f32 round trip.
+    let area = Unit::Feet.get_area_unit(100_000.123_456);
+    let value = UnitValue::from_area(area).get_converted_value(Unit::Feet);
+    assert!((value - 100_000.123_456).abs() < 1e-6);
   }
 
   #[test]
@@ -455,6 +580,73 @@ mod tests {
     assert_eq!(unit_value.get_converted_value(Unit::Meters), 1.0);
   }
 
+  #[test]
+  fn test_with_factor() {
+    let unit_value = UnitValue::new(100.0, Unit::Feet, UnitValueItemType::Area);
+    let with_waste = unit_value.with_factor(1.1).unwrap();
+    assert!((with_waste.get_converted_value(Unit::Feet) - 110.0).abs() < 1e-9);
+    assert!(matches!(
+      unit_value.with_factor(-0.5),
+      Err(TakeoffError::InvalidInput { .. })
+    ));
+    assert!(unit_value.with_factor(f64::NAN).is_err());
+  }
+
+  #[test]
+  fn test_to_sig_figs() {
+    let area = UnitValue::new(12_345.0, Unit::Feet, UnitValueItemType::Area);
+    assert!((area.to_sig_figs(3, Unit::Feet) - 12_300.0).abs() < 1e-9);
+    assert!((area.to_sig_figs(2, Unit::Meters) - 1_100.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_approx_eq() {
+    let feet = UnitValue::new(1.0, Unit::Feet, UnitValueItemType::Length);
+    let inches = UnitValue::new(12.0 + 1e-9, Unit::Inches, UnitValueItemType::Length);
+    assert!(feet.approx_eq(&inches, 1e-4));
+    assert!(inches.approx_eq(&feet, 1e-4));
+
+    let longer = UnitValue::new(12.1, Unit::Inches, UnitValueItemType::Length);
+    assert!(!feet.approx_eq(&longer, 1e-4));
+    assert!(feet.approx_eq(&longer, 1e-2));
+
+    let area = UnitValue::new(1.0, Unit::Feet, UnitValueItemType::Area);
+    assert!(!feet.approx_eq(&area, 1.0));
+
+    let zero = UnitValue::new(0.0, Unit::Meters, UnitValueItemType::Volume);
+    let noise = UnitValue::new(1e-12, Unit::Meters, UnitValueItemType::Volume);
+    assert!(zero.approx_eq(&noise, 0.0));
+  }
+
+  #[test]
+  fn test_get_value_in() {
+    let unit_value = UnitValue::new(2.0, Unit::Meters, UnitValueItemType::Length);
+    assert_eq!(unit_value.get_value_in(None), None);
+
+    let unit_value = unit_value.with_preferred_unit(Some(Unit::Centimeters));
+    assert_eq!(unit_value.get_value_in(None), Some(200.0));
+    assert_eq!(unit_value.get_value_in(Some(Unit::Meters)), Some(2.0));
+    assert_eq!(
+      unit_value.with_factor(2.0).unwrap().preferred_unit(),
+      Some(Unit::Centimeters)
+    );
+  }
+
+  #[test]
+  fn test_format_dual() {
+    let unit_value = UnitValue::new(1.0, Unit::Yards, UnitValueItemType::Area);
+    assert_eq!(
+      unit_value.format_dual(Unit::Yards, Unit::Feet),
+      "1 yd² (9 ft²)"
+    );
+
+    let unit_value = UnitValue::new(2.0, Unit::Meters, UnitValueItemType::Length);
+    assert_eq!(
+      unit_value.format_dual(Unit::Meters, Unit::Centimeters),
+      "2 m (200 cm)"
+    );
+  }
+
   #[test]
   fn test_unit_from_str() {
     assert_eq!(Unit::from_str("yards").unwrap(), Unit::Yards);
@@ -1,9 +1,10 @@
-use crate::coords::Point;
-use crate::measurement::Measurement;
+use crate::coords::{DistanceTrait, Point};
+use crate::measurement::{Measurement, open_ring, rectangle_corners};
 use geo::LineString;
 use geo::Simplify;
 use napi::bindgen_prelude::Result;
 use napi_derive::napi;
+use std::collections::HashMap;
 
 /// Simplify a polyline using the Ramer-Douglas-Peucker algorithm
 #[napi]
@@ -14,6 +15,330 @@ pub fn simplify_polyline(points: Vec<Point>, tolerance: f64) -> Vec<Point> {
   simplified.into_iter().map(Point::from).collect()
 }
 
+/// Upper bound on the points [`densify_polyline`] inserts into one polyline.
+pub const MAX_DENSIFIED_POINTS: usize = 100_000;
+
+/// Densify a polyline by inserting evenly spaced points along its segments
+///
+/// Every segment longer than `max_segment_length` is split into equal parts no longer than it.
+/// Original points are kept. At most about [`MAX_DENSIFIED_POINTS`] points are inserted: a
+/// `max_segment_length` too short for that is lengthened to `total length / MAX_DENSIFIED_POINTS`.
+/// Returns the points unchanged if `max_segment_length` is not a positive finite number.
+#[napi]
+pub fn densify_polyline(points: Vec<Point>, max_segment_length: f64) -> Vec<Point> {
+  if !(max_segment_length.is_finite() && max_segment_length > 0.0) || points.len() < 2 {
+    return points;
+  }
+  let total_length: f64 = points.windows(2).map(|w| w[0].distance_to(&w[1])).sum();
+  let max_segment_length = max_segment_length.max(total_length / MAX_DENSIFIED_POINTS as f64);
+
+  let mut densified = Vec::with_capacity(points.len());
+  densified.push(points[0]);
+  for segment in points.windows(2) {
+    let (a, b) = (segment[0], segment[1]);
+    let parts = (a.distance_to(&b) / max_segment_length)
+      .ceil()
+      .clamp(1.0, MAX_DENSIFIED_POINTS as f64) as usize;
+    for k in 1..parts {
+      let t = k as f64 / parts as f64;
+      densified.push(Point::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t));
+    }
+    densified.push(b);
+  }
+  densified
+}
+
+/// Project a point onto the nearest point of a polyline
+///
+/// Returns the closest point on any segment (a vertex or a perpendicular foot) and its
+/// distance from `p`; of equally close points the first along the polyline wins. A single
+/// point polyline projects onto that point. Returns `None` for an empty polyline.
+pub fn project_point_onto_polyline(p: Point, polyline: &[Point]) -> Option<(Point, f64)> {
+  let project = |a: Point, b: Point| {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared > 0.0 {
+      (((p.x - a.x) * dx + (p.y - a.y) * dy) / length_squared).clamp(0.0, 1.0)
+    } else {
+      0.0
+    };
+    let foot = Point::new(a.x + dx * t, a.y + dy * t);
+    (foot, p.distance_to(&foot))
+  };
+  match polyline {
+    [] => None,
+    [only] => Some((*only, p.distance_to(only))),
+    _ => polyline
+      .windows(2)
+      .map(|segment| project(segment[0], segment[1]))
+      .reduce(|best, candidate| {
+        if candidate.1 < best.1 {
+          candidate
+        } else {
+          best
+        }
+      }),
+  }
+}
+
+/// Project a point onto the nearest point of a polyline, as `[closest point, distance]`
+///
+/// See [`project_point_onto_polyline`]. Returns `None` for an empty polyline.
+#[napi(js_name = "projectPointOntoPolyline")]
+pub fn project_point_onto_polyline_js(point: Point, polyline: Vec<Point>) -> Option<(Point, f64)> {
+  project_point_onto_polyline(point, &polyline)
+}
+
+/// Snap nearly horizontal or vertical edges of a polyline to exactly horizontal or vertical
+///
+/// Edges are processed in order, each from its (possibly already adjusted) first vertex: an
+/// edge within `angle_tolerance_deg` of an axis is straightened by moving its later vertex.
+/// Diagonal edges and the first vertex are left untouched. A closed ring (last point equal to
+/// the first) stays closed, so its closing edge back to the start is not adjusted. Returns the
+/// points unchanged if the tolerance is not a finite number in `[0, 45)`.
+#[napi]
+pub fn orthogonalize(points: Vec<Point>, angle_tolerance_deg: f64) -> Vec<Point> {
+  if !(angle_tolerance_deg.is_finite() && (0.0..45.0).contains(&angle_tolerance_deg)) {
+    return points;
+  }
+  let tolerance = angle_tolerance_deg.to_radians();
+
+  let closed = points.len() > 2 && points.first() == points.last();
+  let mut snapped = points;
+  for i in 1..snapped.len() {
+    let (a, b) = (snapped[i - 1], snapped[i]);
+    let (dx, dy) = ((b.x - a.x).abs(), (b.y - a.y).abs());
+    if dx == 0.0 && dy == 0.0 {
+      continue;
+    }
+    if dy.atan2(dx) <= tolerance {
+      snapped[i].y = a.y;
+    } else if dx.atan2(dy) <= tolerance {
+      snapped[i].x = a.x;
+    }
+  }
+  if closed {
+    let last = snapped.len() - 1;
+    snapped[last] = snapped[0];
+  }
+  snapped
+}
+
+/// Count points after merging those within `radius` of each other (e.g. double clicks)
+///
+/// Merging is transitive: points chained by gaps of at most `radius` form one cluster even if
+/// its ends are further apart. Points are bucketed on a `radius`-sized grid, so only
+/// neighbouring cells are compared; cell indices saturate for coordinates far beyond `radius`,
+/// where the distance check still decides. Every point counts on its own if `radius` is not a
+/// positive finite number.
+#[napi]
+pub fn cluster_count(points: Vec<Point>, radius: f64) -> u32 {
+  if !(radius.is_finite() && radius > 0.0) {
+    return points.len() as u32;
+  }
+
+  fn find(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+      parents[i] = parents[parents[i]];
+      i = parents[i];
+    }
+    i
+  }
+
+  let cell = |v: f64| (v / radius).floor() as i64;
+  let mut parents: Vec<usize> = (0..points.len()).collect();
+  let mut clusters = points.len() as u32;
+  let mut buckets: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+  for (i, p) in points.iter().enumerate() {
+    let (cx, cy) = (cell(p.x), cell(p.y));
+    for x in cx.saturating_sub(1)..=cx.saturating_add(1) {
+      for y in cy.saturating_sub(1)..=cy.saturating_add(1) {
+        for &j in buckets.get(&(x, y)).into_iter().flatten() {
+          if p.distance_to(&points[j]) <= radius {
+            let (a, b) = (find(&mut parents, i), find(&mut parents, j));
+            if a != b {
+              parents[a] = b;
+              clusters -= 1;
+            }
+          }
+        }
+      }
+    }
+    buckets.entry((cx, cy)).or_default().push(i);
+  }
+  clusters
+}
+
+type VertexKey = (u64, u64);
+
+fn vertex_key(point: &Point) -> VertexKey {
+  (point.x.to_bits(), point.y.to_bits())
+}
+
+fn edge_key(a: &Point, b: &Point) -> (VertexKey, VertexKey) {
+  let (a, b) = (vertex_key(a), vertex_key(b));
+  if a <= b { (a, b) } else { (b, a) }
+}
+
+/// Simplify measurements together so boundaries they share stay identical.
+///
+/// Each polygon ring and polyline is split into arcs at the vertices where the set of
+/// measurements sharing the adjacent edges changes. Every arc is simplified once (with
+/// Ramer-Douglas-Peucker, like `simplify_polyline`) and reused by all measurements that share
+/// it, so adjacent rooms don't open sliver gaps or overlaps. Edges are shared only when their
+/// vertices coincide exactly.
+///
+/// Rectangles and counts are returned unchanged (rectangle edges still count as shared
+/// boundaries). Rings that would collapse below 3 points, or whose simplified points fail
+/// `Measurement::with_points` validation, keep their original points.
+#[napi]
+pub fn simplify_shared(measurements: Vec<Measurement>, tolerance: f64) -> Vec<Measurement> {
+  // (points, closed, rewritable) per measurement
+  let paths: Vec<Option<(Vec<Point>, bool, bool)>> = measurements
+    .iter()
+    .map(|measurement| match measurement {
+      Measurement::Polygon { points, .. } => Some((open_ring(points).to_vec(), true, true)),
+      Measurement::Polyline { points, .. } => Some((points.clone(), false, true)),
+      Measurement::Rectangle {
+        points, rotation, ..
+      } => Some((rectangle_corners(*points, *rotation).to_vec(), true, false)),
+      Measurement::Count { .. } => None,
+    })
+    .collect();
+
+  let mut edge_owners: HashMap<(VertexKey, VertexKey), Vec<usize>> = HashMap::new();
+  for (index, (points, closed, _)) in paths
+    .iter()
+    .enumerate()
+    .filter_map(|(i, p)| Some((i, p.as_ref()?)))
+  {
+    for (a, b) in path_edges(points, *closed) {
+      let owners = edge_owners.entry(edge_key(a, b)).or_default();
+      if owners.last() != Some(&index) {
+        owners.push(index);
+      }
+    }
+  }
+
+  let mut arc_cache: HashMap<Vec<VertexKey>, Vec<Point>> = HashMap::new();
+  measurements
+    .into_iter()
+    .zip(paths)
+    .map(|(measurement, path)| match path {
+      Some((points, closed, true)) => {
+        let simplified =
+          simplify_path_shared(&points, closed, tolerance, &edge_owners, &mut arc_cache);
+        // Keep the original if the simplified points fail the measurement's validation.
+        measurement.with_points(simplified).unwrap_or(measurement)
+      }
+      _ => measurement,
+    })
+    .collect()
+}
+
+fn path_edges(points: &[Point], closed: bool) -> Vec<(&Point, &Point)> {
+  let mut edges: Vec<(&Point, &Point)> = points.windows(2).map(|w| (&w[0], &w[1])).collect();
+  if closed && points.len() > 2 {
+    edges.push((&points[points.len() - 1], &points[0]));
+  }
+  edges
+}
+
+fn simplify_path_shared(
+  points: &[Point],
+  closed: bool,
+  tolerance: f64,
+  edge_owners: &HashMap<(VertexKey, VertexKey), Vec<usize>>,
+  arc_cache: &mut HashMap<Vec<VertexKey>, Vec<Point>>,
+) -> Vec<Point> {
+  let n = points.len();
+  let min_points = if closed { 3 } else { 2 };
+  if n <= min_points {
+    return points.to_vec();
+  }
+  let owners = |a: usize, b: usize| edge_owners.get(&edge_key(&points[a], &points[b]));
+
+  // Vertices where the sharing changes are fixed; so are polyline endpoints.
+  let anchors: Vec<usize> = (0..n)
+    .filter(|&i| {
+      if !closed {
+        return i == 0 || i == n - 1 || owners(i - 1, i) != owners(i, i + 1);
+      }
+      owners((i + n - 1) % n, i) != owners(i, (i + 1) % n)
+    })
+    .collect();
+
+  if anchors.is_empty() {
+    // An unshared ring: simplify it as a whole.
+    let mut ring = points.to_vec();
+    ring.push(points[0]);
+    let mut simplified = simplify_polyline(ring, tolerance);
+    simplified.pop();
+    return if simplified.len() >= 3 {
+      simplified
+    } else {
+      points.to_vec()
+    };
+  }
+
+  let mut result: Vec<Point> = Vec::with_capacity(n);
+  let arc_count = if closed {
+    anchors.len()
+  } else {
+    anchors.len() - 1
+  };
+  for (k, &start) in anchors.iter().enumerate().take(arc_count) {
+    let end = anchors[(k + 1) % anchors.len()];
+    let len = if end > start {
+      end - start
+    } else {
+      end + n - start
+    };
+    let arc: Vec<Point> = (0..=len)
+      .map(|offset| points[(start + offset) % n])
+      .collect();
+    let simplified = simplify_arc(&arc, tolerance, arc_cache);
+    // Consecutive arcs share their anchor; keep it once.
+    let skip = usize::from(!result.is_empty());
+    result.extend(simplified.into_iter().skip(skip));
+  }
+  if closed {
+    result.pop();
+  }
+
+  if result.len() >= min_points {
+    result
+  } else {
+    points.to_vec()
+  }
+}
+
+/// Simplify an arc in a canonical direction so both sides of a shared boundary match exactly.
+fn simplify_arc(
+  arc: &[Point],
+  tolerance: f64,
+  arc_cache: &mut HashMap<Vec<VertexKey>, Vec<Point>>,
+) -> Vec<Point> {
+  let forward: Vec<VertexKey> = arc.iter().map(vertex_key).collect();
+  let backward: Vec<VertexKey> = forward.iter().rev().copied().collect();
+  let reversed = backward < forward;
+  let key = if reversed { backward } else { forward };
+
+  let canonical = arc_cache.entry(key).or_insert_with(|| {
+    let mut points = arc.to_vec();
+    if reversed {
+      points.reverse();
+    }
+    simplify_polyline(points, tolerance)
+  });
+
+  let mut simplified = canonical.clone();
+  if reversed {
+    simplified.reverse();
+  }
+  simplified
+}
+
 /// Get the centroid of a measurement
 ///
 /// Returns `None` if the measurement has invalid geometry.
@@ -39,6 +364,32 @@ pub fn reposition_measurement_to_centroid(
     .map_err(Into::into)
 }
 
+/// Round a value to `figs` significant figures, e.g. `12_345.0` to `12_300.0` and `0.012345`
+/// to `0.012` with 3 and 2 figures, so values of very different magnitudes display sensibly.
+///
+/// `figs` is clamped to `1..=17`, the most an `f64` can hold. Zero, infinite and NaN values
+/// are returned unchanged, as are values too close to the `f64` limits to scale (e.g. `1e-300`
+/// to 17 figures).
+#[napi]
+pub fn round_sig_figs(value: f64, figs: u32) -> f64 {
+  const MAX_SIG_FIGS: u32 = 17;
+  if value == 0.0 || !value.is_finite() {
+    return value;
+  }
+  let figs = figs.clamp(1, MAX_SIG_FIGS) as i32;
+  let exponent = figs - 1 - value.abs().log10().floor() as i32;
+  let factor = 10_f64.powi(exponent.abs());
+  if !factor.is_finite() {
+    return value;
+  }
+  // Dividing by an exact power of ten avoids multiplying by an inexact one (e.g. 0.01).
+  if exponent >= 0 {
+    (value * factor).round() / factor
+  } else {
+    (value / factor).round() * factor
+  }
+}
+
 /// Generate a random id
 #[napi]
 pub fn generate_random_id() -> String {
@@ -49,6 +400,104 @@ pub fn generate_random_id() -> String {
 mod tests {
   use super::*;
 
+  #[test]
+  fn test_round_sig_figs() {
+    assert_eq!(round_sig_figs(12_345.0, 3), 12_300.0);
+    assert_eq!(round_sig_figs(0.012345, 2), 0.012);
+    assert_eq!(round_sig_figs(-987.6, 2), -990.0);
+    assert_eq!(round_sig_figs(9.96, 2), 10.0);
+    assert_eq!(round_sig_figs(4.5, 0), 5.0);
+    assert_eq!(round_sig_figs(0.0, 3), 0.0);
+    assert!(round_sig_figs(f64::NAN, 3).is_nan());
+    assert_eq!(round_sig_figs(1e-300, 17), 1e-300);
+    assert_eq!(round_sig_figs(1.25, u32::MAX), 1.25);
+  }
+
+  #[test]
+  fn test_project_point_onto_polyline() {
+    let polyline = [
+      Point::new(0.0, 0.0),
+      Point::new(10.0, 0.0),
+      Point::new(10.0, 10.0),
+    ];
+
+    // Perpendicular foot on the first segment.
+    let (closest, distance) = project_point_onto_polyline(Point::new(4.0, 3.0), &polyline).unwrap();
+    assert_eq!(closest, Point::new(4.0, 0.0));
+    assert!((distance - 3.0).abs() < 1e-12);
+
+    // Nearer to the second segment.
+    let (closest, distance) =
+      project_point_onto_polyline(Point::new(12.0, 6.0), &polyline).unwrap();
+    assert_eq!(closest, Point::new(10.0, 6.0));
+    assert!((distance - 2.0).abs() < 1e-12);
+
+    // Beyond the end, the end vertex is closest.
+    let (closest, distance) =
+      project_point_onto_polyline(Point::new(13.0, 14.0), &polyline).unwrap();
+    assert_eq!(closest, Point::new(10.0, 10.0));
+    assert!((distance - 5.0).abs() < 1e-12);
+
+    let (closest, _) = project_point_onto_polyline(Point::new(1.0, 1.0), &polyline[..1]).unwrap();
+    assert_eq!(closest, Point::new(0.0, 0.0));
+    assert!(project_point_onto_polyline(Point::new(1.0, 1.0), &[]).is_none());
+  }
+
+  #[test]
+  fn test_orthogonalize() {
+    let near_rectangle = vec![
+      Point::new(0.0, 0.0),
+      Point::new(10.0, 0.2),
+      Point::new(10.1, 5.0),
+      Point::new(-0.1, 5.1),
+      Point::new(0.0, 0.0),
+    ];
+    assert_eq!(
+      orthogonalize(near_rectangle.clone(), 5.0),
+      vec![
+        Point::new(0.0, 0.0),
+        Point::new(10.0, 0.0),
+        Point::new(10.0, 5.0),
+        Point::new(-0.1, 5.0),
+        Point::new(0.0, 0.0),
+      ]
+    );
+
+    // An open polyline snaps its last edge like any other.
+    let open = vec![Point::new(0.0, 0.0), Point::new(0.1, 5.0)];
+    assert_eq!(
+      orthogonalize(open, 5.0),
+      vec![Point::new(0.0, 0.0), Point::new(0.0, 5.0)]
+    );
+
+    // A 45° diagonal is never snapped; nothing is within a 0.5° tolerance here.
+    let diagonal = vec![Point::new(0.0, 0.0), Point::new(3.0, 3.0)];
+    assert_eq!(orthogonalize(diagonal.clone(), 10.0), diagonal);
+    assert_eq!(orthogonalize(near_rectangle.clone(), 0.5), near_rectangle);
+  }
+
+  #[test]
+  fn test_cluster_count() {
+    let points = vec![
+      // A double click.
+      Point::new(0.0, 0.0),
+      Point::new(0.5, 0.0),
+      // A chain: each gap is within the radius, the ends are not.
+      Point::new(10.0, 0.0),
+      Point::new(10.9, 0.0),
+      Point::new(11.8, 0.0),
+      // Alone.
+      Point::new(-5.0, 3.0),
+    ];
+    assert_eq!(cluster_count(points.clone(), 1.0), 3);
+    assert_eq!(cluster_count(points.clone(), 0.1), 6);
+    assert_eq!(cluster_count(points, 0.0), 6);
+    assert_eq!(cluster_count(vec![], 1.0), 0);
+    // Cell indices past the i64 range saturate instead of overflowing.
+    let far = vec![Point::new(1.0, 0.0), Point::new(1.0, 0.0)];
+    assert_eq!(cluster_count(far, 1e-300), 1);
+  }
+
   #[test]
   fn test_simplify_polyline() {
     let points = vec![
@@ -68,6 +517,145 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_simplify_shared_keeps_shared_edge_identical() {
+    let polygon = |id: &str, points: Vec<Point>| Measurement::Polygon {
+      id: id.to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points,
+      metadata: None,
+    };
+    // Two rooms sharing the wall x = 10, digitized with a slight wobble in the middle.
+    let left = polygon(
+      "left",
+      vec![
+        Point::new(0.0, 0.0),
+        Point::new(10.0, 0.0),
+        Point::new(10.0, 3.0),
+        Point::new(10.05, 5.0),
+        Point::new(10.0, 7.0),
+        Point::new(10.0, 10.0),
+        Point::new(0.0, 10.0),
+      ],
+    );
+    let right = polygon(
+      "right",
+      vec![
+        Point::new(10.0, 0.0),
+        Point::new(20.0, 0.0),
+        Point::new(20.0, 10.0),
+        Point::new(10.0, 10.0),
+        Point::new(10.0, 7.0),
+        Point::new(10.05, 5.0),
+        Point::new(10.0, 3.0),
+      ],
+    );
+
+    let simplified = simplify_shared(vec![left, right], 0.1);
+    let wall = |m: &Measurement| -> Vec<Point> {
+      let Measurement::Polygon { points, .. } = m else {
+        panic!("expected polygon");
+      };
+      let mut wall: Vec<Point> = points
+        .iter()
+        .copied()
+        .filter(|p| p.x > 9.0 && p.x < 11.0)
+        .collect();
+      wall.sort_by(|a, b| a.y.total_cmp(&b.y));
+      wall
+    };
+
+    assert_eq!(
+      wall(&simplified[0]),
+      vec![Point::new(10.0, 0.0), Point::new(10.0, 10.0)]
+    );
+    assert_eq!(wall(&simplified[0]), wall(&simplified[1]));
+    // No gap or overlap: the rooms still tile the 20 x 10 footprint.
+    let total: f64 = simplified.iter().map(|m| m.pixel_area().unwrap()).sum();
+    assert_eq!(total, 200.0);
+  }
+
+  #[test]
+  fn test_simplify_shared_two_rectangles() {
+    let polygon = |id: &str, points: Vec<Point>| Measurement::Polygon {
+      id: id.to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points,
+      metadata: None,
+    };
+    // Two 10 x 10 rectangles sharing the edge x = 10, each with a redundant vertex on it.
+    let left = polygon(
+      "left",
+      vec![
+        Point::new(0.0, 0.0),
+        Point::new(10.0, 0.0),
+        Point::new(10.0, 5.0),
+        Point::new(10.0, 10.0),
+        Point::new(0.0, 10.0),
+      ],
+    );
+    let right = polygon(
+      "right",
+      vec![
+        Point::new(10.0, 0.0),
+        Point::new(20.0, 0.0),
+        Point::new(20.0, 10.0),
+        Point::new(10.0, 10.0),
+        Point::new(10.0, 5.0),
+      ],
+    );
+
+    let simplified = simplify_shared(vec![left, right], 0.1);
+    let shared_edge = |m: &Measurement| -> Vec<Point> {
+      let Measurement::Polygon { points, .. } = m else {
+        panic!("expected polygon");
+      };
+      let mut edge: Vec<Point> = points.iter().copied().filter(|p| p.x == 10.0).collect();
+      edge.sort_by(|a, b| a.y.total_cmp(&b.y));
+      edge
+    };
+
+    assert_eq!(shared_edge(&simplified[0]), shared_edge(&simplified[1]));
+    assert_eq!(
+      shared_edge(&simplified[0]),
+      vec![Point::new(10.0, 0.0), Point::new(10.0, 10.0)]
+    );
+    for measurement in &simplified {
+      assert_eq!(measurement.pixel_area().unwrap(), 100.0);
+    }
+  }
+
+  #[test]
+  fn test_densify_polyline() {
+    let points = vec![
+      Point::new(0.0, 0.0),
+      Point::new(10.0, 0.0),
+      Point::new(10.0, 1.0),
+    ];
+    let densified = densify_polyline(points.clone(), 4.0);
+    assert_eq!(
+      densified,
+      vec![
+        Point::new(0.0, 0.0),
+        Point::new(10.0 * (1.0 / 3.0), 0.0),
+        Point::new(10.0 * (2.0 / 3.0), 0.0),
+        Point::new(10.0, 0.0),
+        Point::new(10.0, 1.0),
+      ]
+    );
+    assert!(densified.windows(2).all(|w| w[0].distance_to(&w[1]) <= 4.0));
+
+    assert_eq!(densify_polyline(points.clone(), 0.0), points);
+
+    // A tiny segment length is capped instead of allocating ~1e12 points.
+    let long = vec![Point::new(0.0, 0.0), Point::new(1000.0, 0.0)];
+    let densified = densify_polyline(long, 1e-9);
+    assert!(densified.len() <= MAX_DENSIFIED_POINTS + 1);
+    assert_eq!(densified.last(), Some(&Point::new(1000.0, 0.0)));
+  }
+
   #[test]
   fn test_get_centroid() {
     let measurement = Measurement::Rectangle {
@@ -75,6 +663,8 @@ mod tests {
       page_id: "1".to_string(),
       group_id: "1".to_string(),
       points: (Point::new(0.0, 0.0), Point::new(1.0, 1.0)),
+      rotation: None,
+      metadata: None,
     };
 
     let centroid = get_centroid(measurement);
@@ -88,6 +678,7 @@ mod tests {
       page_id: "1".to_string(),
       group_id: "1".to_string(),
       points: vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)], // Only 2 points
+      metadata: None,
     };
 
     let centroid = get_centroid(measurement);
@@ -101,6 +692,8 @@ mod tests {
       page_id: "1".to_string(),
       group_id: "1".to_string(),
       points: (Point::new(0.0, 0.0), Point::new(2.0, 2.0)),
+      rotation: None,
+      metadata: None,
     };
     let new_centroid = Point::new(10.0, 20.0);
     let result = reposition_measurement_to_centroid(measurement, new_centroid).unwrap();
@@ -115,6 +708,7 @@ mod tests {
       page_id: "1".to_string(),
       group_id: "1".to_string(),
       points: vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)],
+      metadata: None,
     };
     let result = reposition_measurement_to_centroid(measurement, Point::new(0.0, 0.0));
     assert!(result.is_err(), "empty geometry should yield error");
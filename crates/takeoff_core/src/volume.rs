@@ -1,14 +1,20 @@
-//! Volumetric cut/fill calculations between a surface mesh and a reference polygon at constant elevation.
+//! Volumetric cut/fill calculations between a surface mesh and a reference polygon at a constant
+//! elevation or on a sloped plane.
 
-use crate::contour::SurfaceMesh;
+use crate::contour::{SurfaceMesh, triangle_plane};
 use crate::coords::Point;
-use geo::{Area, Contains, Coord, CoordsIter, LineString, Point as GeoPoint, Polygon, Rect};
+use crate::error::{TakeoffError, TakeoffResult};
+use crate::measurement::Measurement;
+use geo::{
+  Area, BooleanOps, BoundingRect, Contains, Coord, CoordsIter, Intersects, LineString,
+  Point as GeoPoint, Polygon, Rect, TriangulateEarcut,
+};
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
 
 /// Input for creating a reference surface from JS/TS.
 #[napi(discriminant = "type")]
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ReferenceSurfaceInput {
   Polygon {
     points: Vec<Point>,
@@ -18,12 +24,23 @@ pub enum ReferenceSurfaceInput {
     points: (Point, Point),
     elevation: f64,
   },
+  /// A polygon pad on an inclined plane (e.g. a pad sloped for drainage).
+  ///
+  /// `elevation_at` is the elevation at the first vertex of `points`; the plane changes by
+  /// `slope_x` per unit of x and `slope_y` per unit of y.
+  SlopedPlane {
+    points: Vec<Point>,
+    elevation_at: f64,
+    slope_x: f64,
+    slope_y: f64,
+  },
 }
 
 impl ReferenceSurfaceInput {
   pub fn to_polygon(&self) -> Polygon<f64> {
     match self {
-      ReferenceSurfaceInput::Polygon { points, .. } => {
+      ReferenceSurfaceInput::Polygon { points, .. }
+      | ReferenceSurfaceInput::SlopedPlane { points, .. } => {
         let points: Vec<Coord<f64>> = points.iter().map(|p| (*p).into()).collect();
         Polygon::new(LineString::from(points), vec![])
       }
@@ -37,11 +54,22 @@ impl ReferenceSurfaceInput {
   }
 }
 
-/// A reference surface: a 2D polygon extruded at a constant elevation (e.g. foundation footprint).
+/// Linear gradient of a sloped reference plane, anchored at `origin`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlaneSlope {
+  pub origin: Point,
+  pub slope_x: f64,
+  pub slope_y: f64,
+}
+
+/// A reference surface: a 2D polygon extruded at a constant elevation (e.g. foundation footprint),
+/// or lying on a sloped plane when `slope` is set.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ReferenceSurface {
   pub polygon: Polygon<f64>,
+  /// Elevation of the surface (at `slope.origin` for a sloped plane).
   pub elevation: f64,
+  pub slope: Option<PlaneSlope>,
 }
 
 impl ReferenceSurface {
@@ -52,12 +80,54 @@ impl ReferenceSurface {
       .map(|p| Into::<Coord<f64>>::into(*p))
       .collect();
     let polygon = Polygon::new(LineString::from(coords), vec![]);
-    Self { polygon, elevation }
+    Self::from_polygon(polygon, elevation)
   }
 
   /// Create from a geo polygon and elevation.
   pub fn from_polygon(polygon: Polygon<f64>, elevation: f64) -> Self {
-    Self { polygon, elevation }
+    Self {
+      polygon,
+      elevation,
+      slope: None,
+    }
+  }
+
+  /// Create a sloped reference surface from an exterior ring.
+  ///
+  /// `elevation` is the elevation at the first vertex of `exterior`; the reference z at (x, y) is
+  /// `elevation + slope_x * (x - x0) + slope_y * (y - y0)`.
+  pub fn sloped(exterior: Vec<Point>, elevation: f64, slope_x: f64, slope_y: f64) -> Self {
+    let origin = exterior.first().copied().unwrap_or(Point::new(0.0, 0.0));
+    Self {
+      slope: Some(PlaneSlope {
+        origin,
+        slope_x,
+        slope_y,
+      }),
+      ..Self::new(exterior, elevation)
+    }
+  }
+
+  /// Reference elevation at (x, y).
+  pub fn elevation_at(&self, x: f64, y: f64) -> f64 {
+    match &self.slope {
+      Some(slope) => {
+        self.elevation + slope.slope_x * (x - slope.origin.x) + slope.slope_y * (y - slope.origin.y)
+      }
+      None => self.elevation,
+    }
+  }
+
+  /// Create a reference surface from a polygon or rectangle measurement at a constant elevation.
+  ///
+  /// The measurement's footprint (in pixels) becomes the reference polygon.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::EmptyGeometry`](crate::TakeoffError::EmptyGeometry) if the
+  /// measurement geometry is invalid or the measurement is a polyline or count.
+  pub fn from_measurement(measurement: &Measurement, elevation: f64) -> TakeoffResult<Self> {
+    Ok(Self::from_polygon(measurement.to_polygon()?, elevation))
   }
 
   /// Polygon area (for default cell size calculation).
@@ -97,6 +167,12 @@ impl From<ReferenceSurfaceInput> for ReferenceSurface {
       ReferenceSurfaceInput::Rectangle { elevation, .. } => {
         ReferenceSurface::from_polygon(input.to_polygon(), elevation)
       }
+      ReferenceSurfaceInput::SlopedPlane {
+        points,
+        elevation_at,
+        slope_x,
+        slope_y,
+      } => ReferenceSurface::sloped(points, elevation_at, slope_x, slope_y),
     }
   }
 }
@@ -111,45 +187,81 @@ pub struct VolumetricResult {
   pub fill: f64,
   /// Area where terrain data was unavailable (z_at returned None).
   pub uncovered_area: f64,
+  /// Volume removed by stripping topsoil (`strip_depth * covered_area`); zero without stripping.
+  pub strip_volume: f64,
+}
+
+/// Per-cell cut/fill map of a surface against a reference (see [`SurfaceMesh::cut_fill_grid`]).
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CutFillGrid {
+  /// Lower-left corner of the first cell, in pixels.
+  pub origin: Point,
+  pub cell_size: f64,
+  pub cols: u32,
+  pub rows: u32,
+  /// Row-major (`row * cols + col`, rows going up in y) terrain z minus reference z at each
+  /// cell center: positive is cut, negative is fill. `None` outside the reference polygon or
+  /// where the mesh has no data.
+  pub deltas: Vec<Option<f64>>,
+}
+
+/// A stored cut/fill computation of a contour against a reference surface.
+///
+/// Volume groups (`MeasurementType::Volume`) aggregate the computations sharing their `group_id`.
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct VolumeComputation {
+  pub id: String,
+  pub group_id: String,
+  pub contour_id: String,
+  pub reference: ReferenceSurfaceInput,
+  pub cell_size: Option<f64>,
+  pub strip_depth: Option<f64>,
 }
 
 impl SurfaceMesh {
   /// Compute cut/fill volume against a reference surface using grid sampling.
   /// Uses `cell_size` if provided; otherwise defaults to `sqrt(polygon_area / 1000)`.
+  /// The reference z is evaluated per cell, so sloped reference surfaces are supported.
+  ///
+  /// `strip_depth` lowers the sampled terrain by a uniform topsoil strip before comparing to the
+  /// reference; the stripped volume is reported separately as `strip_volume`. `None` means no
+  /// stripping.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::EmptyGeometry`] if the reference polygon is empty or has no area
+  /// (e.g. collinear points), so a degenerate pad is not mistaken for one that matches the
+  /// terrain and needs no earthwork. Returns [`TakeoffError::InvalidInput`] if `cell_size` is
+  /// not a positive finite number.
   pub fn volume_against(
     &self,
     reference: &ReferenceSurface,
     cell_size: Option<f64>,
-  ) -> VolumetricResult {
-    let bbox = match reference.bounding_box() {
-      Some(b) => b,
-      None => {
-        return VolumetricResult {
-          cut: 0.0,
-          fill: 0.0,
-          uncovered_area: 0.0,
-        };
-      }
-    };
-
+    strip_depth: Option<f64>,
+  ) -> TakeoffResult<VolumetricResult> {
     let area = reference.area();
-    if area <= 0.0 {
-      return VolumetricResult {
-        cut: 0.0,
-        fill: 0.0,
-        uncovered_area: 0.0,
-      };
-    }
+    let bbox = reference
+      .bounding_box()
+      .filter(|_| area > 0.0)
+      .ok_or_else(|| TakeoffError::empty_geometry("reference polygon has no area"))?;
 
     let cell_size = cell_size.unwrap_or_else(|| (area / 1000.0).sqrt());
+    if !(cell_size.is_finite() && cell_size > 0.0) {
+      return Err(TakeoffError::invalid_input(format!(
+        "cell_size must be a positive number, got {cell_size}"
+      )));
+    }
     let cell_area = cell_size * cell_size;
 
     let (min_x, min_y, max_x, max_y) = bbox;
-    let ref_elev = reference.elevation;
+    let strip_depth = strip_depth.unwrap_or(0.0);
 
     let mut cut = 0.0;
     let mut fill = 0.0;
     let mut uncovered_area = 0.0;
+    let mut covered_area = 0.0;
 
     let mut x = min_x + cell_size / 2.0;
     while x < max_x {
@@ -159,7 +271,8 @@ impl SurfaceMesh {
         if reference.polygon.contains(&point) {
           match self.z_at(x, y) {
             Some(terrain_z) => {
-              let delta = terrain_z - ref_elev;
+              covered_area += cell_area;
+              let delta = terrain_z - strip_depth - reference.elevation_at(x, y);
               if delta > 0.0 {
                 cut += cell_area * delta;
               } else if delta < 0.0 {
@@ -174,17 +287,134 @@ impl SurfaceMesh {
       x += cell_size;
     }
 
-    VolumetricResult {
+    Ok(VolumetricResult {
       cut,
       fill,
       uncovered_area,
+      strip_volume: strip_depth * covered_area,
+    })
+  }
+
+  /// Sample terrain minus reference elevation on a grid over the reference's bounding box, for
+  /// cut/fill heatmaps.
+  ///
+  /// Cells are sampled at their centers, like [`SurfaceMesh::volume_against`], and the grid
+  /// extends to cover the whole bounding box. An empty reference or a `cell_size` that is not
+  /// positive and finite yields an empty grid.
+  pub fn cut_fill_grid(&self, reference: &ReferenceSurface, cell_size: f64) -> CutFillGrid {
+    let bbox = reference
+      .bounding_box()
+      .filter(|_| cell_size.is_finite() && cell_size > 0.0);
+    let Some((min_x, min_y, max_x, max_y)) = bbox else {
+      return CutFillGrid {
+        origin: Point::new(0.0, 0.0),
+        cell_size,
+        cols: 0,
+        rows: 0,
+        deltas: vec![],
+      };
+    };
+
+    let cols = ((max_x - min_x) / cell_size).ceil().max(1.0) as u32;
+    let rows = ((max_y - min_y) / cell_size).ceil().max(1.0) as u32;
+    let deltas = (0..rows)
+      .flat_map(|row| (0..cols).map(move |col| (row, col)))
+      .map(|(row, col)| {
+        let x = min_x + (col as f64 + 0.5) * cell_size;
+        let y = min_y + (row as f64 + 0.5) * cell_size;
+        if !reference.polygon.contains(&GeoPoint::new(x, y)) {
+          return None;
+        }
+        Some(self.z_at(x, y)? - reference.elevation_at(x, y))
+      })
+      .collect();
+
+    CutFillGrid {
+      origin: Point::new(min_x, min_y),
+      cell_size,
+      cols,
+      rows,
+      deltas,
+    }
+  }
+
+  /// Compute cut/fill volume against a reference surface exactly, without grid sampling.
+  ///
+  /// Each mesh triangle is clipped to the reference polygon. Over a triangle both the terrain
+  /// and the (flat or sloped) reference are planes, so their difference is linear and the
+  /// volume of each clipped piece is integrated analytically, splitting pieces that straddle
+  /// the reference at the zero crossing. The result is exact up to floating point error.
+  ///
+  /// `uncovered_area` is the part of the reference polygon not covered by the mesh. No topsoil
+  /// stripping is applied, so `strip_volume` is zero.
+  pub fn volume_against_exact(&self, reference: &ReferenceSurface) -> VolumetricResult {
+    let mut cut = 0.0;
+    let mut fill = 0.0;
+    let mut covered_area = 0.0;
+
+    for tri in &self.triangles {
+      let [a, b, c] = tri.map(|i| self.vertices[i as usize]);
+      let Some(terrain_z) = triangle_plane(a, b, c) else {
+        continue;
+      };
+
+      let triangle = Polygon::new(
+        LineString::from(vec![(a.x, a.y), (b.x, b.y), (c.x, c.y)]),
+        vec![],
+      );
+      let overlaps = match (triangle.bounding_rect(), reference.polygon.bounding_rect()) {
+        (Some(t), Some(r)) => t.intersects(&r),
+        _ => false,
+      };
+      if !overlaps {
+        continue;
+      }
+
+      for piece in triangle.intersection(&reference.polygon) {
+        for sub in piece.earcut_triangles() {
+          let corners = [sub.v1(), sub.v2(), sub.v3()];
+          let area = geo::Triangle::from(corners).unsigned_area();
+          if area <= 0.0 {
+            continue;
+          }
+          covered_area += area;
+          let delta = corners.map(|p| terrain_z(p.x, p.y) - reference.elevation_at(p.x, p.y));
+          cut += positive_part_integral(area, delta);
+          fill += positive_part_integral(area, delta.map(|d| -d));
+        }
+      }
+    }
+
+    VolumetricResult {
+      cut,
+      fill,
+      uncovered_area: (reference.area() - covered_area).max(0.0),
+      strip_volume: 0.0,
     }
   }
 }
 
+/// Integral of `max(d, 0)` over a triangle of area `area` on which `d` is linear with the given
+/// vertex values.
+fn positive_part_integral(area: f64, d: [f64; 3]) -> f64 {
+  let total = area * (d[0] + d[1] + d[2]) / 3.0;
+  let mut sorted = d;
+  sorted.sort_by(|a, b| b.total_cmp(a));
+  let [high, mid, low] = sorted;
+  match (high > 0.0, mid > 0.0, low > 0.0) {
+    (false, _, _) => 0.0,
+    (true, true, true) => total,
+    // Only the highest corner is above zero: the positive part is a smaller triangle there.
+    (true, false, _) => area * high.powi(3) / (3.0 * (high - mid) * (high - low)),
+    // Only the lowest corner is at or below zero: add back the negative part cut off there.
+    (true, true, false) => total + area * (-low).powi(3) / (3.0 * (high - low) * (mid - low)),
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::contour::MeshTolerances;
   use crate::coords::Point3D;
 
   #[test]
@@ -197,6 +427,7 @@ mod tests {
         Point3D::new(0.0, 10.0, 10.0),
       ],
       triangles: vec![[0, 1, 2], [0, 2, 3]],
+      tolerances: MeshTolerances::default(),
     };
     let reference = ReferenceSurface::new(
       vec![
@@ -207,7 +438,7 @@ mod tests {
       ],
       5.0,
     );
-    let result = mesh.volume_against(&reference, Some(1.0));
+    let result = mesh.volume_against(&reference, Some(1.0), None).unwrap();
     assert!(result.cut > 0.0, "expected cut > 0");
     assert!(result.fill < 1e-9, "expected fill ~ 0");
     assert!(result.uncovered_area < 1e-9, "expected no uncovered area");
@@ -231,6 +462,7 @@ mod tests {
         Point3D::new(0.0, 10.0, 2.0),
       ],
       triangles: vec![[0, 1, 2], [0, 2, 3]],
+      tolerances: MeshTolerances::default(),
     };
     let reference = ReferenceSurface::new(
       vec![
@@ -241,7 +473,7 @@ mod tests {
       ],
       5.0,
     );
-    let result = mesh.volume_against(&reference, Some(1.0));
+    let result = mesh.volume_against(&reference, Some(1.0), None).unwrap();
     assert!(result.fill > 0.0, "expected fill > 0");
     assert!(result.cut < 1e-9, "expected cut ~ 0");
     let expected_fill_approx = 6.0 * 6.0 * 3.0;
@@ -262,6 +494,7 @@ mod tests {
         Point3D::new(5.0, 10.0, 10.0),
       ],
       triangles: vec![[0, 1, 2]],
+      tolerances: MeshTolerances::default(),
     };
     let reference = ReferenceSurface::new(
       vec![
@@ -272,12 +505,305 @@ mod tests {
       ],
       5.0,
     );
-    let result = mesh.volume_against(&reference, Some(1.0));
+    let result = mesh.volume_against(&reference, Some(1.0), None).unwrap();
     assert!(result.uncovered_area > 0.0, "expected uncovered area");
     assert!(result.cut < 1e-9);
     assert!(result.fill < 1e-9);
   }
 
+  #[test]
+  fn test_volume_sloped_pad_against_flat_terrain() {
+    let mesh = SurfaceMesh {
+      vertices: vec![
+        Point3D::new(0.0, 0.0, 10.0),
+        Point3D::new(10.0, 0.0, 10.0),
+        Point3D::new(10.0, 10.0, 10.0),
+        Point3D::new(0.0, 10.0, 10.0),
+      ],
+      triangles: vec![[0, 1, 2], [0, 2, 3]],
+      tolerances: MeshTolerances::default(),
+    };
+    // Pad at terrain level along x = 0, rising 1 unit per unit of x.
+    let reference = ReferenceSurface::from(ReferenceSurfaceInput::SlopedPlane {
+      points: vec![
+        Point::new(0.0, 0.0),
+        Point::new(10.0, 0.0),
+        Point::new(10.0, 10.0),
+        Point::new(0.0, 10.0),
+      ],
+      elevation_at: 10.0,
+      slope_x: 1.0,
+      slope_y: 0.0,
+    });
+    assert_eq!(reference.elevation_at(0.0, 5.0), 10.0);
+    assert_eq!(reference.elevation_at(4.0, 5.0), 14.0);
+
+    let result = mesh.volume_against(&reference, Some(1.0), None).unwrap();
+    // Fill is the wedge between terrain and pad: integral of x over the 10x10 square = 500.
+    let expected_fill = 500.0;
+    assert!(result.cut < 1e-9, "expected cut ~ 0, got {}", result.cut);
+    assert!(
+      (result.fill - expected_fill).abs() < expected_fill * 0.01,
+      "fill should be ~{}, got {}",
+      expected_fill,
+      result.fill
+    );
+
+    let flat = ReferenceSurface::new(
+      vec![
+        Point::new(0.0, 0.0),
+        Point::new(10.0, 0.0),
+        Point::new(10.0, 10.0),
+        Point::new(0.0, 10.0),
+      ],
+      10.0,
+    );
+    let flat_result = mesh.volume_against(&flat, Some(1.0), None).unwrap();
+    assert!(flat_result.cut < 1e-9 && flat_result.fill < 1e-9);
+  }
+
+  #[test]
+  fn test_volume_against_exact_matches_grid() {
+    // Terrain z = x over a 10 x 10 square.
+    let mesh = SurfaceMesh {
+      vertices: vec![
+        Point3D::new(0.0, 0.0, 0.0),
+        Point3D::new(10.0, 0.0, 10.0),
+        Point3D::new(10.0, 10.0, 10.0),
+        Point3D::new(0.0, 10.0, 0.0),
+      ],
+      triangles: vec![[0, 1, 2], [0, 2, 3]],
+      tolerances: MeshTolerances::default(),
+    };
+    // The pad at elevation 5 straddles the terrain: x in [5, 8] is cut, [2, 5] is fill.
+    let reference = ReferenceSurface::new(
+      vec![
+        Point::new(2.0, 2.0),
+        Point::new(8.0, 2.0),
+        Point::new(8.0, 8.0),
+        Point::new(2.0, 8.0),
+      ],
+      5.0,
+    );
+    let exact = mesh.volume_against_exact(&reference);
+    assert!((exact.cut - 27.0).abs() < 1e-9, "cut: {}", exact.cut);
+    assert!((exact.fill - 27.0).abs() < 1e-9, "fill: {}", exact.fill);
+    assert!(exact.uncovered_area.abs() < 1e-9);
+
+    let grid = mesh.volume_against(&reference, Some(0.1), None).unwrap();
+    assert!((grid.cut - exact.cut).abs() < exact.cut * 0.02);
+    assert!((grid.fill - exact.fill).abs() < exact.fill * 0.02);
+
+    // A sloped triangular pad, half off the mesh.
+    let reference = ReferenceSurface::sloped(
+      vec![
+        Point::new(5.0, 5.0),
+        Point::new(15.0, 5.0),
+        Point::new(5.0, 15.0),
+      ],
+      2.0,
+      0.0,
+      0.5,
+    );
+    let exact = mesh.volume_against_exact(&reference);
+    let grid = mesh.volume_against(&reference, Some(0.05), None).unwrap();
+    // Only the 5 x 5 corner [5, 10] x [5, 10] of the pad lies on the mesh, all of it cut.
+    assert!((exact.uncovered_area - 25.0).abs() < 1e-9);
+    assert!((exact.cut - 106.25).abs() < 1e-9, "cut: {}", exact.cut);
+    assert!(exact.fill.abs() < 1e-9);
+    assert!((grid.cut - exact.cut).abs() < exact.cut * 0.02);
+    assert!((grid.fill - exact.fill).abs() < exact.fill.max(1.0) * 0.05);
+    assert!((grid.uncovered_area - exact.uncovered_area).abs() < exact.uncovered_area * 0.05);
+  }
+
+  #[test]
+  fn test_volume_with_strip_depth() {
+    let mesh = SurfaceMesh {
+      vertices: vec![
+        Point3D::new(0.0, 0.0, 10.0),
+        Point3D::new(10.0, 0.0, 10.0),
+        Point3D::new(10.0, 10.0, 10.0),
+        Point3D::new(0.0, 10.0, 10.0),
+      ],
+      triangles: vec![[0, 1, 2], [0, 2, 3]],
+      tolerances: MeshTolerances::default(),
+    };
+    let reference = ReferenceSurface::new(
+      vec![
+        Point::new(2.0, 2.0),
+        Point::new(8.0, 2.0),
+        Point::new(8.0, 8.0),
+        Point::new(2.0, 8.0),
+      ],
+      5.0,
+    );
+    let unstripped = mesh.volume_against(&reference, Some(1.0), None).unwrap();
+    assert_eq!(unstripped.strip_volume, 0.0);
+
+    let stripped = mesh
+      .volume_against(&reference, Some(1.0), Some(1.0))
+      .unwrap();
+    // 36 cells of area 1 covered; 1 unit of topsoil removed from each.
+    assert!((stripped.strip_volume - 36.0).abs() < 1e-9);
+    assert!(
+      (unstripped.cut - stripped.cut - stripped.strip_volume).abs() < 1e-6,
+      "stripping should move volume from cut to strip: {} vs {} + {}",
+      unstripped.cut,
+      stripped.cut,
+      stripped.strip_volume
+    );
+  }
+
+  #[test]
+  fn test_from_measurement_rectangle() {
+    let measurement = Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(2.0, 2.0), Point::new(8.0, 8.0)),
+      rotation: None,
+      metadata: None,
+    };
+    let reference = ReferenceSurface::from_measurement(&measurement, 5.0).unwrap();
+    assert_eq!(reference.elevation, 5.0);
+    assert!((reference.area() - 36.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_volume_degenerate_reference_is_an_error() {
+    let mesh = SurfaceMesh {
+      vertices: vec![
+        Point3D::new(0.0, 0.0, 5.0),
+        Point3D::new(10.0, 0.0, 5.0),
+        Point3D::new(10.0, 10.0, 5.0),
+        Point3D::new(0.0, 10.0, 5.0),
+      ],
+      triangles: vec![[0, 1, 2], [0, 2, 3]],
+      tolerances: MeshTolerances::default(),
+    };
+    let collinear = ReferenceSurface::new(
+      vec![
+        Point::new(1.0, 1.0),
+        Point::new(5.0, 5.0),
+        Point::new(9.0, 9.0),
+      ],
+      5.0,
+    );
+    assert!(matches!(
+      mesh.volume_against(&collinear, Some(1.0), None),
+      Err(TakeoffError::EmptyGeometry { .. })
+    ));
+
+    // A pad level with the terrain is a genuine zero, not an error.
+    let level = ReferenceSurface::new(
+      vec![
+        Point::new(1.0, 1.0),
+        Point::new(9.0, 1.0),
+        Point::new(9.0, 9.0),
+      ],
+      5.0,
+    );
+    let result = mesh.volume_against(&level, Some(1.0), None).unwrap();
+    assert_eq!((result.cut, result.fill), (0.0, 0.0));
+
+    for cell_size in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+      assert!(matches!(
+        mesh.volume_against(&level, Some(cell_size), None),
+        Err(TakeoffError::InvalidInput { .. })
+      ));
+    }
+  }
+
+  #[test]
+  fn test_cut_fill_grid() {
+    // Terrain rising with x: z = x over a 10 x 10 square.
+    let mesh = SurfaceMesh {
+      vertices: vec![
+        Point3D::new(0.0, 0.0, 0.0),
+        Point3D::new(10.0, 0.0, 10.0),
+        Point3D::new(10.0, 10.0, 10.0),
+        Point3D::new(0.0, 10.0, 0.0),
+      ],
+      triangles: vec![[0, 1, 2], [0, 2, 3]],
+      tolerances: MeshTolerances::default(),
+    };
+    // Reference at z = 5 extends past the mesh to x = 12.
+    let reference = ReferenceSurface::new(
+      vec![
+        Point::new(0.0, 0.0),
+        Point::new(12.0, 0.0),
+        Point::new(12.0, 4.0),
+        Point::new(0.0, 4.0),
+      ],
+      5.0,
+    );
+
+    let grid = mesh.cut_fill_grid(&reference, 4.0);
+    assert_eq!(grid.origin, Point::new(0.0, 0.0));
+    assert_eq!((grid.cols, grid.rows), (3, 1));
+    let deltas: Vec<Option<f64>> = grid.deltas.iter().map(|d| d.map(|d| d.round())).collect();
+    // Centers at x = 2 (fill), x = 6 (cut) and x = 10 (on the mesh edge).
+    assert_eq!(deltas[..2], [Some(-3.0), Some(1.0)]);
+
+    let grid = mesh.cut_fill_grid(&reference, 3.0);
+    assert_eq!((grid.cols, grid.rows), (4, 2));
+    assert_eq!(grid.deltas.len(), 8);
+    // The last column (x = 10.5) is off the mesh.
+    assert_eq!(grid.deltas[3], None);
+
+    assert!(mesh.cut_fill_grid(&reference, 0.0).deltas.is_empty());
+  }
+
+  #[test]
+  fn test_reference_surface_input_serde_round_trip() {
+    let inputs = [
+      ReferenceSurfaceInput::Polygon {
+        points: vec![
+          Point::new(0.0, 0.0),
+          Point::new(10.0, 0.0),
+          Point::new(10.0, 10.0),
+        ],
+        elevation: 12.5,
+      },
+      ReferenceSurfaceInput::Rectangle {
+        points: (Point::new(0.0, 0.0), Point::new(20.0, 5.0)),
+        elevation: -3.25,
+      },
+    ];
+    for input in inputs {
+      let json = serde_json::to_string(&input).unwrap();
+      let round_trip: ReferenceSurfaceInput = serde_json::from_str(&json).unwrap();
+      assert_eq!(round_trip, input);
+    }
+  }
+
+  #[test]
+  fn test_from_measurement_polyline_and_count_error() {
+    let polyline = Measurement::Polyline {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)],
+      metadata: None,
+    };
+    assert!(matches!(
+      ReferenceSurface::from_measurement(&polyline, 5.0),
+      Err(crate::error::TakeoffError::EmptyGeometry { .. })
+    ));
+
+    let count = Measurement::Count {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0),),
+      metadata: None,
+    };
+    assert!(matches!(
+      ReferenceSurface::from_measurement(&count, 5.0),
+      Err(crate::error::TakeoffError::EmptyGeometry { .. })
+    ));
+  }
+
   #[test]
   fn test_volume_degenerate_polygon() {
     let mesh = SurfaceMesh {
@@ -287,6 +813,7 @@ mod tests {
         Point3D::new(5.0, 10.0, 10.0),
       ],
       triangles: vec![[0, 1, 2]],
+      tolerances: MeshTolerances::default(),
     };
     let reference = ReferenceSurface::new(
       vec![
@@ -296,8 +823,6 @@ mod tests {
       ],
       5.0,
     );
-    let result = mesh.volume_against(&reference, Some(1.0));
-    assert_eq!(result.cut, 0.0);
-    assert_eq!(result.fill, 0.0);
+    assert!(mesh.volume_against(&reference, Some(1.0), None).is_err());
   }
 }
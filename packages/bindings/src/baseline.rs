@@ -0,0 +1,263 @@
+//! Batch computation of golden baseline values (RFC-007).
+//!
+//! Builds measurements and scales from baseline entries (schema in `test_data/README.md`) and
+//! computes their area, length and count, so external tooling can regenerate `baseline.json`
+//! and the golden test can compare against it.
+
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use takeoff_core::coords::Point;
+use takeoff_core::error::{TakeoffError, TakeoffResult};
+use takeoff_core::measurement::Measurement;
+use takeoff_core::scale::{Scale, ScaleDefinition};
+use takeoff_core::unit::Unit;
+
+use crate::measurement::MeasurementWrapper;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaselineScale {
+  pub pixel_distance: f64,
+  pub real_distance: f64,
+  /// Unit name, parsed case-insensitively (see `Unit::from_str`).
+  pub unit: String,
+}
+
+/// Expected values in the entry's `output_unit`; only the keys applicable to the kind are set.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BaselineExpected {
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub length: Option<f64>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub area: Option<f64>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub count: Option<f64>,
+}
+
+/// One case of `baseline.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+  pub id: String,
+  /// `"Polygon"`, `"Polyline"`, `"Rectangle"` or `"Count"`.
+  pub kind: String,
+  pub points: Vec<Point>,
+  pub scale: BaselineScale,
+  pub output_unit: String,
+  /// Missing when tooling computes values for new entries.
+  #[serde(default)]
+  pub expected: BaselineExpected,
+}
+
+/// Computed values for one entry, in its `output_unit`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaselineOutput {
+  pub id: String,
+  pub length: Option<f64>,
+  pub area: Option<f64>,
+  pub count: Option<f64>,
+}
+
+impl BaselineEntry {
+  /// Build the measurement on page `"1"`, group `"1"`.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::InvalidInput`] for an unknown kind, or a rectangle or count with
+  /// the wrong number of points.
+  pub fn to_measurement(&self) -> TakeoffResult<Measurement> {
+    let points = self.points.clone();
+    let id = self.id.clone();
+    let page_id = "1".to_string();
+    let group_id = "1".to_string();
+
+    let measurement = match self.kind.as_str() {
+      "Polygon" => Measurement::Polygon {
+        id,
+        page_id,
+        group_id,
+        points,
+        metadata: None,
+      },
+      "Polyline" => Measurement::Polyline {
+        id,
+        page_id,
+        group_id,
+        points,
+        metadata: None,
+      },
+      "Rectangle" => match points[..] {
+        [start, end] => Measurement::Rectangle {
+          id,
+          page_id,
+          group_id,
+          points: (start, end),
+          rotation: None,
+          metadata: None,
+        },
+        _ => return Err(self.invalid("a rectangle must have exactly 2 points")),
+      },
+      "Count" => match points[..] {
+        [point] => Measurement::Count {
+          id,
+          page_id,
+          group_id,
+          points: (point,),
+          metadata: None,
+        },
+        _ => return Err(self.invalid("a count must have exactly 1 point")),
+      },
+      kind => return Err(self.invalid(&format!("unknown kind {kind}"))),
+    };
+    Ok(measurement)
+  }
+
+  /// Build the page-wide scale of the entry.
+  ///
+  /// # Errors
+  ///
+  /// Returns [`TakeoffError::UnknownUnit`] if the unit is not recognized.
+  pub fn to_scale(&self) -> TakeoffResult<Scale> {
+    Ok(Scale::Default {
+      id: "scale-1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: self.scale.pixel_distance,
+        real_distance: self.scale.real_distance,
+        unit: Unit::from_str(&self.scale.unit)?,
+      },
+    })
+  }
+
+  /// Compute the entry's values the same way the bindings do.
+  ///
+  /// Counts only report `count`; polylines report `length`, and polygons and rectangles
+  /// report both `area` and `length` (their perimeter).
+  pub fn compute(&self) -> TakeoffResult<BaselineOutput> {
+    let output_unit = Unit::from_str(&self.output_unit)?;
+    let measurement = self.to_measurement()?;
+    let is_areal = measurement.is_areal();
+    let measurement_wrapper = MeasurementWrapper::default(measurement);
+    measurement_wrapper.set_scale(self.to_scale()?);
+
+    if measurement_wrapper.is_count() {
+      return Ok(BaselineOutput {
+        id: self.id.clone(),
+        length: None,
+        area: None,
+        count: Some(measurement_wrapper.get_count()),
+      });
+    }
+    let area = if is_areal {
+      measurement_wrapper
+        .get_area_value()?
+        .map(|area| output_unit.convert_area_to_unit(area))
+    } else {
+      None
+    };
+    let length = measurement_wrapper
+      .get_length_value()?
+      .map(|length| output_unit.convert_length_to_unit(length));
+    Ok(BaselineOutput {
+      id: self.id.clone(),
+      length,
+      area,
+      count: None,
+    })
+  }
+
+  fn invalid(&self, message: &str) -> TakeoffError {
+    TakeoffError::invalid_input(format!("baseline entry {}: {message}", self.id))
+  }
+}
+
+/// Compute the values of a batch of baseline entries, in order.
+///
+/// Entries are split into contiguous chunks computed on separate threads (serially on wasm),
+/// since each entry is independent.
+///
+/// # Errors
+///
+/// Returns the first error in entry order; see [`BaselineEntry::compute`].
+pub fn compute_baseline(entries: &[BaselineEntry]) -> TakeoffResult<Vec<BaselineOutput>> {
+  #[cfg(not(target_family = "wasm"))]
+  {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = entries.len().div_ceil(threads).max(1);
+    std::thread::scope(|s| {
+      let handles: Vec<_> = entries
+        .chunks(chunk_size)
+        .map(|chunk| {
+          s.spawn(move || {
+            chunk
+              .iter()
+              .map(BaselineEntry::compute)
+              .collect::<TakeoffResult<Vec<_>>>()
+          })
+        })
+        .collect();
+      let mut outputs = Vec::with_capacity(entries.len());
+      for handle in handles {
+        let chunk = handle
+          .join()
+          .unwrap_or_else(|panic| std::panic::resume_unwind(panic))?;
+        outputs.extend(chunk);
+      }
+      Ok(outputs)
+    })
+  }
+  #[cfg(target_family = "wasm")]
+  {
+    entries.iter().map(BaselineEntry::compute).collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn entry(id: &str, kind: &str, points: Vec<Point>) -> BaselineEntry {
+    BaselineEntry {
+      id: id.to_string(),
+      kind: kind.to_string(),
+      points,
+      scale: BaselineScale {
+        pixel_distance: 10.0,
+        real_distance: 1.0,
+        unit: "Feet".to_string(),
+      },
+      output_unit: "Inches".to_string(),
+      expected: BaselineExpected::default(),
+    }
+  }
+
+  #[test]
+  fn test_compute_baseline() {
+    let entries = vec![
+      entry(
+        "line",
+        "Polyline",
+        vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0)],
+      ),
+      entry("count", "Count", vec![Point::new(1.0, 1.0)]),
+    ];
+    let outputs = compute_baseline(&entries).unwrap();
+    assert_eq!(outputs.len(), 2);
+    assert_eq!(outputs[0].id, "line");
+    assert!((outputs[0].length.unwrap() - 12.0).abs() < 1e-9);
+    assert_eq!((outputs[0].area, outputs[0].count), (None, None));
+    assert_eq!(
+      outputs[1],
+      BaselineOutput {
+        id: "count".to_string(),
+        length: None,
+        area: None,
+        count: Some(1.0),
+      }
+    );
+
+    let bad = entry("bad", "Rectangle", vec![Point::new(0.0, 0.0)]);
+    assert!(matches!(
+      compute_baseline(&[bad]),
+      Err(TakeoffError::InvalidInput { .. })
+    ));
+  }
+}
@@ -1,3 +1,4 @@
+use crate::measurement::MeasurementWrapper;
 use crate::state::TakeoffStateHandler;
 use crate::utils::lock_mutex;
 use napi::Result;
@@ -9,7 +10,10 @@ use takeoff_core::coords::{Point, Point3D};
 use takeoff_core::error::TakeoffResult;
 use takeoff_core::scale::Scale;
 use takeoff_core::unit::{Unit, UnitValue};
-use takeoff_core::volume::{ReferenceSurface, ReferenceSurfaceInput, VolumetricResult};
+use takeoff_core::volume::{
+  CutFillGrid, ReferenceSurface, ReferenceSurfaceInput, VolumetricResult,
+};
+use uom::si::f64::{Length, Volume};
 
 // --- NAPI Input Types (JS-facing) ---
 
@@ -37,6 +41,7 @@ pub struct VolumetricUnitResult {
   cut: UnitValue,
   fill: UnitValue,
   uncovered_area: UnitValue,
+  strip: UnitValue,
 }
 
 #[napi]
@@ -55,6 +60,32 @@ impl VolumetricUnitResult {
   pub fn uncovered_area(&self) -> UnitValue {
     self.uncovered_area.clone()
   }
+
+  /// Volume removed by topsoil stripping.
+  #[napi(getter)]
+  pub fn strip(&self) -> UnitValue {
+    self.strip.clone()
+  }
+}
+
+// --- DEM Grid ---
+
+/// Maximum number of cells `get_dem_grid` will sample (a 4096 x 4096 grid).
+pub const MAX_DEM_GRID_CELLS: usize = 4096 * 4096;
+
+/// A regular grid of sampled elevations (digital elevation model).
+///
+/// `values` is row-major: the sample at column `c`, row `r` is `values[r * cols + c]`
+/// and sits at `(origin_x + c * step, origin_y + r * step)`. Cells outside the mesh are `None`.
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DemGrid {
+  pub origin_x: f64,
+  pub origin_y: f64,
+  pub step: i32,
+  pub cols: u32,
+  pub rows: u32,
+  pub values: Vec<Option<f64>>,
 }
 
 // --- ContourWrapper ---
@@ -89,7 +120,9 @@ impl ContourWrapper {
   pub fn set_contour(&self, contour: ContourInput) {
     *lock_mutex(self.contour.lock(), "contour")
       .expect("BUG: contour mutex should not be poisoned") = contour;
-    let _ = self.rebuild_surface_mesh();
+    if let Err(err) = self.rebuild_surface_mesh() {
+      log::debug!("failed to rebuild surface mesh: {}", err);
+    }
   }
 
   /// Set the scale of the contour.
@@ -124,7 +157,7 @@ impl ContourWrapper {
             let _ = self.set_scale(scale.clone());
             return Some(scale);
           }
-        } else {
+        } else if current_scale.is_none() {
           current_scale = Some(scale.clone());
         }
       }
@@ -132,10 +165,19 @@ impl ContourWrapper {
         let _ = self.set_scale(scale.clone());
         return Some(scale);
       }
+      // The page lost its last applicable scale: drop the mesh built from the old one.
+      if let Err(err) = self.clear_scale() {
+        log::debug!("failed to clear contour scale: {}", err);
+      }
     }
     None
   }
 
+  fn clear_scale(&self) -> TakeoffResult<()> {
+    *lock_mutex(self.scale.lock(), "scale")? = None;
+    self.rebuild_surface_mesh()
+  }
+
   fn rebuild_surface_mesh(&self) -> TakeoffResult<()> {
     let scale_guard = lock_mutex(self.scale.lock(), "scale")?;
     if let Some(scale) = scale_guard.as_ref() {
@@ -146,7 +188,8 @@ impl ContourWrapper {
           drop(scale_guard);
           *lock_mutex(self.surface_mesh.lock(), "surface_mesh")? = Some(mesh);
         }
-        Err(_) => {
+        Err(err) => {
+          log::debug!("surface mesh for contour {} not built: {}", contour.id, err);
           drop(contour);
           drop(scale_guard);
           *lock_mutex(self.surface_mesh.lock(), "surface_mesh")? = None;
@@ -185,6 +228,38 @@ impl ContourWrapper {
       .and_then(|s| s.clone())
   }
 
+  /// Get the contour's extent in real-world `unit` as `[min, max]` corners (see
+  /// `ContourInput::bounding_box_in_unit`).
+  ///
+  /// Returns `None` without a scale, and an error if the contour has no points.
+  #[napi]
+  pub fn get_bounding_box_in_unit(&self, unit: Unit) -> Result<Option<(Point, Point)>> {
+    let Some(scale) = self.get_scale() else {
+      return Ok(None);
+    };
+    let contour = lock_mutex(self.contour.lock(), "contour")?;
+    let ((min_x, min_y), (max_x, max_y)) = contour.bounding_box_in_unit(&scale, unit)?;
+    Ok(Some((Point::new(min_x, min_y), Point::new(max_x, max_y))))
+  }
+
+  /// Export the surface mesh as Wavefront OBJ (see `SurfaceMesh::to_obj`).
+  ///
+  /// Returns `None` if the surface mesh has not been built (e.g. no scale).
+  #[napi]
+  pub fn to_obj(&self) -> Option<String> {
+    let mesh_guard = lock_mutex(self.surface_mesh.lock(), "surface_mesh").ok()?;
+    mesh_guard.as_ref().map(SurfaceMesh::to_obj)
+  }
+
+  /// Export the surface mesh as ASCII STL (see `SurfaceMesh::to_stl_ascii`).
+  ///
+  /// Returns `None` if the surface mesh has not been built (e.g. no scale).
+  #[napi]
+  pub fn to_stl_ascii(&self) -> Option<String> {
+    let mesh_guard = lock_mutex(self.surface_mesh.lock(), "surface_mesh").ok()?;
+    mesh_guard.as_ref().map(SurfaceMesh::to_stl_ascii)
+  }
+
   /// Get the surface points of the contour.
   #[napi]
   pub fn get_surface_points(&self) -> Option<Vec<Point3D>> {
@@ -192,6 +267,31 @@ impl ContourWrapper {
     mesh_guard.as_ref().map(|mesh| mesh.vertices.clone())
   }
 
+  /// Get the surface points of the contour after Laplacian smoothing of the elevations.
+  ///
+  /// x/y and the triangulation are unchanged; boundary vertices keep their elevation.
+  /// The stored surface mesh is not modified.
+  #[napi]
+  pub fn get_smoothed_surface_points(&self, iterations: u32, lambda: f64) -> Option<Vec<Point3D>> {
+    let mesh_guard = lock_mutex(self.surface_mesh.lock(), "surface_mesh").ok()?;
+    mesh_guard
+      .as_ref()
+      .map(|mesh| mesh.smoothed(iterations, lambda).vertices)
+  }
+
+  /// Get the surface mesh of the contour restricted to a boundary polygon in pixels, e.g. a
+  /// project boundary (see `SurfaceMesh::clip_to_polygon`).
+  ///
+  /// Vertices where the mesh crosses the boundary are added and the triangles index into the
+  /// returned vertices; the stored surface mesh is not modified.
+  #[napi]
+  pub fn get_clipped_surface_mesh(&self, boundary: Vec<Point>) -> Option<SurfaceMesh> {
+    let mesh_guard = lock_mutex(self.surface_mesh.lock(), "surface_mesh").ok()?;
+    mesh_guard
+      .as_ref()
+      .map(|mesh| mesh.clip_to_polygon(&boundary))
+  }
+
   /// Get the z value at the given x and y coordinates.
   #[napi]
   pub fn get_z_at(&self, x: f64, y: f64) -> Option<f64> {
@@ -200,6 +300,38 @@ impl ContourWrapper {
     mesh.z_at(x, y)
   }
 
+  /// Get the terrain elevation under a measurement's centroid, in the scale's unit, e.g. to
+  /// set a pad elevation to match existing grade.
+  ///
+  /// Returns `None` if the surface mesh or scale is not available, the measurement has no
+  /// centroid, or the centroid is off the mesh.
+  #[napi]
+  pub fn elevation_at_measurement(&self, measurement: &MeasurementWrapper) -> Option<f64> {
+    let centroid = measurement.get_measurement().get_centroid().ok()?;
+    let z = self.get_z_at(centroid.x, centroid.y)?;
+    let scale_guard = lock_mutex(self.scale.lock(), "scale").ok()?;
+    let ratio = scale_guard.as_ref()?.ratio().ok()?;
+    Some(z / ratio)
+  }
+
+  /// Get the over-terrain length of a path draped over the surface, in the scale's unit.
+  ///
+  /// See `SurfaceMesh::drape_length`. Returns `None` if the surface mesh or scale is not available.
+  pub fn drape_length(&self, points: &[Point]) -> Option<Length> {
+    let mesh_guard = lock_mutex(self.surface_mesh.lock(), "surface_mesh").ok()?;
+    let mesh = mesh_guard.as_ref()?;
+    let scale_guard = lock_mutex(self.scale.lock(), "scale").ok()?;
+    let scale = scale_guard.as_ref()?;
+    let ratio = scale.ratio().ok()?;
+    Some(scale.get_unit().get_unit(mesh.drape_length(points) / ratio))
+  }
+
+  /// See `SurfaceMesh::drape_slopes`. Returns `None` if the surface mesh is not available.
+  pub fn drape_slopes(&self, points: &[Point]) -> Option<Vec<Option<f64>>> {
+    let mesh_guard = lock_mutex(self.surface_mesh.lock(), "surface_mesh").ok()?;
+    Some(mesh_guard.as_ref()?.drape_slopes(points))
+  }
+
   /// Get the scatter data of the contour.
   #[napi]
   pub fn get_scatter_data(&self, step: i32) -> Option<Vec<Point3D>> {
@@ -234,6 +366,62 @@ impl ContourWrapper {
     Some(data)
   }
 
+  /// Get the elevations of the contour sampled on a full rectangular grid.
+  ///
+  /// Uses the same integer sampling as `get_scatter_data`, but keeps uncovered cells as
+  /// `None` so the grid can be rendered directly (e.g. as a heatmap).
+  /// Returns `None` if `step` is not positive, the surface mesh is not available, the
+  /// bounds do not fit in `i32`, or the grid would exceed [`MAX_DEM_GRID_CELLS`].
+  #[napi]
+  pub fn get_dem_grid(&self, step: i32) -> Option<DemGrid> {
+    if step <= 0 {
+      return None;
+    }
+
+    let contour = lock_mutex(self.contour.lock(), "contour").ok()?;
+    let bounding_box = contour.bounding_box()?;
+    drop(contour);
+
+    let mesh_guard = lock_mutex(self.surface_mesh.lock(), "surface_mesh").ok()?;
+    let surface_mesh = mesh_guard.as_ref()?;
+
+    let (min_x, min_y) = bounding_box.0;
+    let (max_x, max_y) = bounding_box.1;
+
+    let to_i32 =
+      |value: f64| (value >= i32::MIN as f64 && value <= i32::MAX as f64).then_some(value as i32);
+    let x_start = i64::from(to_i32(min_x.floor())?);
+    let x_end = i64::from(to_i32(max_x.ceil())?);
+    let y_start = i64::from(to_i32(min_y.floor())?);
+    let y_end = i64::from(to_i32(max_y.ceil())?);
+    let step_i64 = i64::from(step);
+
+    let cols = usize::try_from((x_end - x_start) / step_i64 + 1).ok()?;
+    let rows = usize::try_from((y_end - y_start) / step_i64 + 1).ok()?;
+    let cells = cols.checked_mul(rows)?;
+    if cells > MAX_DEM_GRID_CELLS {
+      return None;
+    }
+
+    let mut values = Vec::with_capacity(cells);
+    for row in 0..rows as i64 {
+      let y = (y_start + row * step_i64) as f64;
+      for col in 0..cols as i64 {
+        let x = (x_start + col * step_i64) as f64;
+        values.push(surface_mesh.z_at(x, y));
+      }
+    }
+
+    Some(DemGrid {
+      origin_x: x_start as f64,
+      origin_y: y_start as f64,
+      step,
+      cols: cols as u32,
+      rows: rows as u32,
+      values,
+    })
+  }
+
   /// Get scaled scatter data of the contour.
   #[napi]
   pub fn get_real_world_scatter_data(&self, step: i32) -> Option<Vec<Point3D>> {
@@ -250,48 +438,154 @@ impl ContourWrapper {
   }
 
   /// Compute raw cut/fill volume (pixel-space values) against a reference surface.
+  ///
+  /// `strip_depth` (pixel-space) lowers the terrain by a uniform topsoil strip before comparing.
+  /// Returns None if the surface mesh is not available, and an error if the reference polygon
+  /// has no area.
   #[napi]
   pub fn raw_volume_against(
     &self,
     reference: ReferenceSurfaceInput,
     cell_size: Option<f64>,
-  ) -> Option<VolumetricResult> {
+    strip_depth: Option<f64>,
+  ) -> Result<Option<VolumetricResult>> {
+    let mesh_guard = lock_mutex(self.surface_mesh.lock(), "surface_mesh")?;
+    let Some(mesh) = mesh_guard.as_ref() else {
+      return Ok(None);
+    };
+    let reference = ReferenceSurface::from(reference);
+    Ok(Some(mesh.volume_against(
+      &reference,
+      cell_size,
+      strip_depth,
+    )?))
+  }
+
+  /// Sample terrain minus reference elevation per cell for a cut/fill heatmap (pixel-space,
+  /// like `raw_volume_against`). Returns None if the surface mesh is not available.
+  #[napi]
+  pub fn cut_fill_grid(
+    &self,
+    reference: ReferenceSurfaceInput,
+    cell_size: f64,
+  ) -> Option<CutFillGrid> {
     let mesh_guard = lock_mutex(self.surface_mesh.lock(), "surface_mesh").ok()?;
     let mesh = mesh_guard.as_ref()?;
-    let reference = ReferenceSurface::from(reference);
-    Some(mesh.volume_against(&reference, cell_size))
+    Some(mesh.cut_fill_grid(&ReferenceSurface::from(reference), cell_size))
   }
 
   /// Compute unit-aware cut/fill volume against a reference surface.
-  /// Returns None if surface mesh or scale is not available.
+  /// Returns None if surface mesh or scale is not available, and an error if the reference
+  /// polygon has no area (a degenerate pad, as opposed to one level with the terrain).
+  ///
+  /// `strip_depth` (pixel-space) lowers the terrain by a uniform topsoil strip before comparing.
   #[napi]
   pub fn volume_against(
     &self,
     reference: ReferenceSurfaceInput,
     cell_size: Option<f64>,
-  ) -> Option<VolumetricUnitResult> {
-    let mesh_guard = lock_mutex(self.surface_mesh.lock(), "surface_mesh").ok()?;
-    let mesh = mesh_guard.as_ref()?;
-    let scale_guard = lock_mutex(self.scale.lock(), "scale").ok()?;
-    let scale = scale_guard.as_ref()?;
+    strip_depth: Option<f64>,
+  ) -> Result<Option<VolumetricUnitResult>> {
+    Ok(self.volume_against_surface(&ReferenceSurface::from(reference), cell_size, strip_depth)?)
+  }
 
-    let reference_surface = ReferenceSurface::from(reference);
-    let raw = mesh.volume_against(&reference_surface, cell_size);
+  /// Compute unit-aware cut/fill volume using a stored measurement as the reference footprint.
+  ///
+  /// The polygon or rectangle measurement with `measurement_id` is extruded at `elevation`
+  /// (pixel-space, like `ReferenceSurfaceInput`).
+  /// Returns `None` if the measurement is not found or the surface mesh or scale is not available.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the measurement is a polyline or count, or its geometry is invalid or
+  /// has no area.
+  #[napi]
+  pub fn volume_against_measurement(
+    &self,
+    measurement_id: String,
+    elevation: f64,
+    cell_size: Option<f64>,
+    strip_depth: Option<f64>,
+  ) -> Result<Option<VolumetricUnitResult>> {
+    let Some(measurement) = self
+      .state
+      .upgrade()
+      .and_then(|state| state.get_measurement(measurement_id))
+    else {
+      return Ok(None);
+    };
+    let reference = ReferenceSurface::from_measurement(&measurement.get_measurement(), elevation)?;
+    Ok(self.volume_against_surface(&reference, cell_size, strip_depth)?)
+  }
 
-    let ratio = scale.ratio().ok()?;
-    let unit = scale.get_unit();
+  /// Compute the net earthwork volume (`fill - cut`, in real-world units) against a reference
+  /// surface. Negative values mean more material is cut than filled.
+  ///
+  /// Returns `None` if the surface mesh or scale is not available, and an error if the
+  /// reference polygon has no area.
+  pub fn net_volume_against(
+    &self,
+    reference_surface: &ReferenceSurface,
+    cell_size: Option<f64>,
+    strip_depth: Option<f64>,
+  ) -> TakeoffResult<Option<Volume>> {
+    let Some((raw, ratio, unit)) =
+      self.raw_volume_with_scale(reference_surface, cell_size, strip_depth)?
+    else {
+      return Ok(None);
+    };
+    let net_real = (raw.fill - raw.cut) / (ratio * ratio * ratio);
+    Ok(Some(unit.get_volume_unit(net_real)))
+  }
+
+  fn volume_against_surface(
+    &self,
+    reference_surface: &ReferenceSurface,
+    cell_size: Option<f64>,
+    strip_depth: Option<f64>,
+  ) -> TakeoffResult<Option<VolumetricUnitResult>> {
+    let Some((raw, ratio, unit)) =
+      self.raw_volume_with_scale(reference_surface, cell_size, strip_depth)?
+    else {
+      return Ok(None);
+    };
 
     // Raw volume is in cubic pixels. Convert: real_volume = raw_volume / ratio^3
     let ratio_cubed = ratio * ratio * ratio;
     let cut_real = raw.cut / ratio_cubed;
     let fill_real = raw.fill / ratio_cubed;
     let uncovered_area_real = raw.uncovered_area / (ratio * ratio);
+    let strip_real = raw.strip_volume / ratio_cubed;
+
+    Ok(Some(VolumetricUnitResult {
+      cut: UnitValue::from_volume(unit.get_volume_unit(cut_real)),
+      fill: UnitValue::from_volume(unit.get_volume_unit(fill_real)),
+      uncovered_area: UnitValue::from_area(unit.get_area_unit(uncovered_area_real)),
+      strip: UnitValue::from_volume(unit.get_volume_unit(strip_real)),
+    }))
+  }
 
-    Some(VolumetricUnitResult {
-      cut: UnitValue::from_volume(unit.get_volume_unit(cut_real as f32)),
-      fill: UnitValue::from_volume(unit.get_volume_unit(fill_real as f32)),
-      uncovered_area: UnitValue::from_area(unit.get_area_unit(uncovered_area_real as f32)),
-    })
+  /// Raw (pixel-space) volume result together with the scale ratio and unit to convert it.
+  fn raw_volume_with_scale(
+    &self,
+    reference_surface: &ReferenceSurface,
+    cell_size: Option<f64>,
+    strip_depth: Option<f64>,
+  ) -> TakeoffResult<Option<(VolumetricResult, f64, Unit)>> {
+    let mesh_guard = lock_mutex(self.surface_mesh.lock(), "surface_mesh")?;
+    let Some(mesh) = mesh_guard.as_ref() else {
+      return Ok(None);
+    };
+    let scale_guard = lock_mutex(self.scale.lock(), "scale")?;
+    let Some(scale) = scale_guard.as_ref() else {
+      return Ok(None);
+    };
+    let Ok(ratio) = scale.ratio() else {
+      return Ok(None);
+    };
+
+    let raw = mesh.volume_against(reference_surface, cell_size, strip_depth)?;
+    Ok(Some((raw, ratio, scale.get_unit())))
   }
 }
 
@@ -300,6 +594,7 @@ mod tests {
   use super::*;
   use takeoff_core::contour::ContourLineInput;
   use takeoff_core::coords::Point;
+  use takeoff_core::measurement::Measurement;
   use takeoff_core::scale::{Scale, ScaleDefinition};
   use takeoff_core::unit::Unit;
 
@@ -342,10 +637,67 @@ mod tests {
     assert!(wrapper.get_z_at(50.0, 50.0).is_none());
   }
 
+  #[test]
+  fn test_volume_against_degenerate_reference() {
+    let wrapper = ContourWrapper::new(test_contour_input());
+    wrapper.set_scale(test_scale()).unwrap();
+    let collinear = ReferenceSurfaceInput::Polygon {
+      points: vec![
+        Point::new(10.0, 10.0),
+        Point::new(50.0, 50.0),
+        Point::new(90.0, 90.0),
+      ],
+      elevation: 10.0,
+    };
+    assert!(wrapper.volume_against(collinear, None, None).is_err());
+
+    let level = ReferenceSurfaceInput::Rectangle {
+      points: (Point::new(25.0, 25.0), Point::new(75.0, 75.0)),
+      elevation: 10.0,
+    };
+    let result = wrapper.volume_against(level, None, None).unwrap().unwrap();
+    assert!(result.cut().get_converted_value(Unit::Feet).abs() < 1e-6);
+  }
+
+  #[test]
+  fn test_elevation_at_measurement() {
+    let wrapper = ContourWrapper::new(test_contour_input());
+    let pad = |start: Point, end: Point| {
+      MeasurementWrapper::default(Measurement::Rectangle {
+        id: "m1".to_string(),
+        page_id: "p1".to_string(),
+        group_id: "g1".to_string(),
+        points: (start, end),
+        rotation: None,
+        metadata: None,
+      })
+    };
+    let inside = pad(Point::new(25.0, 25.0), Point::new(75.0, 75.0));
+    assert_eq!(wrapper.elevation_at_measurement(&inside), None);
+
+    // 2 px per foot: the 10 ft surface is 20 px high in the mesh.
+    wrapper
+      .set_scale(Scale::Default {
+        id: "s1".to_string(),
+        page_id: "p1".to_string(),
+        scale: ScaleDefinition {
+          pixel_distance: 2.0,
+          real_distance: 1.0,
+          unit: Unit::Feet,
+        },
+      })
+      .unwrap();
+    let elevation = wrapper.elevation_at_measurement(&inside).unwrap();
+    assert!((elevation - 10.0).abs() < 1e-6);
+
+    let outside = pad(Point::new(200.0, 200.0), Point::new(300.0, 300.0));
+    assert_eq!(wrapper.elevation_at_measurement(&outside), None);
+  }
+
   #[test]
   fn test_contour_wrapper_with_scale() {
     let wrapper = ContourWrapper::new(test_contour_input());
-    wrapper.set_scale(test_scale());
+    wrapper.set_scale(test_scale()).unwrap();
     let points = wrapper.get_surface_points();
     assert!(points.is_some());
     let points = points.unwrap();
@@ -357,9 +709,43 @@ mod tests {
   #[test]
   fn test_contour_wrapper_scatter_data_with_scale() {
     let wrapper = ContourWrapper::new(test_contour_input());
-    wrapper.set_scale(test_scale());
+    wrapper.set_scale(test_scale()).unwrap();
     let scatter = wrapper.get_scatter_data(10);
     assert!(scatter.is_some());
     assert!(!scatter.unwrap().is_empty());
   }
+
+  #[test]
+  fn test_contour_wrapper_dem_grid() {
+    let wrapper = ContourWrapper::new(test_contour_input());
+    assert!(wrapper.get_dem_grid(10).is_none());
+
+    wrapper.set_scale(test_scale()).unwrap();
+    assert!(wrapper.get_dem_grid(0).is_none());
+
+    let grid = wrapper.get_dem_grid(10).unwrap();
+    assert_eq!((grid.origin_x, grid.origin_y), (0.0, 0.0));
+    assert_eq!((grid.cols, grid.rows), (11, 11));
+    assert_eq!(grid.values.len(), 121);
+    // Centre of the square is inside the mesh; flat terrain at 10 ft with a 1:1 scale.
+    let centre = grid.values[5 * 11 + 5].unwrap();
+    assert!((centre - 10.0).abs() < 1e-6);
+  }
+
+  #[test]
+  fn test_contour_wrapper_dem_grid_too_large() {
+    let mut input = test_contour_input();
+    input.lines[0].points = vec![
+      Point::new(0.0, 0.0),
+      Point::new(100_000.0, 0.0),
+      Point::new(100_000.0, 100_000.0),
+      Point::new(0.0, 100_000.0),
+    ];
+    let wrapper = ContourWrapper::new(input);
+    wrapper.set_scale(test_scale()).unwrap();
+    // 100001 x 100001 cells at step 1 is well beyond the cap; a coarser step fits.
+    assert!(wrapper.get_dem_grid(1).is_none());
+    let grid = wrapper.get_dem_grid(100).unwrap();
+    assert_eq!((grid.cols, grid.rows), (1001, 1001));
+  }
 }
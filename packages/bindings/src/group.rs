@@ -1,13 +1,36 @@
 use crate::measurement::MeasurementWrapper;
 use crate::state::TakeoffStateHandler;
 use crate::utils::lock_mutex;
-use anyhow::Result;
+use geo::{unary_union, Area as _, Coord, MapCoords, MultiPolygon, Polygon};
+use napi::Result;
 use napi_derive::napi;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, Weak};
 use takeoff_core::error::TakeoffResult;
-use takeoff_core::group::Group;
-use takeoff_core::unit::UnitValue;
-use uom::si::f32::{Area, Length};
+use takeoff_core::group::{Group, MeasurementType};
+use takeoff_core::scale::Scale;
+use takeoff_core::unit::{Unit, UnitValue};
+use takeoff_core::volume::ReferenceSurface;
+use uom::si::area::square_meter;
+use uom::si::f64::{Area, Length, Volume};
+use uom::si::length::meter;
+
+/// Group totals computed on demand, see `GroupWrapper::preview_totals`.
+#[napi(object, object_from_js = false)]
+pub struct GroupSummary {
+  pub area: Option<UnitValue>,
+  pub length: Option<UnitValue>,
+  pub points: Option<f64>,
+  pub count: Option<f64>,
+}
+
+/// One bin of `GroupWrapper::area_histogram`, with bounds in the requested unit.
+#[napi(object, object_from_js = false)]
+pub struct HistogramBin {
+  pub lower: f64,
+  pub upper: f64,
+  pub count: u32,
+}
 
 #[napi]
 #[derive(Debug, Clone)]
@@ -17,6 +40,9 @@ pub struct GroupWrapper {
   length: Arc<Mutex<Option<Length>>>,
   points: Arc<Mutex<Option<f64>>>,
   count: Arc<Mutex<Option<f64>>>,
+  volume: Arc<Mutex<Option<Volume>>>,
+  /// Set for lazily loaded groups until a total is first read.
+  pending: Arc<AtomicBool>,
 
   // #[serde(skip)]
   state: Weak<TakeoffStateHandler>,
@@ -32,12 +58,38 @@ impl GroupWrapper {
       length: Arc::new(Mutex::new(None)),
       points: Arc::new(Mutex::new(None)),
       count: Arc::new(Mutex::new(None)),
+      volume: Arc::new(Mutex::new(None)),
+      pending: Arc::new(AtomicBool::new(false)),
     };
     let _ = res.recompute_measurements();
     res
   }
 
-  fn calculate_area(&self, measurements: &[MeasurementWrapper]) -> Result<Option<Area>> {
+  /// Create a wrapper whose totals are computed when first read instead of up front.
+  pub fn new_lazy(group: Group, state: Arc<TakeoffStateHandler>) -> Self {
+    Self {
+      group,
+      state: Arc::downgrade(&state),
+      area: Arc::new(Mutex::new(None)),
+      length: Arc::new(Mutex::new(None)),
+      points: Arc::new(Mutex::new(None)),
+      count: Arc::new(Mutex::new(None)),
+      volume: Arc::new(Mutex::new(None)),
+      pending: Arc::new(AtomicBool::new(true)),
+    }
+  }
+
+  /// Compute the totals of a lazily loaded group, once.
+  fn ensure_computed(&self) {
+    if self.pending.load(Ordering::SeqCst) {
+      let _ = self.recompute_measurements();
+    }
+  }
+
+  fn calculate_area(&self, measurements: &[MeasurementWrapper]) -> TakeoffResult<Option<Area>> {
+    if measurements.is_empty() {
+      return Ok(Some(Area::new::<square_meter>(0.0)));
+    }
     let area = measurements
       .iter()
       .filter_map(|measurement| measurement.get_area_value().unwrap_or(None))
@@ -46,6 +98,9 @@ impl GroupWrapper {
   }
 
   fn calculate_length(&self, measurements: &[MeasurementWrapper]) -> TakeoffResult<Option<Length>> {
+    if measurements.is_empty() {
+      return Ok(Some(Length::new::<meter>(0.0)));
+    }
     let mut length_opt = None;
     for measurement in measurements {
       if let Ok(Some(length)) = measurement.get_length_value() {
@@ -70,6 +125,36 @@ impl GroupWrapper {
     Some(measurements.len() as f64)
   }
 
+  /// Sum the net volumes of the state's volume computations for this group.
+  ///
+  /// Returns `None` if the group has no computations, or if any computation's contour is
+  /// missing or has no surface mesh or scale yet, so a partial sum is never reported as the
+  /// total.
+  ///
+  /// # Errors
+  ///
+  /// Returns the first failing computation's error (e.g. a reference polygon with no area).
+  fn calculate_volume(&self, state: &TakeoffStateHandler) -> TakeoffResult<Option<Volume>> {
+    let mut total: Option<Volume> = None;
+    for volume in state.get_volumes_by_group_id(self.id().to_string()) {
+      let Some(contour) = state.get_contour(volume.contour_id.clone()) else {
+        return Ok(None);
+      };
+      let net = contour
+        .net_volume_against(
+          &ReferenceSurface::from(volume.reference),
+          volume.cell_size,
+          volume.strip_depth,
+        )
+        .map_err(|err| err.with_context(format!("volume {}", volume.id)))?;
+      let Some(net) = net else {
+        return Ok(None);
+      };
+      total = Some(total.map_or(net, |acc| acc + net));
+    }
+    Ok(total)
+  }
+
   /// Recompute all measurements for this group.
   ///
   /// # Errors
@@ -78,7 +163,15 @@ impl GroupWrapper {
   /// - Mutex lock fails (poisoned mutex)
   /// - Area calculation fails
   /// - Length calculation fails
-  pub fn recompute_measurements(&self) -> Result<()> {
+  /// - A volume computation fails
+  pub fn recompute_measurements(&self) -> TakeoffResult<()> {
+    self.pending.store(false, Ordering::SeqCst);
+    self
+      .recompute_totals()
+      .map_err(|err| err.with_context(format!("group {}", self.id())))
+  }
+
+  fn recompute_totals(&self) -> TakeoffResult<()> {
     if let Some(state) = self.state.upgrade() {
       let measurements = state.get_measurements_by_group_id(self.id().to_string());
 
@@ -97,6 +190,13 @@ impl GroupWrapper {
       {
         *lock_mutex(self.count.lock(), "count")? = self.calculate_count(&measurements);
       }
+
+      {
+        // A failed computation clears the total before the error is raised.
+        let volume = self.calculate_volume(&state);
+        *lock_mutex(self.volume.lock(), "volume")? = volume.as_ref().ok().copied().flatten();
+        volume?;
+      }
     }
     Ok(())
   }
@@ -107,27 +207,216 @@ impl GroupWrapper {
     &self.group.id
   }
 
+  #[napi]
+  /// Whether the group has no measurements.
+  ///
+  /// Use this to tell an empty group apart from one whose members sum to zero; both report
+  /// a zero `area` and `length`.
+  pub fn is_empty(&self) -> bool {
+    self.state.upgrade().is_none_or(|state| {
+      state
+        .get_measurements_by_group_id(self.id().to_string())
+        .is_empty()
+    })
+  }
+
   #[napi(getter)]
   /// Get the area for this group.
   ///
-  /// Returns `None` if the area has not been computed or if the mutex is poisoned.
+  /// - A zero area if the group is empty (see `is_empty`).
+  /// - The sum of the members' areas once computed; members without an area (no scale,
+  ///   polylines, counts) are skipped.
+  /// - `None` if no member has an area, the totals have not been computed or the mutex is
+  ///   poisoned.
+  ///
+  /// The group's `unit_override` is the value's preferred unit (see `UnitValue::get_value_in`).
   pub fn get_area(&self) -> Option<UnitValue> {
+    self.ensure_computed();
     if let Ok(area) = self.area.lock() {
       if let Some(area) = area.as_ref() {
-        return Some(UnitValue::from_area(*area));
+        return Some(UnitValue::from_area(*area).with_preferred_unit(self.group.unit_override));
       }
     }
     None
   }
 
+  #[napi]
+  /// Get the area for this group with overlapping members counted once.
+  ///
+  /// `area` adds member areas together, which is right when overlap is intentional (e.g. two
+  /// coats over the same wall). This instead unions the member polygons before measuring, so
+  /// overlapping paint or flooring regions are not double counted. Members without a scale or
+  /// without an area (polylines, counts) are skipped.
+  ///
+  /// Returns `None` if no member contributes an area.
+  pub fn get_net_area(&self) -> Option<UnitValue> {
+    let merged = self.merged_polygons_in_meters()?;
+    Some(UnitValue::from_area(
+      Unit::Meters.get_area_unit(merged.unsigned_area()),
+    ))
+  }
+
+  #[napi]
+  /// Get the length of the outer boundary of this group's merged polygons.
+  ///
+  /// `length` adds member perimeters together, so a wall shared by two adjoining rooms counts
+  /// twice. This instead unions the member polygons and measures only the exterior rings of
+  /// the result, e.g. the total exterior wall of joined rooms. Holes in the merged region are
+  /// not included. Members are skipped as for `get_net_area`.
+  ///
+  /// Returns `None` if no member contributes an area.
+  pub fn get_outer_perimeter(&self) -> Option<UnitValue> {
+    let merged = self.merged_polygons_in_meters()?;
+    let perimeter: f64 = merged
+      .iter()
+      .flat_map(|polygon| polygon.exterior().lines())
+      .map(|line| line.dx().hypot(line.dy()))
+      .sum();
+    Some(UnitValue::from_length(Unit::Meters.get_unit(perimeter)))
+  }
+
+  #[napi]
+  /// Compute this group's totals as if every member used `override_scale`, e.g. to preview a
+  /// scale change before committing it.
+  ///
+  /// Nothing is written: the cached group and member totals are left as they are. With
+  /// `None`, each member's current scale is used. Members that cannot be measured are skipped
+  /// as in `area` and `length`, and an empty group reports zero area and length.
+  pub fn preview_totals(&self, override_scale: Option<Scale>) -> Result<GroupSummary> {
+    let measurements = self
+      .state
+      .upgrade()
+      .map(|state| state.get_measurements_by_group_id(self.id().to_string()))
+      .unwrap_or_default();
+    let scale_for =
+      |measurement: &MeasurementWrapper| override_scale.clone().or_else(|| measurement.get_scale());
+
+    let mut area = None;
+    let mut length = None;
+    if measurements.is_empty() {
+      area = Some(Area::new::<square_meter>(0.0));
+      length = Some(Length::new::<meter>(0.0));
+    }
+    for measurement in &measurements {
+      let Some(scale) = scale_for(measurement) else {
+        continue;
+      };
+      if let Ok(value) = measurement.area_with_scale(&scale) {
+        area = Some(area.map_or(value, |acc| acc + value));
+      }
+      if let Ok(value) = measurement.length_with_scale(&scale) {
+        length = Some(length.map_or(value, |acc| acc + value));
+      }
+    }
+
+    Ok(GroupSummary {
+      area: area.map(UnitValue::from_area),
+      length: length.map(UnitValue::from_length),
+      points: self.calculate_points(&measurements),
+      count: self.calculate_count(&measurements),
+    })
+  }
+
+  #[napi]
+  /// Get the combined pixel extent `((min_x, min_y), (max_x, max_y))` of the members'
+  /// bounding boxes, e.g. to zoom to the group.
+  ///
+  /// Returns `None` if the group has no measurements (or none with points).
+  pub fn extent(&self) -> Option<((f64, f64), (f64, f64))> {
+    let state = self.state.upgrade()?;
+    state
+      .get_measurements_by_group_id(self.id().to_string())
+      .iter()
+      .filter_map(|measurement| measurement.get_measurement().bounding_box())
+      .reduce(
+        |((a_min_x, a_min_y), (a_max_x, a_max_y)), ((b_min_x, b_min_y), (b_max_x, b_max_y))| {
+          (
+            (a_min_x.min(b_min_x), a_min_y.min(b_min_y)),
+            (a_max_x.max(b_max_x), a_max_y.max(b_max_y)),
+          )
+        },
+      )
+  }
+
+  #[napi]
+  /// Distribution of member areas in `unit`, e.g. to spot outliers during review.
+  ///
+  /// Areas are split into `bins` equal-width bins from the smallest to the largest area; the
+  /// last bin includes its upper bound. When all areas are equal a single bin is returned.
+  /// Members without a scale or without an area (polylines, counts) are skipped, and a group
+  /// with no areas (or `bins` of 0) gives no bins.
+  pub fn area_histogram(&self, bins: u32, unit: Unit) -> Vec<HistogramBin> {
+    let areas: Vec<f64> = self
+      .state
+      .upgrade()
+      .map(|state| state.get_measurements_by_group_id(self.id().to_string()))
+      .unwrap_or_default()
+      .iter()
+      .filter_map(|measurement| measurement.get_area_value().ok().flatten())
+      .map(|area| unit.convert_area_to_unit(area))
+      .collect();
+    if areas.is_empty() || bins == 0 {
+      return vec![];
+    }
+    let min = areas.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = areas.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if min == max {
+      return vec![HistogramBin {
+        lower: min,
+        upper: max,
+        count: areas.len() as u32,
+      }];
+    }
+
+    let width = (max - min) / bins as f64;
+    let mut counts = vec![0u32; bins as usize];
+    for area in areas {
+      let index = (((area - min) / width) as usize).min(counts.len() - 1);
+      counts[index] += 1;
+    }
+    counts
+      .into_iter()
+      .enumerate()
+      .map(|(i, count)| HistogramBin {
+        lower: min + width * i as f64,
+        upper: if i + 1 == bins as usize {
+          max
+        } else {
+          min + width * (i + 1) as f64
+        },
+        count,
+      })
+      .collect()
+  }
+
+  /// The union of the members' polygons in real-world meters, or `None` if no member has one.
+  fn merged_polygons_in_meters(&self) -> Option<MultiPolygon<f64>> {
+    let state = self.state.upgrade()?;
+    let polygons: Vec<Polygon<f64>> = state
+      .get_measurements_by_group_id(self.id().to_string())
+      .iter()
+      .filter_map(real_polygon_in_meters)
+      .collect();
+    if polygons.is_empty() {
+      return None;
+    }
+    Some(unary_union(&polygons))
+  }
+
   #[napi(getter)]
   /// Get the length for this group.
   ///
-  /// Returns `None` if the length has not been computed or if the mutex is poisoned.
+  /// - A zero length if the group is empty (see `is_empty`).
+  /// - The sum of the members' lengths once computed; members without a scale are skipped.
+  /// - `None` if no member has a length, the totals have not been computed or the mutex is
+  ///   poisoned.
+  ///
+  /// The group's `unit_override` is the value's preferred unit (see `UnitValue::get_value_in`).
   pub fn get_length(&self) -> Option<UnitValue> {
+    self.ensure_computed();
     if let Ok(length) = lock_mutex(self.length.lock(), "length") {
       if let Some(length) = length.as_ref() {
-        return Some(UnitValue::from_length(*length));
+        return Some(UnitValue::from_length(*length).with_preferred_unit(self.group.unit_override));
       }
     }
     None
@@ -138,6 +427,7 @@ impl GroupWrapper {
   ///
   /// Returns `None` if the points count has not been computed or if the mutex is poisoned.
   pub fn get_points(&self) -> Option<f64> {
+    self.ensure_computed();
     lock_mutex(self.points.lock(), "points")
       .ok()
       .and_then(|p| *p)
@@ -148,11 +438,80 @@ impl GroupWrapper {
   ///
   /// Returns `None` if the count has not been computed or if the mutex is poisoned.
   pub fn get_count(&self) -> Option<f64> {
+    self.ensure_computed();
     lock_mutex(self.count.lock(), "count").ok().and_then(|c| *c)
   }
 
+  #[napi(getter)]
+  /// Get the net volume (`fill - cut`) for this group.
+  ///
+  /// Returns `None` if no volume has been computed or if the mutex is poisoned.
+  pub fn get_volume(&self) -> Option<UnitValue> {
+    self.ensure_computed();
+    lock_mutex(self.volume.lock(), "volume")
+      .ok()
+      .and_then(|volume| *volume)
+      .map(UnitValue::from_volume)
+  }
+
+  #[napi(getter)]
+  /// Get the value matching the group's measurement type: area, length or volume.
+  ///
+  /// Returns `None` for count groups (use `count`) or if the value has not been computed.
+  pub fn primary_value(&self) -> Option<UnitValue> {
+    match self.group.measurement_type {
+      MeasurementType::Area => self.get_area(),
+      MeasurementType::Linear => self.get_length(),
+      MeasurementType::Count => None,
+      MeasurementType::Volume => self.get_volume(),
+    }
+  }
+
+  #[napi(getter)]
+  /// Get the display color of the group.
+  pub fn color(&self) -> Option<String> {
+    self.group.color.clone()
+  }
+
+  #[napi(getter)]
+  /// Get the unit the group's totals are displayed in, if overridden.
+  pub fn unit_override(&self) -> Option<Unit> {
+    self.group.unit_override
+  }
+
+  #[napi]
+  /// Get the area for this group converted to a unit.
+  ///
+  /// Without `unit` the group's `unit_override` is used.
+  /// Returns `None` if the area has not been computed or no unit is available.
+  pub fn get_converted_area(&self, unit: Option<Unit>) -> Option<f64> {
+    self.get_area()?.get_value_in(unit)
+  }
+
+  #[napi]
+  /// Get the length for this group converted to a unit.
+  ///
+  /// Without `unit` the group's `unit_override` is used.
+  /// Returns `None` if the length has not been computed or no unit is available.
+  pub fn get_converted_length(&self, unit: Option<Unit>) -> Option<f64> {
+    self.get_length()?.get_value_in(unit)
+  }
+
   #[napi(getter)]
   pub fn get_group(&self) -> Group {
     self.group.clone()
   }
 }
+
+/// The measurement's polygon in real-world meters, so members with different scales can be merged.
+fn real_polygon_in_meters(measurement: &MeasurementWrapper) -> Option<Polygon<f64>> {
+  let scale = measurement.get_scale()?;
+  let ratio = scale.ratio().ok()?;
+  let meters_per_unit = scale.get_unit().get_unit(1.0).get::<meter>();
+  let polygon = measurement.get_measurement().to_polygon().ok()?;
+  let factor = meters_per_unit / ratio;
+  Some(polygon.map_coords(|c| Coord {
+    x: c.x * factor,
+    y: c.y * factor,
+  }))
+}
@@ -1,5 +1,6 @@
 #![deny(clippy::all)]
 
+pub mod baseline;
 pub mod contour;
 pub mod group;
 pub mod measurement;
@@ -1,11 +1,19 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, Weak};
 
 use napi_derive::napi;
-use takeoff_core::error::TakeoffResult;
+use serde::{Deserialize, Serialize};
+use takeoff_core::coords::Point;
+use takeoff_core::error::{TakeoffError, TakeoffResult};
+use takeoff_core::group::MeasurementType;
+use takeoff_core::measurement::{linear_volume, ShapeStats};
+use takeoff_core::page::CoordinateSystem;
 use takeoff_core::scale::Scale;
 use takeoff_core::unit::UnitValue;
 use takeoff_core::{measurement::Measurement, unit::Unit};
-use uom::si::f32::{Area, Length};
+use uom::si::area::{hectare, square_foot, square_meter};
+use uom::si::f64::{Area, Length};
 
 use crate::state::TakeoffStateHandler;
 
@@ -13,15 +21,60 @@ use napi::Result;
 
 use crate::utils::lock_mutex;
 
+/// Square feet in an acre.
+const SQUARE_FEET_PER_ACRE: f64 = 43_560.0;
+
+/// A self-describing snapshot of a measurement for export to other systems.
+///
+/// Pixel geometry is only meaningful together with its scale, so both are included along with
+/// the real-world values they produce. `area` and `length` are in the scale's `unit`.
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MeasurementExport {
+  pub measurement: Measurement,
+  pub page_id: String,
+  /// The scale resolved for the measurement, if any.
+  pub scale: Option<Scale>,
+  /// Pixels per real-world unit of `scale`.
+  pub ratio: Option<f64>,
+  pub unit: Option<Unit>,
+  /// Real-world area; `None` without a scale or for polylines and counts.
+  pub area: Option<f64>,
+  /// Real-world length (perimeter for polygons and rectangles); `None` without a scale.
+  pub length: Option<f64>,
+}
+
+/// The smallest circle enclosing a measurement (see `Measurement::min_enclosing_circle`).
+#[napi(object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnclosingCircle {
+  /// Center in pixels.
+  pub center: Point,
+  /// Radius in the requested unit.
+  pub radius: f64,
+}
+
+/// Smallest pixels-per-unit ratio accepted when converting pixel areas to real areas.
+const MIN_AREA_SCALE_RATIO: f64 = 1e-9;
+
 #[napi]
 #[derive(Debug, Clone)]
 pub struct MeasurementWrapper {
   measurement: Arc<Mutex<Measurement>>,
 
   scale: Arc<Mutex<Option<Scale>>>,
+  /// Scale pinned by the caller; while set, page scales are not auto-detected.
+  scale_override: Arc<Mutex<Option<Scale>>>,
+  /// Set for lazily loaded measurements until the scale is first needed.
+  scale_pending: Arc<AtomicBool>,
+  /// Set while the scale is the state's `default_scale` rather than a page scale.
+  using_fallback_scale: Arc<AtomicBool>,
+  /// Stacking order for hit-testing; higher is on top.
+  z_index: Arc<Mutex<Option<i32>>>,
+  /// Position within the group for reports; see `TakeoffStateHandler::reorder_measurement`.
+  order: Arc<Mutex<Option<u32>>>,
   area: Arc<Mutex<Option<Area>>>,
   length: Arc<Mutex<Option<Length>>>,
-  points: f64,
 
   // #[serde(skip)]
   state: Weak<TakeoffStateHandler>,
@@ -30,22 +83,27 @@ pub struct MeasurementWrapper {
 #[napi]
 impl MeasurementWrapper {
   pub fn new(measurement: Measurement, state: Arc<TakeoffStateHandler>) -> Self {
-    let points = match measurement.clone() {
-      Measurement::Count { .. } => 1,
-      Measurement::Polygon { points, .. } => points.len(),
-      Measurement::Polyline { points, .. } => points.len(),
-      Measurement::Rectangle { .. } => 4,
-    };
     Self {
       measurement: Arc::new(Mutex::new(measurement)),
       scale: Arc::new(Mutex::new(None)),
+      scale_override: Arc::new(Mutex::new(None)),
+      scale_pending: Arc::new(AtomicBool::new(false)),
+      using_fallback_scale: Arc::new(AtomicBool::new(false)),
+      z_index: Arc::new(Mutex::new(None)),
+      order: Arc::new(Mutex::new(None)),
       area: Arc::new(Mutex::new(None)),
       length: Arc::new(Mutex::new(None)),
-      points: points as f64,
       state: Arc::downgrade(&state),
     }
   }
 
+  /// Create a wrapper whose scale is resolved on first use instead of by the state.
+  pub fn new_lazy(measurement: Measurement, state: Arc<TakeoffStateHandler>) -> Self {
+    let res = Self::new(measurement, state);
+    res.scale_pending.store(true, Ordering::SeqCst);
+    res
+  }
+
   pub fn default(measurement: Measurement) -> Self {
     Self::new(measurement, Arc::new(TakeoffStateHandler::default()))
   }
@@ -53,13 +111,102 @@ impl MeasurementWrapper {
   pub fn set_measurement(&self, measurement: Measurement) {
     *lock_mutex(self.measurement.lock(), "measurement")
       .expect("BUG: measurement mutex should not be poisoned") = measurement;
-    // Ignore recomputation errors - they will be handled when values are accessed
-    let _ = self.recompute_measurements();
+    // Recomputation errors are surfaced again when values are accessed
+    if let Err(err) = self.recompute_measurements() {
+      log::debug!("failed to recompute measurement: {}", err);
+    }
   }
 
+  /// The number of points in the current geometry; a rectangle counts its four corners.
   #[napi(getter)]
   pub fn get_points(&self) -> f64 {
-    self.points
+    let measurement = lock_mutex(self.measurement.lock(), "measurement")
+      .expect("BUG: measurement mutex should not be poisoned");
+    let points = match &*measurement {
+      Measurement::Count { .. } => 1,
+      Measurement::Polygon { points, .. } => points.len(),
+      Measurement::Polyline { points, .. } => points.len(),
+      Measurement::Rectangle { .. } => 4,
+    };
+    points as f64
+  }
+
+  /// Replace the points of the measurement, keeping its kind, ids and metadata.
+  ///
+  /// The new geometry is validated first; on error the measurement is left unchanged.
+  /// Polygons are stored counter-clockwise, as with `upsert_measurement`.
+  #[napi]
+  pub fn update_points(&self, points: Vec<Point>) -> Result<()> {
+    let measurement = self.get_measurement().with_points(points)?;
+    self.commit_geometry(measurement);
+    Ok(())
+  }
+
+  /// Store an edited geometry of this measurement, wound like `upsert_measurement` stores it.
+  fn commit_geometry(&self, mut measurement: Measurement) {
+    measurement.normalize_winding();
+    self.set_measurement(measurement);
+    self.touch();
+  }
+
+  /// Record an edit made through this wrapper in the state's versions (see
+  /// `TakeoffStateHandler::measurement_version`).
+  fn touch(&self) {
+    if let Some(state) = self.state.upgrade() {
+      state.touch_measurement(&self.id());
+    }
+  }
+
+  /// Get this measurement converted to a polygon, e.g. to close a polyline or edit a
+  /// rectangle's corners (see `Measurement::as_polygon`).
+  ///
+  /// The state is not changed; upsert the result to apply it. The id is kept unless `new_id`
+  /// is given.
+  #[napi]
+  pub fn as_polygon(&self, new_id: Option<String>) -> Result<Measurement> {
+    let mut polygon = self.get_measurement().as_polygon(new_id)?;
+    polygon.normalize_winding();
+    Ok(polygon)
+  }
+
+  /// Get this measurement converted to a polyline following its outline (see
+  /// `Measurement::as_polyline`).
+  ///
+  /// The state is not changed; upsert the result to apply it. The id is kept unless `new_id`
+  /// is given.
+  #[napi]
+  pub fn as_polyline(&self, new_id: Option<String>) -> Result<Measurement> {
+    Ok(self.get_measurement().as_polyline(new_id)?)
+  }
+
+  /// Insert a vertex before `index` in a polygon or polyline (see `Measurement::insert_vertex`).
+  ///
+  /// On error the measurement is left unchanged. Polygons are stored counter-clockwise, as
+  /// with `update_points`.
+  #[napi]
+  pub fn insert_vertex(&self, index: u32, point: Point) -> Result<()> {
+    let measurement = self
+      .get_measurement()
+      .insert_vertex(index as usize, point)?;
+    self.commit_geometry(measurement);
+    Ok(())
+  }
+
+  /// Remove the vertex at `index` from a polygon or polyline (see `Measurement::remove_vertex`).
+  ///
+  /// On error the measurement is left unchanged. Polygons are stored counter-clockwise, as
+  /// with `update_points`.
+  #[napi]
+  pub fn remove_vertex(&self, index: u32) -> Result<()> {
+    let measurement = self.get_measurement().remove_vertex(index as usize)?;
+    self.commit_geometry(measurement);
+    Ok(())
+  }
+
+  /// Get the vertices of the measurement uniformly (see `Measurement::all_points`).
+  #[napi]
+  pub fn get_points_list(&self) -> Result<Vec<Point>> {
+    Ok(lock_mutex(self.measurement.lock(), "measurement")?.all_points())
   }
 
   #[napi(getter)]
@@ -67,18 +214,45 @@ impl MeasurementWrapper {
     1.0
   }
 
+  /// Resolve the scale of a lazily loaded measurement, once.
+  ///
+  /// Unlike `calculate_scale` this does not recompute the group, so it is safe to call while
+  /// the group itself is being computed.
+  fn ensure_scale(&self) {
+    if !self.scale_pending.swap(false, Ordering::SeqCst) {
+      return;
+    }
+    if let Some(scale) = self.resolve_scale() {
+      if let Ok(mut current) = lock_mutex(self.scale.lock(), "scale") {
+        *current = Some(scale);
+      }
+    }
+  }
+
   fn calculate_area(&self) -> TakeoffResult<Option<Area>> {
+    self.ensure_scale();
     let scale_guard = lock_mutex(self.scale.lock(), "scale")?;
-    if let Some(scale) = scale_guard.as_ref() {
-      let scale_ratio = scale.ratio()?;
+    scale_guard
+      .as_ref()
+      .map(|scale| self.area_with_scale(scale))
+      .transpose()
+  }
 
-      let raw_area = self.raw_area()?;
+  /// The real-world area of the measurement under `scale`, without reading or writing the
+  /// cached values.
+  pub fn area_with_scale(&self, scale: &Scale) -> TakeoffResult<Area> {
+    scaled_area(self.raw_area()?, scale)
+  }
 
-      let area = raw_area / (scale_ratio * scale_ratio);
-      let res = scale.get_unit().get_area_unit(area as f32);
-      return Ok(Some(res));
-    }
-    Ok(None)
+  /// The real-world length of the measurement under `scale`, without reading or writing the
+  /// cached values.
+  pub fn length_with_scale(&self, scale: &Scale) -> TakeoffResult<Length> {
+    let scale_ratio = scale.ratio()?;
+
+    let raw_perimeter = self.raw_perimeter()?;
+
+    let length = raw_perimeter / scale_ratio;
+    Ok(scale.get_unit().get_unit(length))
   }
 
   #[napi(getter)]
@@ -88,6 +262,32 @@ impl MeasurementWrapper {
       .clone()
   }
 
+  /// Get the geodesic area for a measurement on a georeferenced page (see
+  /// `Measurement::geodesic_area`).
+  ///
+  /// Returns an error unless the measurement's page is marked `CoordinateSystem::LonLat`, so
+  /// degree and pixel coordinates are never mixed; the scaled `area` stays the default.
+  #[napi]
+  pub fn get_geodesic_area(&self) -> Result<UnitValue> {
+    let page_id = self.page_id();
+    let coordinate_system = self
+      .state
+      .upgrade()
+      .and_then(|state| state.get_page(page_id.clone()))
+      .and_then(|page| page.coordinate_system)
+      .unwrap_or_default();
+    if coordinate_system != CoordinateSystem::LonLat {
+      return Err(
+        TakeoffError::invalid_input(format!(
+          "page {page_id} does not use longitude/latitude coordinates"
+        ))
+        .into(),
+      );
+    }
+    let area = lock_mutex(self.measurement.lock(), "measurement")?.geodesic_area()?;
+    Ok(UnitValue::from_area(Area::new::<square_meter>(area)))
+  }
+
   #[napi(getter)]
   pub fn get_area(&self) -> Option<UnitValue> {
     if let Ok(Some(area)) = self.get_area_value() {
@@ -105,6 +305,44 @@ impl MeasurementWrapper {
   }
 
   pub fn calculate_scale(&self) -> Option<Scale> {
+    self.scale_pending.store(false, Ordering::SeqCst);
+    if let Some(scale) = self.get_scale_override() {
+      self.using_fallback_scale.store(false, Ordering::SeqCst);
+      return Some(scale);
+    }
+
+    let scale = self.resolve_scale()?;
+    self.set_scale(scale.clone());
+    Some(scale)
+  }
+
+  /// The page scale from `detect_scale`, or else the state's `default_scale`.
+  fn resolve_scale(&self) -> Option<Scale> {
+    if let Some(scale) = self.detect_scale() {
+      self.using_fallback_scale.store(false, Ordering::SeqCst);
+      return Some(scale);
+    }
+    let fallback = self
+      .state
+      .upgrade()
+      .and_then(|state| state.get_default_scale());
+    self
+      .using_fallback_scale
+      .store(fallback.is_some(), Ordering::SeqCst);
+    fallback
+  }
+
+  /// Whether the values are uncalibrated estimates from the state's fallback `default_scale`
+  /// because no page scale applies, so the UI can mark them.
+  #[napi]
+  pub fn is_using_fallback_scale(&self) -> bool {
+    self.ensure_scale();
+    self.using_fallback_scale.load(Ordering::SeqCst) && self.get_scale_override().is_none()
+  }
+
+  /// Find the page scale that applies to this measurement: the first area scale containing it,
+  /// otherwise the first page-wide scale (scales are ordered by id).
+  fn detect_scale(&self) -> Option<Scale> {
     let mut current_scale: Option<Scale> = None;
     let measurement = lock_mutex(self.measurement.lock(), "measurement").ok()?;
     let geometry = match measurement.to_geometry() {
@@ -117,24 +355,19 @@ impl MeasurementWrapper {
       for scale in state.get_page_scales(&self.page_id()) {
         if matches!(scale, Scale::Area { .. }) {
           if scale.is_in_bounding_box(&geometry) {
-            self.set_scale(scale.clone());
             return Some(scale);
           }
-        } else {
-          current_scale = Some(scale.clone());
+        } else if current_scale.is_none() {
+          current_scale = Some(scale);
         }
       }
     }
 
-    if let Some(scale) = current_scale {
-      self.set_scale(scale.clone());
-      return Some(scale);
-    }
-    None
+    current_scale
   }
 
   #[napi]
-  pub fn convert_area(&self, unit: Unit) -> Result<Option<f32>> {
+  pub fn convert_area(&self, unit: Unit) -> Result<Option<f64>> {
     let area = self.calculate_area()?;
     Ok(area.map(|area| unit.convert_area_to_unit(area)))
   }
@@ -148,27 +381,192 @@ impl MeasurementWrapper {
   }
 
   fn calculate_length(&self) -> TakeoffResult<Option<Length>> {
+    self.ensure_scale();
     let scale_guard = lock_mutex(self.scale.lock(), "scale")?;
-    if let Some(scale) = scale_guard.as_ref() {
-      let scale_ratio = scale.ratio()?;
+    scale_guard
+      .as_ref()
+      .map(|scale| self.length_with_scale(scale))
+      .transpose()
+  }
 
-      let raw_perimeter = self.raw_perimeter()?;
+  /// Get the real-world diagonal length (see `Measurement::diagonal_length`).
+  ///
+  /// Returns `None` without a scale, and an error for measurements without a diagonal.
+  #[napi]
+  pub fn get_diagonal_length(&self) -> Result<Option<UnitValue>> {
+    let Some(scale) = self.get_scale() else {
+      return Ok(None);
+    };
+    let ratio = scale.ratio()?;
+    let diagonal = lock_mutex(self.measurement.lock(), "measurement")?.diagonal_length()?;
+    Ok(Some(UnitValue::from_length(
+      scale.get_unit().get_unit(diagonal / ratio),
+    )))
+  }
 
-      let length = raw_perimeter / scale_ratio;
-      let res = scale.get_unit().get_unit(length as f32);
-      return Ok(Some(res));
-    }
-    Ok(None)
+  /// Get the real-world sum of both diagonals, e.g. for cross bracing (see
+  /// `Measurement::sum_of_diagonals`).
+  ///
+  /// Returns `None` without a scale, and an error for measurements other than rectangles and
+  /// four-vertex polygons.
+  #[napi]
+  pub fn get_sum_of_diagonals(&self) -> Result<Option<UnitValue>> {
+    let Some(scale) = self.get_scale() else {
+      return Ok(None);
+    };
+    let ratio = scale.ratio()?;
+    let diagonals = lock_mutex(self.measurement.lock(), "measurement")?.sum_of_diagonals()?;
+    Ok(Some(UnitValue::from_length(
+      scale.get_unit().get_unit(diagonals / ratio),
+    )))
   }
 
+  /// Offset the measurement outward (positive) or inward (negative) by a real-world distance,
+  /// e.g. a wall thickness (see `Measurement::offset_polygon`).
+  ///
+  /// Returns `None` without a scale, and an error for polylines, counts or offsets that
+  /// collapse the polygon.
   #[napi]
-  pub fn convert_length(&self, unit: Unit) -> Result<Option<f32>> {
+  pub fn offset(&self, distance: f64, unit: Unit) -> Result<Option<Measurement>> {
+    let Some(scale) = self.get_scale() else {
+      return Ok(None);
+    };
+    let pixels = unit.convert(distance, &scale.get_unit()) * scale.ratio()?;
+    let offset = lock_mutex(self.measurement.lock(), "measurement")?.offset_polygon(pixels)?;
+    Ok(Some(offset))
+  }
+
+  /// Get the smallest circle enclosing the measurement, with the radius in `unit`.
+  ///
+  /// Returns `None` without a scale.
+  #[napi]
+  pub fn get_min_enclosing_circle(&self, unit: Unit) -> Result<Option<EnclosingCircle>> {
+    let Some(scale) = self.get_scale() else {
+      return Ok(None);
+    };
+    let ratio = scale.ratio()?;
+    let (center, radius) =
+      lock_mutex(self.measurement.lock(), "measurement")?.min_enclosing_circle()?;
+    let radius = scale.get_unit().get_unit(radius / ratio);
+    Ok(Some(EnclosingCircle {
+      center,
+      radius: unit.convert_length_to_unit(radius),
+    }))
+  }
+
+  /// Get pixel-space shape metrics for trace sanity warnings (see `Measurement::shape_stats`).
+  ///
+  /// Needs no scale; returns an error for counts.
+  #[napi]
+  pub fn get_shape_stats(&self) -> Result<ShapeStats> {
+    Ok(lock_mutex(self.measurement.lock(), "measurement")?.shape_stats()?)
+  }
+
+  #[napi]
+  pub fn convert_length(&self, unit: Unit) -> Result<Option<f64>> {
     if let Some(length) = self.calculate_length()? {
       return Ok(Some(unit.convert_length_to_unit(length)));
     }
     Ok(None)
   }
 
+  /// Get the cached area converted to `unit`, independently of the scale's own unit.
+  ///
+  /// Unlike `convert_area` this reuses the cached value instead of recomputing it.
+  #[napi]
+  pub fn get_area_in(&self, unit: Unit) -> Result<Option<f64>> {
+    let area = self.get_area_value()?;
+    Ok(area.map(|area| unit.convert_area_to_unit(area)))
+  }
+
+  /// Get the cached length converted to `unit`, independently of the scale's own unit.
+  ///
+  /// Unlike `convert_length` this reuses the cached value instead of recomputing it.
+  #[napi]
+  pub fn get_length_in(&self, unit: Unit) -> Result<Option<f64>> {
+    let length = self.get_length_value()?;
+    Ok(length.map(|length| unit.convert_length_to_unit(length)))
+  }
+
+  /// Get the area in acres (43,560 ft²), for large sites.
+  ///
+  /// Returns `None` if the area cannot be computed (no scale, polyline or count).
+  #[napi]
+  pub fn get_area_acres(&self) -> Option<f64> {
+    let area = self.get_area_value().ok().flatten()?;
+    // uom's acre is rounded, while 43,560 ft² (1 ft = 0.3048 m) is exact.
+    Some(area.get::<square_foot>() / SQUARE_FEET_PER_ACRE)
+  }
+
+  /// Get the area in hectares (10,000 m²), for large sites.
+  ///
+  /// Returns `None` if the area cannot be computed (no scale, polyline or count).
+  #[napi]
+  pub fn get_area_hectares(&self) -> Option<f64> {
+    let area = self.get_area_value().ok().flatten()?;
+    Some(area.get::<hectare>())
+  }
+
+  /// How much the area would change if the geometry were replaced by `candidate`, in the
+  /// square of the scale's unit (e.g. for a "+12 sf" tooltip while dragging).
+  ///
+  /// Computes `candidate_area - current_area` under the current scale without touching the
+  /// measurement or its cached values. Returns `None` without a scale, and an error if either
+  /// geometry has no area (polylines, counts) or is invalid.
+  #[napi]
+  pub fn area_delta_if(&self, candidate: Measurement) -> Result<Option<f64>> {
+    let Some(scale) = self.get_scale() else {
+      return Ok(None);
+    };
+    let current = self.area_with_scale(&scale)?;
+    let candidate = scaled_area(candidate.pixel_area()?, &scale)?;
+    Ok(Some(
+      scale.get_unit().convert_area_to_unit(candidate - current),
+    ))
+  }
+
+  /// Get the area multiplied by a waste factor (e.g. `1.1` for 10% waste), in `unit`.
+  ///
+  /// Returns `None` if the area cannot be computed, and an error if `factor` is negative or
+  /// not finite.
+  #[napi]
+  pub fn get_area_with_waste(&self, factor: f64, unit: Unit) -> Result<Option<f64>> {
+    let Some(area) = self.get_area() else {
+      return Ok(None);
+    };
+    Ok(Some(area.with_factor(factor)?.get_converted_value(unit)))
+  }
+
+  /// Get the volume of a constant cross-section run along this measurement's length, e.g. a
+  /// strip footing (see `linear_volume`). `profile_area` is in square `unit`.
+  ///
+  /// Returns `None` if the length cannot be computed, and an error if `profile_area` is
+  /// negative or not finite.
+  #[napi]
+  pub fn get_strip_volume(&self, profile_area: f64, unit: Unit) -> Result<Option<UnitValue>> {
+    let Some(length) = self.get_length_value()? else {
+      return Ok(None);
+    };
+    let volume = linear_volume(length, unit.get_area_unit(profile_area))?;
+    Ok(Some(UnitValue::from_volume(volume)))
+  }
+
+  /// Get the area displayed in two units, e.g. `"50 m² (538.195 ft²)"`.
+  ///
+  /// Returns `None` if the area cannot be computed (no scale, polyline or count).
+  #[napi]
+  pub fn get_area_dual(&self, primary: Unit, secondary: Unit) -> Option<String> {
+    Some(self.get_area()?.format_dual(primary, secondary))
+  }
+
+  /// Get the length displayed in two units, e.g. `"12 m (39.37 ft)"`.
+  ///
+  /// Returns `None` if the length cannot be computed (no scale).
+  #[napi]
+  pub fn get_length_dual(&self, primary: Unit, secondary: Unit) -> Option<String> {
+    Some(self.get_length().ok()??.format_dual(primary, secondary))
+  }
+
   #[napi(getter)]
   pub fn get_length(&self) -> Result<Option<UnitValue>> {
     if let Some(length) = self.calculate_length()? {
@@ -177,12 +575,23 @@ impl MeasurementWrapper {
     Ok(None)
   }
 
-  pub fn recompute_measurements(&self) -> TakeoffResult<()> {
-    let area = self.calculate_area();
-    *lock_mutex(self.area.lock(), "area")? = area?;
+  /// Re-detect the scale and recompute the cached values, for when an automatic
+  /// recompute did not fire.
+  ///
+  /// # Errors
+  ///
+  /// Returns the [`TakeoffError`] raised while computing the area or length.
+  #[napi]
+  pub fn recompute(&self) -> Result<()> {
+    self.calculate_scale();
+    self.recompute_measurements()?;
+    Ok(())
+  }
 
-    let length = self.calculate_length();
-    *lock_mutex(self.length.lock(), "length")? = length?;
+  pub fn recompute_measurements(&self) -> TakeoffResult<()> {
+    self
+      .recompute_values()
+      .map_err(|err| err.with_context(format!("measurement {}", self.id())))?;
 
     // Ignore recomputation errors - they will be handled when group values are accessed
     if let Some(state) = self.state.upgrade() {
@@ -191,20 +600,90 @@ impl MeasurementWrapper {
     Ok(())
   }
 
+  fn recompute_values(&self) -> TakeoffResult<()> {
+    let area = self.calculate_area();
+    *lock_mutex(self.area.lock(), "area")? = area?;
+
+    let length = self.calculate_length();
+    *lock_mutex(self.length.lock(), "length")? = length?;
+    Ok(())
+  }
+
   pub fn set_scale(&self, scale: Scale) {
     *lock_mutex(self.scale.lock(), "scale").expect("BUG: scale mutex should not be poisoned") =
       Some(scale);
-    // Ignore recomputation errors - they will be handled when values are accessed
-    let _ = self.recompute_measurements();
+    // Recomputation errors are surfaced again when values are accessed
+    if let Err(err) = self.recompute_measurements() {
+      log::debug!("failed to recompute measurement: {}", err);
+    }
   }
 
   #[napi(getter)]
   pub fn get_scale(&self) -> Option<Scale> {
+    self.ensure_scale();
     lock_mutex(self.scale.lock(), "scale")
       .ok()
       .and_then(|s| s.clone())
   }
 
+  /// Get the scale pinned with `set_scale_override`, if any.
+  #[napi(getter)]
+  pub fn get_scale_override(&self) -> Option<Scale> {
+    lock_mutex(self.scale_override.lock(), "scale_override")
+      .ok()
+      .and_then(|s| s.clone())
+  }
+
+  /// Pin the scale of this measurement, e.g. for a detail callout drawn at a different scale
+  /// than the page's auto-detected one.
+  ///
+  /// While set, the override is used instead of the page's scales. Passing `None` re-enables
+  /// auto-detection.
+  #[napi]
+  pub fn set_scale_override(&self, scale: Option<Scale>) -> Result<()> {
+    *lock_mutex(self.scale_override.lock(), "scale_override")? = scale.clone();
+    match scale {
+      Some(scale) => self.set_scale(scale),
+      None => {
+        *lock_mutex(self.scale.lock(), "scale")? = None;
+        if self.calculate_scale().is_none() {
+          self.recompute_measurements()?;
+        }
+      }
+    }
+    self.touch();
+    Ok(())
+  }
+
+  /// Get the stacking order used to pick the top measurement when several overlap.
+  #[napi(getter)]
+  pub fn get_z_index(&self) -> Option<i32> {
+    lock_mutex(self.z_index.lock(), "z_index")
+      .ok()
+      .and_then(|z| *z)
+  }
+
+  /// Set the stacking order; higher values are on top in `measurement_at`. `None` counts as 0.
+  ///
+  /// The value is kept when the measurement is updated through `upsert_measurement`.
+  #[napi]
+  pub fn set_z_index(&self, z_index: Option<i32>) -> Result<()> {
+    *lock_mutex(self.z_index.lock(), "z_index")? = z_index;
+    self.touch();
+    Ok(())
+  }
+
+  /// Get the position of this measurement within its group, if one was set.
+  #[napi(getter)]
+  pub fn get_order(&self) -> Option<u32> {
+    lock_mutex(self.order.lock(), "order").ok().and_then(|o| *o)
+  }
+
+  pub fn set_order(&self, order: Option<u32>) -> TakeoffResult<()> {
+    *lock_mutex(self.order.lock(), "order")? = order;
+    Ok(())
+  }
+
   #[napi(getter)]
   pub fn id(&self) -> String {
     lock_mutex(self.measurement.lock(), "measurement")
@@ -221,6 +700,23 @@ impl MeasurementWrapper {
       .to_string()
   }
 
+  /// Get the metadata (arbitrary key/value labels such as cost code or phase) of the measurement.
+  #[napi(getter)]
+  pub fn get_metadata(&self) -> Option<HashMap<String, String>> {
+    lock_mutex(self.measurement.lock(), "measurement")
+      .expect("BUG: measurement mutex should not be poisoned")
+      .metadata()
+      .cloned()
+  }
+
+  /// Replace the metadata of the measurement. Geometry and computed values are unaffected.
+  #[napi]
+  pub fn set_metadata(&self, metadata: Option<HashMap<String, String>>) -> Result<()> {
+    lock_mutex(self.measurement.lock(), "measurement")?.set_metadata(metadata);
+    self.touch();
+    Ok(())
+  }
+
   #[napi(getter)]
   pub fn get_group_id(&self) -> String {
     lock_mutex(self.measurement.lock(), "measurement")
@@ -229,6 +725,61 @@ impl MeasurementWrapper {
       .to_string()
   }
 
+  /// The kind of quantity the measurement takes off (see `Measurement::kind`).
+  #[napi(getter)]
+  pub fn kind(&self) -> MeasurementType {
+    lock_mutex(self.measurement.lock(), "measurement")
+      .expect("BUG: measurement mutex should not be poisoned")
+      .kind()
+  }
+
+  /// Whether the measurement encloses an area (polygons and rectangles).
+  #[napi]
+  pub fn is_areal(&self) -> bool {
+    lock_mutex(self.measurement.lock(), "measurement")
+      .expect("BUG: measurement mutex should not be poisoned")
+      .is_areal()
+  }
+
+  /// Whether the measurement is taken off as a length (polylines and rectangles).
+  #[napi]
+  pub fn is_linear(&self) -> bool {
+    lock_mutex(self.measurement.lock(), "measurement")
+      .expect("BUG: measurement mutex should not be poisoned")
+      .is_linear()
+  }
+
+  /// Whether the measurement is a count.
+  #[napi]
+  pub fn is_count(&self) -> bool {
+    lock_mutex(self.measurement.lock(), "measurement")
+      .expect("BUG: measurement mutex should not be poisoned")
+      .is_count()
+  }
+
+  /// Export the measurement with its scale and real-world values embedded.
+  #[napi]
+  pub fn export(&self) -> MeasurementExport {
+    let measurement = self.get_measurement();
+    let scale = self.get_scale();
+    let unit = scale.as_ref().map(Scale::get_unit);
+    MeasurementExport {
+      page_id: measurement.page_id().to_string(),
+      measurement,
+      ratio: scale.as_ref().and_then(|scale| scale.ratio().ok()),
+      scale,
+      unit,
+      area: unit.and_then(|unit| {
+        let area = self.get_area_value().ok().flatten()?;
+        Some(unit.convert_area_to_unit(area))
+      }),
+      length: unit.and_then(|unit| {
+        let length = self.get_length_value().ok().flatten()?;
+        Some(unit.convert_length_to_unit(length))
+      }),
+    }
+  }
+
   #[napi(getter)]
   pub fn raw_area(&self) -> Result<f64> {
     let area = lock_mutex(self.measurement.lock(), "measurement")?.pixel_area()?;
@@ -242,6 +793,20 @@ impl MeasurementWrapper {
   }
 }
 
+/// Convert a pixel area to a real-world area under `scale`.
+fn scaled_area(raw_area: f64, scale: &Scale) -> TakeoffResult<Area> {
+  let scale_ratio = scale.ratio()?;
+  // Squaring a tiny ratio blows areas up to meaningless values, so reject it up front.
+  if !scale_ratio.is_finite() || scale_ratio < MIN_AREA_SCALE_RATIO {
+    return Err(TakeoffError::invalid_scale(format!(
+      "scale ratio {scale_ratio} is too small to compute an area"
+    )));
+  }
+
+  let area = raw_area / (scale_ratio * scale_ratio);
+  Ok(scale.get_unit().get_area_unit(area))
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -255,6 +820,8 @@ mod tests {
       page_id: "1".to_string(),
       group_id: "1".to_string(),
       points: (Point::new(0.0, 0.0), Point::new(100.0, 50.0)),
+      rotation: None,
+      metadata: None,
     };
 
     assert_eq!(measurement.pixel_area().unwrap(), 5000.0);
@@ -287,6 +854,494 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_calculate_area_rejects_degenerate_ratio() {
+    let measurement = Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(100.0, 50.0)),
+      rotation: None,
+      metadata: None,
+    };
+    let measurement_wrapper = MeasurementWrapper::default(measurement);
+    measurement_wrapper.set_scale(Scale::Default {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 1.0,
+        real_distance: 1e12,
+        unit: Unit::Meters,
+      },
+    });
+
+    assert!(matches!(
+      measurement_wrapper.calculate_area(),
+      Err(TakeoffError::InvalidScale { .. })
+    ));
+    assert!(measurement_wrapper.convert_area(Unit::Meters).is_err());
+  }
+
+  #[test]
+  fn test_export_embeds_scale_and_values() {
+    let measurement = Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "p1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(100.0, 50.0)),
+      rotation: None,
+      metadata: None,
+    };
+    let measurement_wrapper = MeasurementWrapper::default(measurement.clone());
+    let unscaled = measurement_wrapper.export();
+    assert_eq!(
+      (unscaled.scale, unscaled.area, unscaled.length),
+      (None, None, None)
+    );
+
+    let scale = Scale::Default {
+      id: "1".to_string(),
+      page_id: "p1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 100.0,
+        real_distance: 2.0,
+        unit: Unit::Meters,
+      },
+    };
+    measurement_wrapper.set_scale(scale.clone());
+    let export = measurement_wrapper.export();
+    assert_eq!(export.measurement, measurement);
+    assert_eq!(export.page_id, "p1");
+    assert_eq!(export.scale, Some(scale));
+    assert_eq!(export.ratio, Some(50.0));
+    assert_eq!(export.unit, Some(Unit::Meters));
+    assert_eq!(export.area, Some(2.0));
+    assert_eq!(export.length, Some(6.0));
+
+    let json = serde_json::to_string(&export).unwrap();
+    assert_eq!(
+      serde_json::from_str::<MeasurementExport>(&json).unwrap(),
+      export
+    );
+  }
+
+  #[test]
+  fn test_scale_override_wins_over_area_scale() {
+    let state = TakeoffStateHandler::new(None);
+    state.upsert_scale(Scale::Area {
+      id: "area".to_string(),
+      page_id: "1".to_string(),
+      bounding_box: (Point::new(0.0, 0.0), Point::new(10.0, 10.0)),
+      scale: ScaleDefinition {
+        pixel_distance: 1.0,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    });
+    state.upsert_measurement(Measurement::Polyline {
+      id: "m1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(1.0, 1.0), Point::new(3.0, 1.0)],
+      metadata: None,
+    });
+    let measurement = state.get_measurement("m1".to_string()).unwrap();
+    assert_eq!(measurement.get_scale().unwrap().id(), "area");
+
+    let detail = Scale::Default {
+      id: "detail".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 2.0,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    };
+    measurement
+      .set_scale_override(Some(detail.clone()))
+      .unwrap();
+    assert_eq!(measurement.get_scale(), Some(detail.clone()));
+    assert!((measurement.convert_length(Unit::Meters).unwrap().unwrap() - 1.0).abs() < 1e-12);
+
+    // Page scale changes re-run auto-detection, which must keep the override.
+    state.upsert_scale(Scale::Default {
+      id: "page".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 4.0,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    });
+    assert_eq!(measurement.get_scale(), Some(detail));
+
+    measurement.set_scale_override(None).unwrap();
+    assert_eq!(measurement.get_scale().unwrap().id(), "area");
+    assert!((measurement.convert_length(Unit::Meters).unwrap().unwrap() - 2.0).abs() < 1e-12);
+  }
+
+  #[test]
+  fn test_get_length_and_area_in_other_units() {
+    let measurement = Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(9.0, 3.0)),
+      rotation: None,
+      metadata: None,
+    };
+    let measurement_wrapper = MeasurementWrapper::default(measurement);
+    measurement_wrapper.set_scale(Scale::Default {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 1.0,
+        real_distance: 1.0,
+        unit: Unit::Feet,
+      },
+    });
+    let area = measurement_wrapper
+      .get_area_in(Unit::Yards)
+      .unwrap()
+      .unwrap();
+    let length = measurement_wrapper
+      .get_length_in(Unit::Feet)
+      .unwrap()
+      .unwrap();
+    assert!((area - 3.0).abs() < 1e-9);
+    assert!((length - 24.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_get_area_acres_and_hectares() {
+    let measurement = Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(200.0, 100.0)),
+      rotation: None,
+      metadata: None,
+    };
+    let measurement_wrapper = MeasurementWrapper::default(measurement);
+    assert_eq!(measurement_wrapper.get_area_acres(), None);
+    assert_eq!(measurement_wrapper.get_area_hectares(), None);
+
+    // 1 px = 1 m: 20,000 m² is 2 ha.
+    measurement_wrapper.set_scale(Scale::Default {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 1.0,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    });
+    let hectares = measurement_wrapper.get_area_hectares().unwrap();
+    let acres = measurement_wrapper.get_area_acres().unwrap();
+    assert!((hectares - 2.0).abs() < 1e-9);
+    assert!((acres - 20_000.0 / 0.3048_f64.powi(2) / 43_560.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_area_delta_if() {
+    let rectangle = |x: f64| Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(x, 10.0)),
+      rotation: None,
+      metadata: None,
+    };
+    let measurement_wrapper = MeasurementWrapper::default(rectangle(10.0));
+    assert_eq!(
+      measurement_wrapper.area_delta_if(rectangle(12.0)).unwrap(),
+      None
+    );
+
+    // 10 px = 1 ft: widening by 2 ft over a 1 ft depth adds 2 sf.
+    measurement_wrapper.set_scale(Scale::Default {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 10.0,
+        real_distance: 1.0,
+        unit: Unit::Feet,
+      },
+    });
+    let delta = measurement_wrapper
+      .area_delta_if(rectangle(30.0))
+      .unwrap()
+      .unwrap();
+    assert!((delta - 2.0).abs() < 1e-9);
+    let delta = measurement_wrapper
+      .area_delta_if(rectangle(5.0))
+      .unwrap()
+      .unwrap();
+    assert!((delta + 0.5).abs() < 1e-9);
+    // The measurement itself is untouched.
+    assert!((measurement_wrapper.raw_area().unwrap() - 100.0).abs() < 1e-9);
+
+    let polyline = Measurement::Polyline {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0)],
+      metadata: None,
+    };
+    assert!(measurement_wrapper.area_delta_if(polyline).is_err());
+  }
+
+  #[test]
+  fn test_recompute_surfaces_errors() {
+    let measurement = Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(1.0, 1.0)),
+      rotation: None,
+      metadata: None,
+    };
+    let measurement_wrapper = MeasurementWrapper::default(measurement);
+    assert!(measurement_wrapper.recompute().is_ok());
+
+    measurement_wrapper.set_scale(Scale::Default {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 1e-12,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    });
+    assert!(measurement_wrapper.recompute().is_err());
+    let err = measurement_wrapper.recompute_measurements().unwrap_err();
+    assert!(err.to_string().starts_with("measurement 1 → invalid scale"));
+    assert!(matches!(
+      err.root_cause(),
+      TakeoffError::InvalidScale { .. }
+    ));
+  }
+
+  #[test]
+  fn test_get_diagonal_length() {
+    let measurement = Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(30.0, 40.0)),
+      rotation: None,
+      metadata: None,
+    };
+    let measurement_wrapper = MeasurementWrapper::default(measurement);
+    assert!(measurement_wrapper.get_diagonal_length().unwrap().is_none());
+
+    measurement_wrapper.set_scale(Scale::Default {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 10.0,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    });
+    let diagonal = measurement_wrapper.get_diagonal_length().unwrap().unwrap();
+    assert!((diagonal.get_converted_value(Unit::Meters) - 5.0).abs() < 1e-12);
+  }
+
+  #[test]
+  fn test_poisoned_scale_lock_names_resource() {
+    let measurement = Measurement::Polygon {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![
+        Point::new(0.0, 0.0),
+        Point::new(10.0, 0.0),
+        Point::new(10.0, 10.0),
+      ],
+      metadata: None,
+    };
+    let measurement_wrapper = MeasurementWrapper::default(measurement);
+    let scale = measurement_wrapper.scale.clone();
+    let _ = std::thread::spawn(move || {
+      let _guard = scale.lock().unwrap();
+      panic!("poison the scale lock");
+    })
+    .join();
+
+    let err = measurement_wrapper.calculate_area().unwrap_err();
+    assert_eq!(err, TakeoffError::poison_error("scale"));
+  }
+
+  #[test]
+  fn test_get_strip_volume() {
+    let measurement = Measurement::Polyline {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(100.0, 0.0)],
+      metadata: None,
+    };
+    let measurement_wrapper = MeasurementWrapper::default(measurement);
+    assert!(measurement_wrapper
+      .get_strip_volume(2.0, Unit::Meters)
+      .unwrap()
+      .is_none());
+
+    measurement_wrapper.set_scale(Scale::Default {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 10.0,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    });
+    // 10 m run with a 2 m² cross-section.
+    let volume = measurement_wrapper
+      .get_strip_volume(2.0, Unit::Meters)
+      .unwrap()
+      .unwrap();
+    assert!((volume.get_converted_value(Unit::Meters) - 20.0).abs() < 1e-9);
+    assert!(measurement_wrapper
+      .get_strip_volume(f64::NAN, Unit::Meters)
+      .is_err());
+  }
+
+  #[test]
+  fn test_offset_by_real_distance() {
+    let measurement = Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(100.0, 50.0)),
+      rotation: None,
+      metadata: None,
+    };
+    let measurement_wrapper = MeasurementWrapper::default(measurement);
+    assert!(measurement_wrapper
+      .offset(10.0, Unit::Centimeters)
+      .unwrap()
+      .is_none());
+
+    measurement_wrapper.set_scale(Scale::Default {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 10.0,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    });
+    // 10 cm is 1 pixel at 10 pixels per meter.
+    let offset = measurement_wrapper
+      .offset(10.0, Unit::Centimeters)
+      .unwrap()
+      .unwrap();
+    assert!((offset.pixel_area().unwrap() - 102.0 * 52.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_update_points_validates_before_committing() {
+    let measurement = Measurement::Polyline {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)],
+      metadata: None,
+    };
+    let measurement_wrapper = MeasurementWrapper::default(measurement.clone());
+    measurement_wrapper.set_scale(Scale::Default {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 1.0,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    });
+
+    assert!(measurement_wrapper
+      .update_points(vec![Point::new(0.0, 0.0)])
+      .is_err());
+    assert_eq!(measurement_wrapper.get_measurement(), measurement);
+
+    measurement_wrapper
+      .update_points(vec![
+        Point::new(0.0, 0.0),
+        Point::new(0.0, 0.0),
+        Point::new(3.0, 4.0),
+      ])
+      .unwrap();
+    assert_eq!(measurement_wrapper.get_measurement().id(), "1");
+    assert_eq!(measurement_wrapper.get_points(), 3.0);
+    let length = measurement_wrapper.convert_length(Unit::Meters).unwrap();
+    assert_eq!(length, Some(5.0));
+  }
+
+  #[test]
+  fn test_vertex_edits_refresh_points_and_winding() {
+    // Stored clockwise, as a wrapper built outside the state may be.
+    let measurement_wrapper = MeasurementWrapper::default(Measurement::Polygon {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![
+        Point::new(0.0, 0.0),
+        Point::new(0.0, 10.0),
+        Point::new(10.0, 10.0),
+        Point::new(10.0, 0.0),
+      ],
+      metadata: None,
+    });
+
+    measurement_wrapper
+      .insert_vertex(1, Point::new(0.0, 5.0))
+      .unwrap();
+    assert_eq!(measurement_wrapper.get_points(), 5.0);
+    assert_eq!(
+      measurement_wrapper.get_points_list().unwrap(),
+      vec![
+        Point::new(10.0, 0.0),
+        Point::new(10.0, 10.0),
+        Point::new(0.0, 10.0),
+        Point::new(0.0, 5.0),
+        Point::new(0.0, 0.0),
+      ]
+    );
+
+    measurement_wrapper.remove_vertex(3).unwrap();
+    assert_eq!(measurement_wrapper.get_points(), 4.0);
+  }
+
+  #[test]
+  fn test_get_min_enclosing_circle() {
+    let measurement = Measurement::Rectangle {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(30.0, 40.0)),
+      rotation: None,
+      metadata: None,
+    };
+    let measurement_wrapper = MeasurementWrapper::default(measurement);
+    measurement_wrapper.set_scale(Scale::Default {
+      id: "1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 10.0,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    });
+    let circle = measurement_wrapper
+      .get_min_enclosing_circle(Unit::Centimeters)
+      .unwrap()
+      .unwrap();
+    assert_eq!(circle.center, Point::new(15.0, 20.0));
+    assert!((circle.radius - 250.0).abs() < 1e-9);
+  }
+
   #[test]
   fn test_calculate_without_scale() {
     let measurement = Measurement::Rectangle {
@@ -294,6 +1349,8 @@ mod tests {
       page_id: "1".to_string(),
       group_id: "1".to_string(),
       points: (Point::new(0.0, 0.0), Point::new(100.0, 50.0)),
+      rotation: None,
+      metadata: None,
     };
     let measurement_wrapper =
       MeasurementWrapper::new(measurement, Arc::new(TakeoffStateHandler::default()));
@@ -316,6 +1373,7 @@ mod tests {
       page_id: "1".to_string(),
       group_id: "1".to_string(),
       points: vec![Point::new(0.0, 0.0), Point::new(0.0, 1.0)],
+      metadata: None,
     };
     let measurement_wrapper =
       MeasurementWrapper::new(measurement, Arc::new(TakeoffStateHandler::default()));
@@ -1,16 +1,44 @@
 use crate::contour::ContourWrapper;
 use crate::group::GroupWrapper;
 use crate::measurement::MeasurementWrapper;
-use anyhow::Result;
 use dashmap::DashMap;
+use geo::{Area as _, Intersects};
+use napi::Result;
 use napi_derive::napi;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use takeoff_core::contour::ContourInput;
-use takeoff_core::group::Group;
+use takeoff_core::coords::{DistanceTrait, Point};
+use takeoff_core::error::{TakeoffError, TakeoffResult};
+use takeoff_core::group::{Group, MeasurementType};
 use takeoff_core::measurement::Measurement;
 use takeoff_core::page::Page;
 use takeoff_core::scale::Scale;
 use takeoff_core::state::StateOptions;
+use takeoff_core::unit::{Unit, UnitValue};
+use takeoff_core::utils::densify_polyline;
+use takeoff_core::volume::VolumeComputation;
+
+/// One slice of a page's measurements, ordered by id (see `get_measurements_page`).
+#[napi(object, object_from_js = false)]
+pub struct MeasurementPage {
+  pub measurements: Vec<MeasurementWrapper>,
+  /// Number of measurements on the page, across all slices.
+  pub total: u32,
+}
+
+/// Combined values of a selection of measurements (see `selection_totals`).
+#[napi(object, object_from_js = false)]
+pub struct SelectionTotals {
+  /// Sum of the areas, in the requested unit squared.
+  pub area: f64,
+  /// Sum of the lengths, including polygon and rectangle perimeters, in the requested unit.
+  pub length: f64,
+  /// Number of selected measurements found in the state.
+  pub count: u32,
+}
+
 #[napi]
 #[derive(Debug, Clone)]
 pub struct TakeoffStateHandler {
@@ -19,6 +47,13 @@ pub struct TakeoffStateHandler {
   measurements: Arc<DashMap<String, MeasurementWrapper>>,
   scales: Arc<DashMap<String, Scale>>,
   contours: Arc<DashMap<String, ContourWrapper>>,
+  volumes: Arc<DashMap<String, VolumeComputation>>,
+  /// Bumped by every upsert, effective removal and measurement edit (see `version`).
+  version: Arc<AtomicU64>,
+  /// State version at which each measurement was last upserted, reordered or edited.
+  measurement_versions: Arc<DashMap<String, u64>>,
+  /// Fallback for measurements without a page scale (see `StateOptions::default_scale`).
+  default_scale: Option<Scale>,
 
   self_arc: Option<Arc<TakeoffStateHandler>>,
 }
@@ -48,25 +83,96 @@ impl TakeoffStateHandler {
       measurements: Arc::new(DashMap::new()),
       scales: Arc::new(DashMap::new()),
       contours: Arc::new(DashMap::new()),
+      volumes: Arc::new(DashMap::new()),
+      version: Arc::new(AtomicU64::new(0)),
+      measurement_versions: Arc::new(DashMap::new()),
+      default_scale: options
+        .as_ref()
+        .and_then(|options| options.default_scale.clone()),
       self_arc: None,
     };
     state.self_arc = Some(Arc::new(state.clone()));
 
+    let lazy = options
+      .as_ref()
+      .and_then(|options| options.lazy)
+      .unwrap_or(false);
     if let Some(options) = options {
       state.add_initial_options(options);
     }
-    state.compute_measurements();
+    if !lazy {
+      state.compute_measurements();
+    }
     state
   }
 
   #[napi]
+  /// Get the measurements of a group in report order.
+  ///
+  /// Measurements are sorted by their `order` (see `reorder_measurement`), then by id;
+  /// measurements without an order come after the ordered ones.
   pub fn get_measurements_by_group_id(&self, group_id: String) -> Vec<MeasurementWrapper> {
-    self
+    let mut measurements: Vec<MeasurementWrapper> = self
       .measurements
       .iter()
       .filter(|entry| entry.value().get_group_id() == group_id)
       .map(|entry| entry.value().clone())
-      .collect()
+      .collect();
+    measurements.sort_by_cached_key(|measurement| {
+      let order = measurement.get_order();
+      (order.is_none(), order, measurement.id())
+    });
+    measurements
+  }
+
+  #[napi]
+  /// Set the position of a measurement within its group for `get_measurements_by_group_id`.
+  ///
+  /// Orders do not need to be contiguous; measurements sharing an order fall back to id.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the measurement is not found.
+  pub fn reorder_measurement(&self, id: String, new_order: u32) -> Result<()> {
+    let measurement = self
+      .get_measurement(id.clone())
+      .ok_or_else(|| TakeoffError::invalid_input(format!("measurement not found: {id}")))?;
+    measurement.set_order(Some(new_order))?;
+    self.touch_measurement(&id);
+    Ok(())
+  }
+
+  /// Get the state version, which increases with every upsert, every removal that removed
+  /// something and every edit through a `MeasurementWrapper` (e.g. `update_points`).
+  ///
+  /// Compare it with a previously read value to detect changes made in the meantime, e.g. for
+  /// optimistic concurrency or to invalidate an undo stack. The initial state is version 0.
+  #[napi]
+  pub fn version(&self) -> f64 {
+    self.version.load(Ordering::SeqCst) as f64
+  }
+
+  /// Get the state version at which a measurement was last upserted, reordered or edited
+  /// through its `MeasurementWrapper`.
+  ///
+  /// Measurements loaded with the initial options report 0. Returns `None` if the
+  /// measurement is not found.
+  #[napi]
+  pub fn measurement_version(&self, id: String) -> Option<f64> {
+    if !self.measurements.contains_key(&id) {
+      return None;
+    }
+    let version = self.measurement_versions.get(&id).map_or(0, |entry| *entry);
+    Some(version as f64)
+  }
+
+  fn bump_version(&self) -> u64 {
+    self.version.fetch_add(1, Ordering::SeqCst) + 1
+  }
+
+  pub(crate) fn touch_measurement(&self, id: &str) {
+    let version = self.bump_version();
+    self.measurement_versions.insert(id.to_string(), version);
   }
 
   /// Get the measurements by page id.
@@ -88,7 +194,362 @@ impl TakeoffStateHandler {
       .collect()
   }
 
+  /// Get a slice of the measurements on a page, for virtualized lists.
+  ///
+  /// Measurements are ordered by id so consecutive calls page through a stable sequence.
+  /// Only the returned slice is cloned.
+  ///
+  /// # Arguments
+  ///
+  /// * `page_id` - The id of the page.
+  /// * `offset` - The number of measurements to skip.
+  /// * `limit` - The maximum number of measurements to return.
+  ///
+  /// # Returns
+  ///
+  /// * `MeasurementPage` - The slice and the total number of measurements on the page.
+  #[napi]
+  pub fn get_measurements_page(&self, page_id: String, offset: u32, limit: u32) -> MeasurementPage {
+    let mut ids: Vec<String> = self
+      .measurements
+      .iter()
+      .filter(|entry| entry.value().page_id() == page_id)
+      .map(|entry| entry.key().clone())
+      .collect();
+    ids.sort();
+    let measurements = ids
+      .iter()
+      .skip(offset as usize)
+      .take(limit as usize)
+      .filter_map(|id| self.measurements.get(id).map(|entry| entry.value().clone()))
+      .collect();
+    MeasurementPage {
+      measurements,
+      total: ids.len() as u32,
+    }
+  }
+
+  /// Get the top measurement at a point on a page, e.g. for click selection.
+  ///
+  /// Only polygons and rectangles are hit, including on their boundary. When several
+  /// overlap, the highest `z_index` wins (unset counts as 0), then the smallest area, then
+  /// the lowest id, so the result is deterministic.
+  ///
+  /// # Arguments
+  ///
+  /// * `page_id` - The id of the page.
+  /// * `point` - The point in pixels.
+  ///
+  /// # Returns
+  ///
+  /// * `None` - If no polygon or rectangle on the page contains the point.
+  /// * `Some(measurement)` - The top measurement at the point.
+  #[napi]
+  pub fn measurement_at(&self, page_id: String, point: Point) -> Option<MeasurementWrapper> {
+    let target = geo::Point::new(point.x, point.y);
+    self
+      .measurements
+      .iter()
+      .filter(|entry| entry.value().page_id() == page_id)
+      .filter_map(|entry| {
+        let polygon = entry.value().get_measurement().to_polygon().ok()?;
+        if !polygon.intersects(&target) {
+          return None;
+        }
+        let z_index = entry.value().get_z_index().unwrap_or(0);
+        Some((z_index, polygon.unsigned_area(), entry.value().clone()))
+      })
+      .min_by(|(z_a, area_a, a), (z_b, area_b, b)| {
+        z_b
+          .cmp(z_a)
+          .then(area_a.total_cmp(area_b))
+          .then_with(|| a.id().cmp(&b.id()))
+      })
+      .map(|(_, _, measurement)| measurement)
+  }
+
+  /// Put ungrouped measurements of one kind on a page into new groups of nearby measurements.
+  ///
+  /// A measurement is ungrouped when its `group_id` does not name a group in the state. Two
+  /// measurements are linked when their centroids are at most `radius` pixels apart, and each
+  /// connected set of links (single-linkage clustering) becomes one new unnamed group of
+  /// `measurement_type`, with a random id. A measurement with no neighbour gets a group of its
+  /// own. Clusters are created in the order of their smallest measurement id, so the result
+  /// does not depend on insertion order. Measurements whose centroid cannot be computed are
+  /// left alone.
+  ///
+  /// Returns the number of groups created; a negative or non-finite `radius` creates none.
+  #[napi]
+  pub fn auto_group_by_proximity(
+    &self,
+    page_id: String,
+    radius: f64,
+    measurement_type: MeasurementType,
+  ) -> u32 {
+    if !radius.is_finite() || radius < 0.0 {
+      return 0;
+    }
+    let mut candidates: Vec<(Measurement, Point)> = self
+      .get_measurements_by_page_id(page_id)
+      .iter()
+      .map(|wrapper| wrapper.get_measurement())
+      .filter(|measurement| {
+        measurement.kind() == measurement_type && !self.groups.contains_key(measurement.group_id())
+      })
+      .filter_map(|measurement| {
+        let centroid = measurement.get_centroid().ok()?;
+        Some((measurement, centroid))
+      })
+      .collect();
+    candidates.sort_by(|(a, _), (b, _)| a.id().cmp(b.id()));
+
+    // Union-find over candidate indices; the root is always the smallest index in the set.
+    let mut parent: Vec<usize> = (0..candidates.len()).collect();
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+      while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+      }
+      i
+    }
+    for (i, (_, a)) in candidates.iter().enumerate() {
+      for (j, (_, b)) in candidates.iter().enumerate().skip(i + 1) {
+        if a.distance_to(b) <= radius {
+          let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+          parent[a.max(b)] = a.min(b);
+        }
+      }
+    }
+
+    let mut group_ids: HashMap<usize, String> = HashMap::new();
+    for (i, (mut measurement, _)) in candidates.into_iter().enumerate() {
+      let root = find(&mut parent, i);
+      let group_id = group_ids
+        .entry(root)
+        .or_insert_with(|| {
+          let id = uuid::Uuid::new_v4().to_string();
+          self.upsert_group(Group {
+            id: id.clone(),
+            name: None,
+            measurement_type,
+            color: None,
+            unit_override: None,
+          });
+          id
+        })
+        .clone();
+      measurement.set_group_id(group_id);
+      self.upsert_measurement(measurement);
+    }
+    group_ids.len() as u32
+  }
+
+  /// Find measurements that share their geometry, e.g. after importing the same drawing twice.
+  ///
+  /// Measurements are duplicates when they are on the same page, have the same type and
+  /// their vertices match after rounding to 0.01 px (see `Measurement::geometry_hash`).
+  ///
+  /// # Returns
+  ///
+  /// * `Vec<Vec<String>>` - One list of ids per set of duplicates, each sorted by id; sets are
+  ///   ordered by their first id. Measurements without duplicates are omitted.
+  #[napi]
+  pub fn find_duplicate_measurements(&self) -> Vec<Vec<String>> {
+    let mut by_geometry: HashMap<(String, u64), Vec<String>> = HashMap::new();
+    for entry in self.measurements.iter() {
+      let measurement = entry.value().get_measurement();
+      by_geometry
+        .entry((
+          measurement.page_id().to_string(),
+          measurement.geometry_hash(),
+        ))
+        .or_default()
+        .push(entry.key().clone());
+    }
+    let mut duplicates: Vec<Vec<String>> = by_geometry
+      .into_values()
+      .filter(|ids| ids.len() > 1)
+      .map(|mut ids| {
+        ids.sort();
+        ids
+      })
+      .collect();
+    duplicates.sort();
+    duplicates
+  }
+
+  /// Get the measurements matching an optional page and an optional group.
+  ///
+  /// Only the filters that are provided are applied; with neither, every measurement is returned.
+  ///
+  /// # Arguments
+  ///
+  /// * `page_id` - The id of the page, if filtering by page.
+  /// * `group_id` - The id of the group, if filtering by group.
+  ///
+  /// # Returns
+  ///
+  /// * `Vec<MeasurementWrapper>` - The measurements that match every provided filter.
+  #[napi]
+  pub fn get_measurements(
+    &self,
+    page_id: Option<String>,
+    group_id: Option<String>,
+  ) -> Vec<MeasurementWrapper> {
+    self
+      .measurements
+      .iter()
+      .filter(|entry| {
+        let measurement = entry.value();
+        page_id
+          .as_ref()
+          .is_none_or(|id| measurement.page_id() == *id)
+          && group_id
+            .as_ref()
+            .is_none_or(|id| measurement.get_group_id() == *id)
+      })
+      .map(|entry| entry.value().clone())
+      .collect()
+  }
+
+  /// Get the measurements tagged with a metadata key/value pair.
+  ///
+  /// Both `key` and `value` are matched case-sensitively. Measurements without
+  /// metadata, or whose metadata does not contain `key`, are never returned.
+  ///
+  /// # Arguments
+  ///
+  /// * `key` - The metadata key, e.g. `"costCode"`.
+  /// * `value` - The value the key must map to, e.g. `"03-300"`.
+  ///
+  /// # Returns
+  ///
+  /// * `Vec<MeasurementWrapper>` - The measurements whose metadata contains the pair.
+  #[napi]
+  pub fn get_measurements_by_tag(&self, key: String, value: String) -> Vec<MeasurementWrapper> {
+    self
+      .measurements
+      .iter()
+      .filter(|entry| {
+        entry
+          .value()
+          .get_metadata()
+          .is_some_and(|metadata| metadata.get(&key) == Some(&value))
+      })
+      .map(|entry| entry.value().clone())
+      .collect()
+  }
+
+  /// Get the number of measurements in the state, without cloning them.
+  #[napi]
+  pub fn measurement_count(&self) -> u32 {
+    self.measurements.len() as u32
+  }
+
+  /// Get the number of groups in the state.
+  #[napi]
+  pub fn group_count(&self) -> u32 {
+    self.groups.len() as u32
+  }
+
+  /// Get the number of pages in the state.
+  #[napi]
+  pub fn page_count(&self) -> u32 {
+    self.pages.len() as u32
+  }
+
+  /// Get the number of scales in the state.
+  #[napi]
+  pub fn scale_count(&self) -> u32 {
+    self.scales.len() as u32
+  }
+
+  /// Get the total area of every measurement in the state, in `unit`.
+  ///
+  /// Measurements without a scale or without an area (polylines, counts) are skipped.
+  #[napi]
+  pub fn total_area(&self, unit: Unit) -> f64 {
+    self
+      .measurements
+      .iter()
+      .filter_map(|entry| entry.value().get_area_value().ok().flatten())
+      .map(|area| unit.convert_area_to_unit(area))
+      .sum()
+  }
+
+  /// Get the total length of every measurement in the state, in `unit`.
+  ///
+  /// Like group lengths, this includes polygon and rectangle perimeters.
+  /// Measurements without a scale are skipped.
+  #[napi]
+  pub fn total_length(&self, unit: Unit) -> f64 {
+    self
+      .measurements
+      .iter()
+      .filter_map(|entry| entry.value().get_length_value().ok().flatten())
+      .map(|length| unit.convert_length_to_unit(length))
+      .sum()
+  }
+
+  /// Get the area of every measurement in a group, in `unit`, in one call.
+  ///
+  /// Values follow the order of `get_measurements_by_group_id`. Entries are `None` for
+  /// measurements without a scale or without an area (polylines, counts).
+  #[napi]
+  pub fn convert_all_areas(&self, group_id: String, unit: Unit) -> Vec<Option<f64>> {
+    self
+      .get_measurements_by_group_id(group_id)
+      .iter()
+      .map(|measurement| {
+        let area = measurement.get_area_value().ok().flatten()?;
+        Some(unit.convert_area_to_unit(area))
+      })
+      .collect()
+  }
+
+  /// Get the length of every measurement in a group, in `unit`, in one call.
+  ///
+  /// Values follow the order of `get_measurements_by_group_id` and include polygon and
+  /// rectangle perimeters. Entries are `None` for measurements without a scale.
+  #[napi]
+  pub fn convert_all_lengths(&self, group_id: String, unit: Unit) -> Vec<Option<f64>> {
+    self
+      .get_measurements_by_group_id(group_id)
+      .iter()
+      .map(|measurement| {
+        let length = measurement.get_length_value().ok().flatten()?;
+        Some(unit.convert_length_to_unit(length))
+      })
+      .collect()
+  }
+
+  /// Get the combined area, length and count of a selection of measurements, in `unit`.
+  ///
+  /// Unknown and repeated ids are ignored. Measurements without a scale, or without an area
+  /// (polylines, counts), are skipped in the sums as for group totals, so an empty selection
+  /// reports zeros.
+  #[napi]
+  pub fn selection_totals(&self, measurement_ids: Vec<String>, unit: Unit) -> SelectionTotals {
+    let ids: HashSet<String> = measurement_ids.into_iter().collect();
+    let mut totals = SelectionTotals {
+      area: 0.0,
+      length: 0.0,
+      count: 0,
+    };
+    for measurement in ids.into_iter().filter_map(|id| self.get_measurement(id)) {
+      totals.count += 1;
+      if let Ok(Some(area)) = measurement.get_area_value() {
+        totals.area += unit.convert_area_to_unit(area);
+      }
+      if let Ok(Some(length)) = measurement.get_length_value() {
+        totals.length += unit.convert_length_to_unit(length);
+      }
+    }
+    totals
+  }
+
   fn add_initial_options(&self, options: StateOptions) {
+    let lazy = options.lazy.unwrap_or(false);
     for page in options.pages {
       self.pages.insert(page.id.clone(), page);
     }
@@ -96,16 +557,23 @@ impl TakeoffStateHandler {
       self.scales.insert(scale.id(), scale);
     }
     for group in options.groups {
-      self.groups.insert(
-        group.id.clone(),
-        GroupWrapper::new(group, self.self_arc.clone().unwrap()),
-      );
+      let state = self.self_arc.clone().unwrap();
+      let wrapper = if lazy {
+        GroupWrapper::new_lazy(group.clone(), state)
+      } else {
+        GroupWrapper::new(group.clone(), state)
+      };
+      self.groups.insert(group.id, wrapper);
     }
-    for measurement in options.measurements {
-      self.measurements.insert(
-        measurement.id().to_string(),
-        MeasurementWrapper::new(measurement, self.self_arc.clone().unwrap()),
-      );
+    for mut measurement in options.measurements {
+      measurement.normalize_winding();
+      let state = self.self_arc.clone().unwrap();
+      let wrapper = if lazy {
+        MeasurementWrapper::new_lazy(measurement, state)
+      } else {
+        MeasurementWrapper::new(measurement, state)
+      };
+      self.measurements.insert(wrapper.id(), wrapper);
     }
   }
 
@@ -150,13 +618,45 @@ impl TakeoffStateHandler {
     // self.find_measurement_scale(measurement)
   }
 
+  /// Get the scales of a page, ordered by id.
+  ///
+  /// The order makes scale resolution deterministic: when several scales could apply, the one
+  /// with the lowest id wins regardless of `DashMap` iteration order.
   pub fn get_page_scales(&self, page_id: &str) -> Vec<Scale> {
-    self
+    let mut scales = self
       .scales
       .iter()
       .filter(|entry| entry.value().page_id() == page_id)
       .map(|entry| entry.value().clone())
-      .collect::<Vec<Scale>>()
+      .collect::<Vec<Scale>>();
+    scales.sort_by_key(Scale::id);
+    scales
+  }
+
+  #[napi]
+  /// Get the page-wide (`Default`) scales of a page when there is more than one.
+  ///
+  /// Only one of them is used (the lowest id), so a non-empty result means measurements on the
+  /// page may be using a different scale than intended.
+  ///
+  /// # Arguments
+  ///
+  /// * `page_id` - The id of the page.
+  ///
+  /// # Returns
+  ///
+  /// * `Vec<Scale>` - The conflicting scales ordered by id, or empty if there is no conflict.
+  pub fn conflicting_default_scales(&self, page_id: String) -> Vec<Scale> {
+    let defaults: Vec<Scale> = self
+      .get_page_scales(&page_id)
+      .into_iter()
+      .filter(|scale| matches!(scale, Scale::Default { .. }))
+      .collect();
+    if defaults.len() > 1 {
+      defaults
+    } else {
+      vec![]
+    }
   }
 
   #[napi]
@@ -171,12 +671,95 @@ impl TakeoffStateHandler {
   /// * `None` - If the page was not found.
   /// * `Some(page)` - If the page was found and updated.
   pub fn upsert_page(&self, page: Page) -> Option<Page> {
+    self.bump_version();
     self.pages.insert(page.id.clone(), page)
   }
 
+  #[napi]
+  pub fn get_page(&self, page_id: String) -> Option<Page> {
+    self.pages.get(&page_id).map(|entry| entry.value().clone())
+  }
+
   #[napi]
   pub fn remove_page(&self, page_id: String) -> Option<Page> {
-    self.pages.remove(&page_id).map(|(_, page)| page)
+    let (_, page) = self.pages.remove(&page_id)?;
+    self.bump_version();
+    Some(page)
+  }
+
+  #[napi]
+  /// Removes everything from the state, keeping the handler itself.
+  ///
+  /// Use this to close a project without recreating the state.
+  pub fn clear(&self) {
+    self.measurements.clear();
+    self.groups.clear();
+    self.pages.clear();
+    self.scales.clear();
+    self.contours.clear();
+    self.volumes.clear();
+    self.measurement_versions.clear();
+    self.bump_version();
+  }
+
+  #[napi]
+  /// Removes a page together with its measurements, scales and contours.
+  ///
+  /// Groups are not tied to a page, so only groups left without any measurements are removed;
+  /// the remaining affected groups are recomputed. Volume computations against the page's
+  /// contours are removed as well.
+  ///
+  /// # Arguments
+  ///
+  /// * `page_id` - The id of the page to remove.
+  pub fn clear_page(&self, page_id: String) {
+    self.bump_version();
+    self.pages.remove(&page_id);
+    self.scales.retain(|_, scale| scale.page_id() != page_id);
+
+    let mut affected_groups = HashSet::new();
+    self.measurements.retain(|_, measurement| {
+      let keep = measurement.page_id() != page_id;
+      if !keep {
+        affected_groups.insert(measurement.get_group_id());
+      }
+      keep
+    });
+    self
+      .measurement_versions
+      .retain(|id, _| self.measurements.contains_key(id));
+
+    let mut removed_contours = HashSet::new();
+    self.contours.retain(|id, contour| {
+      let keep = contour.page_id() != page_id;
+      if !keep {
+        removed_contours.insert(id.clone());
+      }
+      keep
+    });
+    self.volumes.retain(|_, volume| {
+      let keep = !removed_contours.contains(&volume.contour_id);
+      if !keep {
+        affected_groups.insert(volume.group_id.clone());
+      }
+      keep
+    });
+
+    for group_id in affected_groups {
+      let is_empty = !self
+        .measurements
+        .iter()
+        .any(|entry| entry.value().get_group_id() == group_id)
+        && !self
+          .volumes
+          .iter()
+          .any(|entry| entry.value().group_id == group_id);
+      if is_empty {
+        self.groups.remove(&group_id);
+      } else {
+        let _ = self.compute_group(&group_id);
+      }
+    }
   }
 
   #[napi]
@@ -199,6 +782,7 @@ impl TakeoffStateHandler {
   /// * `None` - If the group was not found.
   /// * `Some(group)` - If the group was found and updated.
   pub fn upsert_group(&self, group: Group) -> Option<Group> {
+    self.bump_version();
     let group_clone = group.clone();
     self.groups.insert(
       group.id.clone(),
@@ -220,6 +804,7 @@ impl TakeoffStateHandler {
   pub fn remove_group(&self, group_id: String) -> Option<Group> {
     let res = self.groups.remove(&group_id);
     if let Some((_, group)) = res {
+      self.bump_version();
       // also remove any measurements tied to this group
       let to_remove: Vec<String> = self
         .measurements
@@ -230,12 +815,42 @@ impl TakeoffStateHandler {
       for mid in to_remove {
         self.remove_measurement(mid);
       }
+      self.volumes.retain(|_, volume| volume.group_id != group_id);
       return Some(group.get_group());
     }
 
     None
   }
 
+  #[napi]
+  /// Removes all measurements of a group while keeping the group itself, e.g. to start a
+  /// takeoff over without losing the group's settings.
+  ///
+  /// # Arguments
+  ///
+  /// * `group_id` - The id of the group to clear.
+  ///
+  /// # Returns
+  ///
+  /// * `u32` - The number of measurements removed.
+  pub fn clear_group_measurements(&self, group_id: String) -> u32 {
+    let mut removed = 0;
+    self.measurements.retain(|id, measurement| {
+      let keep = measurement.get_group_id() != group_id;
+      if !keep {
+        self.measurement_versions.remove(id);
+        removed += 1;
+      }
+      keep
+    });
+    if removed > 0 {
+      self.bump_version();
+      // Ignore recomputation errors - they will be handled when group values are accessed
+      let _ = self.compute_group(&group_id);
+    }
+    removed
+  }
+
   #[napi]
   /// Inserts or updates a measurement in the state.
   ///
@@ -243,15 +858,25 @@ impl TakeoffStateHandler {
   ///
   /// * `measurement` - The measurement to insert or update.
   ///
+  /// Polygons are stored with a counter-clockwise exterior ring. When updating,
+  /// metadata already stored on the measurement is kept if the incoming
+  /// measurement carries none.
+  ///
   /// # Returns
   ///
   /// * `None` - If the measurement was not found.
   /// * `Some(measurement)` - If the measurement was found and updated.
   pub fn upsert_measurement(&self, measurement: Measurement) -> Option<Measurement> {
+    let mut measurement = measurement;
+    measurement.normalize_winding();
     let id = measurement.id().to_string();
+    self.touch_measurement(&id);
 
     if let Some(prev_measurement) = self.measurements.get(&id) {
-      prev_measurement.set_measurement(measurement.clone());
+      if measurement.metadata().is_none() {
+        measurement.set_metadata(prev_measurement.get_metadata());
+      }
+      prev_measurement.set_measurement(measurement);
       return Some(prev_measurement.get_measurement());
     }
 
@@ -282,6 +907,8 @@ impl TakeoffStateHandler {
   pub fn remove_measurement(&self, measurement_id: String) -> Option<Measurement> {
     let res = self.measurements.remove(&measurement_id);
     if let Some((_, measurement)) = res {
+      self.measurement_versions.remove(&measurement_id);
+      self.bump_version();
       // Ignore recomputation errors - they will be handled when group values are accessed
       let _ = self.compute_group(&measurement.get_group_id());
       return Some(measurement.get_measurement());
@@ -290,27 +917,129 @@ impl TakeoffStateHandler {
   }
 
   #[napi]
-  pub fn get_measurement(&self, measurement_id: String) -> Option<MeasurementWrapper> {
-    self
-      .measurements
-      .get(&measurement_id)
-      .map(|entry| entry.value().clone())
+  /// Splits a polyline at a vertex, replacing it with two polylines.
+  ///
+  /// # Arguments
+  ///
+  /// * `measurement_id` - The id of the polyline to split.
+  /// * `index` - The vertex shared by both halves; must be an interior vertex.
+  /// * `first_id` - The id of the half from the start to `index`.
+  /// * `second_id` - The id of the half from `index` to the end.
+  ///
+  /// # Returns
+  ///
+  /// * `Vec<Measurement>` - The two new measurements.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if the measurement is not found or cannot be split, or if the new ids
+  /// are equal or already used by another measurement (either half may reuse
+  /// `measurement_id`); the state is unchanged in that case.
+  pub fn split_polyline(
+    &self,
+    measurement_id: String,
+    index: u32,
+    first_id: String,
+    second_id: String,
+  ) -> Result<Vec<Measurement>> {
+    let measurement = self
+      .get_measurement(measurement_id.clone())
+      .ok_or_else(|| {
+        TakeoffError::invalid_input(format!("measurement not found: {measurement_id}"))
+      })?
+      .get_measurement();
+    if first_id == second_id {
+      return Err(
+        TakeoffError::invalid_input(format!("split halves need distinct ids, got {first_id}"))
+          .into(),
+      );
+    }
+    if let Some(taken) = [&first_id, &second_id]
+      .into_iter()
+      .find(|id| **id != measurement_id && self.measurements.contains_key(*id))
+    {
+      return Err(
+        TakeoffError::invalid_input(format!("measurement id already in use: {taken}")).into(),
+      );
+    }
+    let (first, second) = measurement.split_polyline_at(index as usize, (first_id, second_id))?;
+    self.remove_measurement(measurement_id);
+    self.upsert_measurement(first.clone());
+    self.upsert_measurement(second.clone());
+    Ok(vec![first, second])
   }
 
   #[napi]
-  /// Inserts or updates a scale in the state.
+  /// Moves a measurement to another group, e.g. to reclassify it, recomputing both groups.
   ///
   /// # Arguments
   ///
-  /// * `scale` - The scale to insert or update.
+  /// * `measurement_id` - The id of the measurement to move.
+  /// * `new_group_id` - The id of the group to move it to.
   ///
-  /// # Returns
+  /// # Errors
   ///
-  /// * `None` - If the scale was not found.
+  /// Returns an error if the measurement or the target group is not found; the state is
+  /// unchanged in that case.
+  pub fn move_measurement_to_group(
+    &self,
+    measurement_id: String,
+    new_group_id: String,
+  ) -> Result<()> {
+    let wrapper = self
+      .get_measurement(measurement_id.clone())
+      .ok_or_else(|| {
+        TakeoffError::invalid_input(format!("measurement not found: {measurement_id}"))
+      })?;
+    if !self.groups.contains_key(&new_group_id) {
+      return Err(TakeoffError::invalid_input(format!("group not found: {new_group_id}")).into());
+    }
+    let old_group_id = wrapper.get_group_id();
+    if old_group_id == new_group_id {
+      return Ok(());
+    }
+
+    let mut measurement = wrapper.get_measurement();
+    measurement.set_group_id(new_group_id);
+    self.touch_measurement(&measurement_id);
+    // Recomputes the new group through the measurement's group id.
+    wrapper.set_measurement(measurement);
+    // Ignore recomputation errors - they will be handled when group values are accessed
+    let _ = self.compute_group(&old_group_id);
+    Ok(())
+  }
+
+  #[napi]
+  pub fn get_measurement(&self, measurement_id: String) -> Option<MeasurementWrapper> {
+    self
+      .measurements
+      .get(&measurement_id)
+      .map(|entry| entry.value().clone())
+  }
+
+  #[napi]
+  /// Inserts or updates a scale in the state.
+  ///
+  /// # Arguments
+  ///
+  /// * `scale` - The scale to insert or update.
+  ///
+  /// # Returns
+  ///
+  /// * `None` - If the scale was not found.
   /// * `Some(scale)` - If the scale was found and updated.
   pub fn upsert_scale(&self, scale: Scale) -> Option<Scale> {
+    self.bump_version();
     let page_id = scale.page_id();
+    let unchanged = self
+      .scales
+      .get(&scale.id())
+      .is_some_and(|prev| prev.page_id() == page_id && prev.equivalent_to(&scale));
     let res = self.scales.insert(scale.id(), scale);
+    // Re-saving the same definition does not change any value, so skip the recompute.
+    if unchanged {
+      return res;
+    }
     self.compute_page(&page_id);
     self.compute_contours(&page_id);
     res
@@ -329,6 +1058,7 @@ impl TakeoffStateHandler {
   pub fn remove_scale(&self, scale_id: String) -> Option<Scale> {
     let scale = self.scales.remove(&scale_id);
     if let Some((_, scale)) = scale {
+      self.bump_version();
       self.compute_page(&scale.page_id());
       self.compute_contours(&scale.page_id());
       return Some(scale);
@@ -336,9 +1066,18 @@ impl TakeoffStateHandler {
     None
   }
 
+  /// Get the fallback scale for measurements without a page scale, if one was configured.
+  #[napi]
+  pub fn get_default_scale(&self) -> Option<Scale> {
+    self.default_scale.clone()
+  }
+
   #[napi]
   /// Get the measurements that are missing a scale.
   ///
+  /// Measurements measured with the fallback `default_scale` are included, since they are not
+  /// calibrated either.
+  ///
   /// # Returns
   ///
   /// * `Vec<MeasurementWrapper>` - The measurements that are missing a scale.
@@ -346,30 +1085,193 @@ impl TakeoffStateHandler {
     self
       .measurements
       .iter()
-      .filter(|entry| entry.value().get_scale().is_none())
+      .filter(|entry| {
+        let measurement = entry.value();
+        measurement.get_scale().is_none() || measurement.is_using_fallback_scale()
+      })
       .map(|entry| entry.value().clone())
       .collect()
   }
 
+  #[napi]
+  /// Get the measurements of a page with a vertex off the page, e.g. to warn about a bad
+  /// import (see `Measurement::is_within_page`).
+  ///
+  /// # Arguments
+  ///
+  /// * `page_id` - The id of the page.
+  ///
+  /// # Returns
+  ///
+  /// * `Vec<MeasurementWrapper>` - The offending measurements ordered by id, or empty if the
+  ///   page is not found or declares no dimensions.
+  pub fn measurements_outside_page(&self, page_id: String) -> Vec<MeasurementWrapper> {
+    let Some(page) = self.get_page(page_id.clone()) else {
+      return vec![];
+    };
+    let mut outside: Vec<MeasurementWrapper> = self
+      .get_measurements_by_page_id(page_id)
+      .into_iter()
+      .filter(|measurement| !measurement.get_measurement().is_within_page(&page))
+      .collect();
+    outside.sort_by_key(|measurement| measurement.id());
+    outside
+  }
+
   #[napi]
   pub fn upsert_contour(&self, input: ContourInput) {
     // let input: takeoff_core::contour::ContourInput = contour.into();
+    self.bump_version();
     let id = input.id.clone();
 
     if let Some(existing) = self.contours.get(&id) {
       existing.set_contour(input);
       existing.calculate_scale();
+      drop(existing);
+      self.compute_contour_volume_groups(&id);
       return;
     }
     // let state = ;
     let wrapper = ContourWrapper::from_input(input, self.self_arc.clone().unwrap());
     wrapper.calculate_scale();
-    self.contours.insert(id, wrapper);
+    self.contours.insert(id.clone(), wrapper);
+    self.compute_contour_volume_groups(&id);
   }
 
   #[napi]
   pub fn remove_contour(&self, contour_id: String) -> bool {
-    self.contours.remove(&contour_id).is_some()
+    let removed = self.contours.remove(&contour_id).is_some();
+    if removed {
+      self.bump_version();
+      self.compute_contour_volume_groups(&contour_id);
+    }
+    removed
+  }
+
+  #[napi]
+  /// Get the over-terrain (slope) length of a measurement draped over a contour's surface.
+  ///
+  /// Polygons and rectangles are measured around their closed perimeter. With
+  /// `max_segment_length` (pixels) the path is densified first so grade changes between
+  /// vertices are followed.
+  ///
+  /// Returns `None` if the measurement or contour is not found, the measurement is a count,
+  /// or the contour has no surface mesh or scale.
+  pub fn get_drape_length(
+    &self,
+    measurement_id: String,
+    contour_id: String,
+    max_segment_length: Option<f64>,
+  ) -> Option<UnitValue> {
+    let points = self.drape_path(measurement_id, max_segment_length)?;
+    let contour = self.get_contour(contour_id)?;
+    contour.drape_length(&points).map(UnitValue::from_length)
+  }
+
+  #[napi]
+  /// Get the percent grade of each segment of a measurement draped over a contour's surface.
+  ///
+  /// Segments follow the same path as `get_drape_length`, so with `max_segment_length` the
+  /// grades are reported per densified segment. A segment's grade is `None` where it leaves
+  /// the surface or has no horizontal run.
+  ///
+  /// Returns `None` if the measurement or contour is not found, the measurement is a count,
+  /// or the contour has no surface mesh.
+  pub fn get_drape_slopes(
+    &self,
+    measurement_id: String,
+    contour_id: String,
+    max_segment_length: Option<f64>,
+  ) -> Option<Vec<Option<f64>>> {
+    let points = self.drape_path(measurement_id, max_segment_length)?;
+    self.get_contour(contour_id)?.drape_slopes(&points)
+  }
+
+  /// The path of a measurement as draped over a surface, optionally densified.
+  fn drape_path(
+    &self,
+    measurement_id: String,
+    max_segment_length: Option<f64>,
+  ) -> Option<Vec<Point>> {
+    let measurement = self.get_measurement(measurement_id)?.get_measurement();
+    let mut points: Vec<Point> = measurement
+      .to_line_string()
+      .ok()?
+      .into_iter()
+      .map(Point::from)
+      .collect();
+    if let Some(max_segment_length) = max_segment_length {
+      points = densify_polyline(points, max_segment_length);
+    }
+    Some(points)
+  }
+
+  #[napi]
+  /// Inserts or updates a volume computation in the state.
+  ///
+  /// The group it belongs to (and its previous group, if it moved) is recomputed.
+  ///
+  /// # Arguments
+  ///
+  /// * `volume` - The volume computation to insert or update.
+  ///
+  /// # Returns
+  ///
+  /// * `None` - If the volume computation was not found.
+  /// * `Some(volume)` - The previous volume computation if it was found and updated.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error if `cell_size` is set but not a positive finite number; the state is
+  /// unchanged in that case.
+  pub fn upsert_volume(&self, volume: VolumeComputation) -> Result<Option<VolumeComputation>> {
+    if let Some(cell_size) = volume.cell_size {
+      if !(cell_size.is_finite() && cell_size > 0.0) {
+        return Err(
+          TakeoffError::invalid_input(format!(
+            "cell_size must be a positive number, got {cell_size}"
+          ))
+          .into(),
+        );
+      }
+    }
+    self.bump_version();
+    let group_id = volume.group_id.clone();
+    let res = self.volumes.insert(volume.id.clone(), volume);
+    if let Some(prev) = res.as_ref() {
+      if prev.group_id != group_id {
+        let _ = self.compute_group(&prev.group_id);
+      }
+    }
+    let _ = self.compute_group(&group_id);
+    Ok(res)
+  }
+
+  #[napi]
+  /// Removes a volume computation from the state.
+  ///
+  /// # Arguments
+  ///
+  /// * `volume_id` - The id of the volume computation to remove.
+  ///
+  /// # Returns
+  /// * `None` - If the volume computation was not found.
+  /// * `Some(volume)` - If the volume computation was found and removed.
+  pub fn remove_volume(&self, volume_id: String) -> Option<VolumeComputation> {
+    let (_, volume) = self.volumes.remove(&volume_id)?;
+    self.bump_version();
+    let _ = self.compute_group(&volume.group_id);
+    Some(volume)
+  }
+
+  #[napi]
+  pub fn get_volumes_by_group_id(&self, group_id: String) -> Vec<VolumeComputation> {
+    self
+      .volumes
+      .iter()
+      .filter(|entry| entry.value().group_id == group_id)
+      .map(|entry| entry.value().clone())
+      .collect()
   }
 
   #[napi]
@@ -409,6 +1311,20 @@ impl TakeoffStateHandler {
       .collect();
     for contour in contours {
       contour.calculate_scale();
+      self.compute_contour_volume_groups(&contour.id());
+    }
+  }
+
+  /// Recompute the groups of all volume computations that use the contour.
+  fn compute_contour_volume_groups(&self, contour_id: &str) {
+    let group_ids: HashSet<String> = self
+      .volumes
+      .iter()
+      .filter(|entry| entry.value().contour_id == contour_id)
+      .map(|entry| entry.value().group_id.clone())
+      .collect();
+    for group_id in group_ids {
+      let _ = self.compute_group(&group_id);
     }
   }
 }
@@ -427,14 +1343,13 @@ impl TakeoffStateHandler {
     }
   }
 
-  pub fn compute_group(&self, group_id: &str) -> Result<()> {
+  pub fn compute_group(&self, group_id: &str) -> TakeoffResult<()> {
     let group = self.groups.get(group_id);
     if let Some(group) = group {
-      std::thread::scope(|s| {
-        s.spawn(|| {
-          // Ignore recomputation errors - they will be handled when group values are accessed
-          let _ = group.recompute_measurements();
-        });
+      return std::thread::scope(|s| {
+        s.spawn(|| group.recompute_measurements())
+          .join()
+          .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
       });
     }
     Ok(())
@@ -451,10 +1366,10 @@ impl TakeoffStateHandler {
     }
   }
 
-  pub fn compute_group(&self, group_id: &str) -> Result<()> {
+  pub fn compute_group(&self, group_id: &str) -> TakeoffResult<()> {
     let group = self.groups.get(group_id);
     if let Some(group) = group {
-      group.recompute_measurements().unwrap();
+      group.recompute_measurements()?;
     }
     Ok(())
   }
@@ -465,11 +1380,12 @@ mod tests {
   use super::*;
   use takeoff_core::contour::ContourLineInput;
   use takeoff_core::coords::Point;
-  use takeoff_core::group::MeasurementType;
   use takeoff_core::measurement::Measurement::*;
+  use takeoff_core::page::CoordinateSystem;
   use takeoff_core::scale::Scale::*;
   use takeoff_core::scale::ScaleDefinition;
   use takeoff_core::unit::Unit;
+  use takeoff_core::volume::ReferenceSurfaceInput;
 
   #[test]
   fn test_find_measurement_scale() {
@@ -478,6 +1394,8 @@ mod tests {
       groups: vec![],
       measurements: vec![],
       scales: vec![],
+      lazy: None,
+      default_scale: None,
     }));
     state.upsert_scale(Area {
       id: "1".to_string(),
@@ -508,6 +1426,7 @@ mod tests {
         Point::new(1.0, 1.0),
         Point::new(0.5, 1.0),
       ],
+      metadata: None,
     };
     state.upsert_measurement(measurement.clone());
     let scale = state.get_measurement_scale(measurement.id().to_string());
@@ -525,10 +1444,9 @@ mod tests {
       })
     );
 
-    let measurement = state
-      .measurements
-      .get(&measurement.id().to_string())
-      .unwrap();
+    // Clone the wrappers out of the maps: holding a map guard while the state writes to the
+    // same shard deadlocks.
+    let measurement = state.get_measurement(measurement.id().to_string()).unwrap();
     let measurement_clone = measurement.clone();
     assert_eq!(
       measurement.get_scale(),
@@ -555,9 +1473,11 @@ mod tests {
       id: "1".to_string(),
       name: None,
       measurement_type: MeasurementType::Area,
+      color: None,
+      unit_override: None,
     };
     state.upsert_group(group);
-    let group = state.groups.get("1").unwrap();
+    let group = state.get_group("1".to_string()).unwrap();
     let group_clone = group.clone();
     assert_eq!(
       group.get_area().unwrap().get_converted_value(Unit::Meters),
@@ -569,6 +1489,8 @@ mod tests {
       page_id: "1".to_string(),
       group_id: "1".to_string(),
       points: (Point::new(0.0, 0.0), Point::new(1.0, 1.0)),
+      rotation: None,
+      metadata: None,
     });
 
     let initial_group_area = {
@@ -577,7 +1499,7 @@ mod tests {
         .unwrap()
         .get_converted_value(Unit::Meters)
     };
-    println!("initial_group_area: {}", initial_group_area);
+    assert_eq!(initial_group_area, 1.25);
 
     let measurement = state.remove_measurement("12".to_string());
     assert!(measurement.is_some());
@@ -594,6 +1516,333 @@ mod tests {
 
     // let group_removed = state.remove_group("1".to_string());
   }
+  #[test]
+  fn test_upsert_measurement_preserves_metadata() {
+    let state = TakeoffStateHandler::new(None);
+    let metadata = std::collections::HashMap::from([("phase".to_string(), "2".to_string())]);
+    state.upsert_measurement(Polyline {
+      id: "m1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)],
+      metadata: Some(metadata.clone()),
+    });
+
+    state.upsert_measurement(Polyline {
+      id: "m1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(2.0, 0.0)],
+      metadata: None,
+    });
+
+    let measurement = state.get_measurement("m1".to_string()).unwrap();
+    assert_eq!(measurement.get_metadata(), Some(metadata));
+    assert_eq!(measurement.raw_perimeter().unwrap(), 2.0);
+  }
+
+  #[test]
+  fn test_get_measurements_by_tag() {
+    let state = TakeoffStateHandler::new(None);
+    let tagged = |id: &str, cost_code: Option<&str>| Polyline {
+      id: id.to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)],
+      metadata: cost_code
+        .map(|code| std::collections::HashMap::from([("costCode".to_string(), code.to_string())])),
+    };
+    state.upsert_measurement(tagged("m1", Some("03-300")));
+    state.upsert_measurement(tagged("m2", Some("03-310")));
+    state.upsert_measurement(tagged("m3", None));
+
+    let found = state.get_measurements_by_tag("costCode".to_string(), "03-300".to_string());
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id(), "m1");
+
+    assert!(state
+      .get_measurements_by_tag("costcode".to_string(), "03-300".to_string())
+      .is_empty());
+    assert!(state
+      .get_measurements_by_tag("phase".to_string(), "03-300".to_string())
+      .is_empty());
+  }
+
+  #[test]
+  fn test_get_measurements_by_page_and_group() {
+    let state = TakeoffStateHandler::new(None);
+    let line = |id: &str, page_id: &str, group_id: &str| Polyline {
+      id: id.to_string(),
+      page_id: page_id.to_string(),
+      group_id: group_id.to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)],
+      metadata: None,
+    };
+    state.upsert_measurement(line("m1", "p1", "g1"));
+    state.upsert_measurement(line("m2", "p1", "g2"));
+    state.upsert_measurement(line("m3", "p2", "g1"));
+
+    let ids = |page_id: Option<&str>, group_id: Option<&str>| {
+      let mut ids: Vec<String> = state
+        .get_measurements(page_id.map(String::from), group_id.map(String::from))
+        .iter()
+        .map(|m| m.id().to_string())
+        .collect();
+      ids.sort();
+      ids
+    };
+    assert_eq!(ids(Some("p1"), Some("g1")), vec!["m1"]);
+    assert_eq!(ids(Some("p1"), None), vec!["m1", "m2"]);
+    assert_eq!(ids(None, Some("g1")), vec!["m1", "m3"]);
+    assert_eq!(ids(None, None), vec!["m1", "m2", "m3"]);
+  }
+
+  #[test]
+  fn test_get_measurements_page() {
+    let state = TakeoffStateHandler::new(None);
+    for id in ["m3", "m1", "m4", "m2"] {
+      state.upsert_measurement(Polyline {
+        id: id.to_string(),
+        page_id: "1".to_string(),
+        group_id: "1".to_string(),
+        points: vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)],
+        metadata: None,
+      });
+    }
+
+    let ids = |offset: u32, limit: u32| {
+      let page = state.get_measurements_page("1".to_string(), offset, limit);
+      assert_eq!(page.total, 4);
+      page
+        .measurements
+        .iter()
+        .map(|m| m.id().to_string())
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(ids(0, 2), vec!["m1", "m2"]);
+    assert_eq!(ids(2, 2), vec!["m3", "m4"]);
+    assert_eq!(ids(3, 10), vec!["m4"]);
+    assert!(ids(10, 2).is_empty());
+    assert_eq!(state.get_measurements_page("2".to_string(), 0, 10).total, 0);
+  }
+
+  #[test]
+  fn test_measurement_at_uses_z_index_then_area() {
+    let state = TakeoffStateHandler::new(None);
+    for (id, max) in [("big", 10.0), ("small", 5.0), ("same", 5.0)] {
+      state.upsert_measurement(Rectangle {
+        id: id.to_string(),
+        page_id: "1".to_string(),
+        group_id: "1".to_string(),
+        points: (Point::new(0.0, 0.0), Point::new(max, max)),
+        rotation: None,
+        metadata: None,
+      });
+    }
+    let top = |x: f64, y: f64| {
+      state
+        .measurement_at("1".to_string(), Point::new(x, y))
+        .map(|m| m.id())
+    };
+
+    // Smallest area wins, then lowest id.
+    assert_eq!(top(1.0, 1.0).as_deref(), Some("same"));
+    assert_eq!(top(8.0, 8.0).as_deref(), Some("big"));
+    assert_eq!(top(20.0, 20.0), None);
+    assert_eq!(
+      state
+        .measurement_at("2".to_string(), Point::new(1.0, 1.0))
+        .map(|m| m.id()),
+      None
+    );
+
+    let big = state.get_measurement("big".to_string()).unwrap();
+    big.set_z_index(Some(1)).unwrap();
+    assert_eq!(top(1.0, 1.0).as_deref(), Some("big"));
+
+    // The z-index survives an update of the geometry.
+    state.upsert_measurement(Rectangle {
+      id: "big".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(12.0, 12.0)),
+      rotation: None,
+      metadata: None,
+    });
+    assert_eq!(top(1.0, 1.0).as_deref(), Some("big"));
+  }
+
+  #[test]
+  fn test_reorder_measurement() {
+    let state = TakeoffStateHandler::new(None);
+    for id in ["a", "b", "c", "d"] {
+      state.upsert_measurement(Count {
+        id: id.to_string(),
+        page_id: "1".to_string(),
+        group_id: "1".to_string(),
+        points: (Point::new(0.0, 0.0),),
+        metadata: None,
+      });
+    }
+    let ids = || {
+      state
+        .get_measurements_by_group_id("1".to_string())
+        .iter()
+        .map(|m| m.id())
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(ids(), vec!["a", "b", "c", "d"]);
+
+    state.reorder_measurement("c".to_string(), 0).unwrap();
+    state.reorder_measurement("a".to_string(), 5).unwrap();
+    assert_eq!(ids(), vec!["c", "a", "b", "d"]);
+    assert!(state.reorder_measurement("x".to_string(), 1).is_err());
+  }
+
+  #[test]
+  fn test_auto_group_by_proximity() {
+    let state = TakeoffStateHandler::new(None);
+    state.upsert_group(Group {
+      id: "existing".to_string(),
+      name: None,
+      measurement_type: MeasurementType::Count,
+      color: None,
+      unit_override: None,
+    });
+    let count = |id: &str, group_id: &str, x: f64| Count {
+      id: id.to_string(),
+      page_id: "1".to_string(),
+      group_id: group_id.to_string(),
+      points: (Point::new(x, 0.0),),
+      metadata: None,
+    };
+    // c1-c2-c3 chain together; c4 is alone.
+    state.upsert_measurement(count("c3", "", 20.0));
+    state.upsert_measurement(count("c1", "", 0.0));
+    state.upsert_measurement(count("c2", "imported", 10.0));
+    state.upsert_measurement(count("c4", "", 100.0));
+    // Already grouped, or another kind: untouched.
+    state.upsert_measurement(count("c5", "existing", 5.0));
+    state.upsert_measurement(Polyline {
+      id: "l1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)],
+      metadata: None,
+    });
+
+    let created = state.auto_group_by_proximity("1".to_string(), 10.0, MeasurementType::Count);
+    assert_eq!(created, 2);
+    assert_eq!(state.group_count(), 3);
+
+    let group_of = |id: &str| {
+      state
+        .get_measurement(id.to_string())
+        .unwrap()
+        .get_group_id()
+    };
+    assert_eq!(group_of("c1"), group_of("c2"));
+    assert_eq!(group_of("c1"), group_of("c3"));
+    assert_ne!(group_of("c1"), group_of("c4"));
+    assert_eq!(group_of("c5"), "existing");
+    assert_eq!(group_of("l1"), "");
+    let group = state.get_group(group_of("c1")).unwrap();
+    assert_eq!(group.get_group().measurement_type, MeasurementType::Count);
+    assert_eq!(state.get_measurements_by_group_id(group_of("c1")).len(), 3);
+
+    // Everything is grouped now.
+    assert_eq!(
+      state.auto_group_by_proximity("1".to_string(), 10.0, MeasurementType::Count),
+      0
+    );
+    assert_eq!(
+      state.auto_group_by_proximity("1".to_string(), -1.0, MeasurementType::Linear),
+      0
+    );
+  }
+
+  #[test]
+  fn test_find_duplicate_measurements() {
+    let state = TakeoffStateHandler::new(None);
+    let line = |id: &str, page_id: &str, end: f64| Polyline {
+      id: id.to_string(),
+      page_id: page_id.to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(end, 0.0)],
+      metadata: None,
+    };
+    state.upsert_measurement(line("m1", "1", 10.0));
+    state.upsert_measurement(line("m3", "1", 10.001));
+    state.upsert_measurement(line("m2", "1", 10.0));
+    state.upsert_measurement(line("m4", "1", 20.0));
+    // Same geometry on another page is not a duplicate.
+    state.upsert_measurement(line("m5", "2", 10.0));
+
+    assert_eq!(
+      state.find_duplicate_measurements(),
+      vec![vec!["m1".to_string(), "m2".to_string(), "m3".to_string()]]
+    );
+  }
+
+  #[test]
+  fn test_default_scale_fallback() {
+    let state = TakeoffStateHandler::new(Some(StateOptions {
+      pages: vec![],
+      groups: vec![],
+      measurements: vec![],
+      scales: vec![],
+      lazy: None,
+      default_scale: Some(Default {
+        id: "fallback".to_string(),
+        page_id: "".to_string(),
+        scale: ScaleDefinition {
+          pixel_distance: 1.0,
+          real_distance: 1.0,
+          unit: Unit::Feet,
+        },
+      }),
+    }));
+    state.upsert_measurement(Polyline {
+      id: "m1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(12.0, 0.0)],
+      metadata: None,
+    });
+    let measurement = state.get_measurement("m1".to_string()).unwrap();
+    assert!(measurement.is_using_fallback_scale());
+    let length = measurement
+      .get_length()
+      .unwrap()
+      .unwrap()
+      .get_converted_value(Unit::Feet);
+    assert!((length - 12.0).abs() < 1e-9);
+    assert_eq!(state.get_measurements_missing_scale().len(), 1);
+
+    // A real page scale takes over.
+    state.upsert_scale(Default {
+      id: "s1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 2.0,
+        real_distance: 1.0,
+        unit: Unit::Feet,
+      },
+    });
+    assert!(!measurement.is_using_fallback_scale());
+    let length = measurement
+      .get_length()
+      .unwrap()
+      .unwrap()
+      .get_converted_value(Unit::Feet);
+    assert!((length - 6.0).abs() < 1e-9);
+    assert!(state.get_measurements_missing_scale().is_empty());
+
+    // Without a configured fallback nothing changes.
+    let state = TakeoffStateHandler::new(None);
+    assert!(state.get_default_scale().is_none());
+  }
+
   #[test]
   fn test_remove_group() {
     let state = TakeoffStateHandler::new(Some(StateOptions {
@@ -601,11 +1850,15 @@ mod tests {
       groups: vec![],
       measurements: vec![],
       scales: vec![],
+      lazy: None,
+      default_scale: None,
     }));
     let group = Group {
       id: "1".to_string(),
       name: None,
       measurement_type: MeasurementType::Area,
+      color: None,
+      unit_override: None,
     };
     state.upsert_group(group);
     // let group = state.groups.get("1").unwrap();
@@ -617,28 +1870,1046 @@ mod tests {
   }
 
   #[test]
-  fn test_upsert_contour_with_deferred_scale() {
-    let state = TakeoffStateHandler::new(None);
-    state.upsert_contour(ContourInput {
-      id: "c1".to_string(),
-      name: None,
-      page_id: "1".to_string(),
-      lines: vec![ContourLineInput {
-        elevation: 10.0,
-        unit: Unit::Feet,
-        points: vec![
-          Point::new(0.0, 0.0),
-          Point::new(100.0, 0.0),
-          Point::new(100.0, 100.0),
-          Point::new(0.0, 100.0),
-        ],
+  fn test_lazy_state_matches_eager() {
+    let options = |lazy| StateOptions {
+      pages: vec![],
+      groups: vec![Group {
+        id: "g1".to_string(),
+        name: None,
+        measurement_type: MeasurementType::Area,
+        color: None,
+        unit_override: None,
       }],
-      points_of_interest: vec![],
-    });
+      measurements: vec![Rectangle {
+        id: "m1".to_string(),
+        page_id: "1".to_string(),
+        group_id: "g1".to_string(),
+        points: (Point::new(0.0, 0.0), Point::new(4.0, 2.0)),
+        rotation: None,
+        metadata: None,
+      }],
+      scales: vec![Default {
+        id: "s1".to_string(),
+        page_id: "1".to_string(),
+        scale: ScaleDefinition {
+          pixel_distance: 2.0,
+          real_distance: 1.0,
+          unit: Unit::Meters,
+        },
+      }],
+      lazy: Some(lazy),
+      default_scale: None,
+    };
+    let eager = TakeoffStateHandler::new(Some(options(false)));
+    let lazy = TakeoffStateHandler::new(Some(options(true)));
 
-    // No scale yet — mesh should be None
-    let contour = state.get_contour("c1".to_string()).unwrap();
-    assert!(contour.get_surface_points().is_none());
+    let area = |state: &TakeoffStateHandler| {
+      state
+        .get_group("g1".to_string())
+        .and_then(|group| group.get_converted_area(Some(Unit::Meters)))
+    };
+    assert_eq!(area(&lazy), Some(2.0));
+    assert_eq!(area(&lazy), area(&eager));
+    let measurement = lazy.get_measurement("m1".to_string()).unwrap();
+    assert_eq!(measurement.get_scale().unwrap().id(), "s1");
+  }
+
+  #[test]
+  fn test_split_polyline() {
+    let state = TakeoffStateHandler::new(None);
+    state.upsert_measurement(Polyline {
+      id: "m1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![
+        Point::new(0.0, 0.0),
+        Point::new(1.0, 0.0),
+        Point::new(2.0, 0.0),
+      ],
+      metadata: None,
+    });
+
+    let halves = state
+      .split_polyline("m1".to_string(), 1, "a".to_string(), "b".to_string())
+      .unwrap();
+    assert_eq!(halves.len(), 2);
+    assert!(state.get_measurement("m1".to_string()).is_none());
+    assert!(state.get_measurement("a".to_string()).is_some());
+    assert!(state.get_measurement("b".to_string()).is_some());
+
+    assert!(state
+      .split_polyline("a".to_string(), 0, "c".to_string(), "d".to_string())
+      .is_err());
+    assert!(state.get_measurement("a".to_string()).is_some());
+
+    state.upsert_measurement(Polyline {
+      id: "m2".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![
+        Point::new(0.0, 0.0),
+        Point::new(1.0, 0.0),
+        Point::new(2.0, 0.0),
+      ],
+      metadata: None,
+    });
+    for (first, second) in [("c", "c"), ("a", "c"), ("c", "b")] {
+      assert!(state
+        .split_polyline("m2".to_string(), 1, first.to_string(), second.to_string())
+        .is_err());
+      assert!(state.get_measurement("m2".to_string()).is_some());
+      assert!(state.get_measurement("c".to_string()).is_none());
+    }
+    assert!(state
+      .split_polyline("m2".to_string(), 1, "m2".to_string(), "c".to_string())
+      .is_ok());
+    assert!(state.get_measurement("m2".to_string()).is_some());
+    assert!(state.get_measurement("c".to_string()).is_some());
+  }
+
+  #[test]
+  fn test_conflicting_default_scales_resolve_to_lowest_id() {
+    let state = TakeoffStateHandler::new(None);
+    let default = |id: &str, pixel_distance: f64| Default {
+      id: id.to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    };
+    state.upsert_scale(default("b", 2.0));
+    assert!(state.conflicting_default_scales("1".to_string()).is_empty());
+
+    state.upsert_scale(default("c", 3.0));
+    state.upsert_scale(default("a", 1.0));
+    let ids: Vec<String> = state
+      .conflicting_default_scales("1".to_string())
+      .iter()
+      .map(Scale::id)
+      .collect();
+    assert_eq!(ids, vec!["a", "b", "c"]);
+
+    state.upsert_measurement(Polyline {
+      id: "m1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)],
+      metadata: None,
+    });
+    assert_eq!(
+      state.get_measurement_scale("m1".to_string()).unwrap().id(),
+      "a"
+    );
+  }
+
+  #[test]
+  fn test_version_counter() {
+    let state = TakeoffStateHandler::new(None);
+    assert_eq!(state.version(), 0.0);
+    let count = |id: &str| Count {
+      id: id.to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0),),
+      metadata: None,
+    };
+
+    state.upsert_measurement(count("a"));
+    state.upsert_measurement(count("b"));
+    assert_eq!(state.version(), 2.0);
+    assert_eq!(state.measurement_version("a".to_string()), Some(1.0));
+    assert_eq!(state.measurement_version("b".to_string()), Some(2.0));
+
+    // Updating one measurement does not touch the other's version.
+    state.upsert_measurement(count("a"));
+    assert_eq!(state.measurement_version("a".to_string()), Some(3.0));
+    assert_eq!(state.measurement_version("b".to_string()), Some(2.0));
+
+    // Removing nothing is not a change.
+    state.remove_measurement("x".to_string());
+    assert_eq!(state.version(), 3.0);
+    state.remove_measurement("a".to_string());
+    assert_eq!(state.version(), 4.0);
+    assert_eq!(state.measurement_version("a".to_string()), None);
+
+    state.upsert_page(Page {
+      id: "1".to_string(),
+      name: None,
+      width: None,
+      height: None,
+      viewport: None,
+      coordinate_system: None,
+    });
+    assert_eq!(state.version(), 5.0);
+  }
+
+  #[test]
+  fn test_wrapper_edits_bump_versions() {
+    let state = TakeoffStateHandler::new(None);
+    state.upsert_measurement(Polyline {
+      id: "a".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)],
+      metadata: None,
+    });
+    assert_eq!(state.version(), 1.0);
+    let wrapper = state.get_measurement("a".to_string()).unwrap();
+
+    wrapper
+      .update_points(vec![Point::new(0.0, 0.0), Point::new(2.0, 0.0)])
+      .unwrap();
+    wrapper.insert_vertex(1, Point::new(1.0, 0.0)).unwrap();
+    wrapper.remove_vertex(1).unwrap();
+    wrapper.set_z_index(Some(1)).unwrap();
+    wrapper.set_metadata(None).unwrap();
+    wrapper
+      .set_scale_override(Some(Scale::Default {
+        id: "s".to_string(),
+        page_id: "1".to_string(),
+        scale: ScaleDefinition {
+          pixel_distance: 1.0,
+          real_distance: 1.0,
+          unit: Unit::Meters,
+        },
+      }))
+      .unwrap();
+    assert_eq!(state.version(), 7.0);
+    assert_eq!(state.measurement_version("a".to_string()), Some(7.0));
+
+    // A rejected edit leaves the versions alone.
+    assert!(wrapper.remove_vertex(5).is_err());
+    assert_eq!(state.version(), 7.0);
+  }
+
+  #[test]
+  fn test_geodesic_area_requires_lon_lat_page() {
+    let state = TakeoffStateHandler::new(None);
+    for (id, coordinate_system) in [("geo", Some(CoordinateSystem::LonLat)), ("plan", None)] {
+      state.upsert_page(Page {
+        id: id.to_string(),
+        name: None,
+        width: None,
+        height: None,
+        viewport: None,
+        coordinate_system,
+      });
+      state.upsert_measurement(Rectangle {
+        id: id.to_string(),
+        page_id: id.to_string(),
+        group_id: "1".to_string(),
+        points: (Point::new(0.0, 0.0), Point::new(0.001, 0.001)),
+        rotation: None,
+        metadata: None,
+      });
+    }
+
+    let area = state
+      .get_measurement("geo".to_string())
+      .unwrap()
+      .get_geodesic_area()
+      .unwrap()
+      .get_converted_value(Unit::Meters);
+    // 0.001° is about 111 m at the equator.
+    assert!((area - 12_309.0).abs() < 10.0);
+    assert!(state
+      .get_measurement("plan".to_string())
+      .unwrap()
+      .get_geodesic_area()
+      .is_err());
+  }
+
+  #[test]
+  fn test_clear_page() {
+    let state = TakeoffStateHandler::new(None);
+    for (page_id, group_id) in [("1", "only-1"), ("2", "shared")] {
+      state.upsert_page(Page {
+        id: page_id.to_string(),
+        name: None,
+        width: None,
+        height: None,
+        viewport: None,
+        coordinate_system: None,
+      });
+      state.upsert_scale(Default {
+        id: format!("s{page_id}"),
+        page_id: page_id.to_string(),
+        scale: ScaleDefinition {
+          pixel_distance: 1.0,
+          real_distance: 1.0,
+          unit: Unit::Meters,
+        },
+      });
+      state.upsert_group(Group {
+        id: group_id.to_string(),
+        name: None,
+        measurement_type: MeasurementType::Linear,
+        color: None,
+        unit_override: None,
+      });
+    }
+    let line = |id: &str, page_id: &str, group_id: &str| Polyline {
+      id: id.to_string(),
+      page_id: page_id.to_string(),
+      group_id: group_id.to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)],
+      metadata: None,
+    };
+    state.upsert_measurement(line("m1", "1", "only-1"));
+    state.upsert_measurement(line("m2", "1", "shared"));
+    state.upsert_measurement(line("m3", "2", "shared"));
+
+    state.clear_page("1".to_string());
+    assert_eq!(state.page_count(), 1);
+    assert_eq!(state.scale_count(), 1);
+    assert_eq!(state.measurement_count(), 1);
+    assert!(state.get_group("only-1".to_string()).is_none());
+    let shared = state.get_group("shared".to_string()).unwrap();
+    assert_eq!(shared.get_count(), Some(1.0));
+
+    state.clear();
+    assert_eq!(state.page_count(), 0);
+    assert_eq!(state.scale_count(), 0);
+    assert_eq!(state.measurement_count(), 0);
+    assert_eq!(state.group_count(), 0);
+  }
+
+  #[test]
+  fn test_group_unit_override() {
+    let state = TakeoffStateHandler::new(None);
+    state.upsert_scale(Default {
+      id: "s1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 1.0,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    });
+    state.upsert_group(Group {
+      id: "1".to_string(),
+      name: None,
+      measurement_type: MeasurementType::Linear,
+      color: Some("#ff0000".to_string()),
+      unit_override: Some(Unit::Centimeters),
+    });
+    state.upsert_measurement(Polyline {
+      id: "m1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(2.0, 0.0)],
+      metadata: None,
+    });
+
+    let group = state.get_group("1".to_string()).unwrap();
+    assert_eq!(group.color(), Some("#ff0000".to_string()));
+    assert_eq!(group.unit_override(), Some(Unit::Centimeters));
+    let length = group.get_converted_length(None).unwrap();
+    assert!((length - 200.0).abs() < 1e-3);
+    // An explicit unit wins over the override.
+    let length = group.get_converted_length(Some(Unit::Meters)).unwrap();
+    assert!((length - 2.0).abs() < 1e-9);
+    assert_eq!(
+      group.get_length().unwrap().preferred_unit(),
+      Some(Unit::Centimeters)
+    );
+  }
+
+  #[test]
+  fn test_counts() {
+    let state = TakeoffStateHandler::new(None);
+    assert_eq!(state.measurement_count(), 0);
+    assert_eq!(state.group_count(), 0);
+    assert_eq!(state.page_count(), 0);
+    assert_eq!(state.scale_count(), 0);
+
+    state.upsert_measurement(Polyline {
+      id: "m1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)],
+      metadata: None,
+    });
+    state.upsert_page(Page {
+      id: "1".to_string(),
+      name: None,
+      width: None,
+      height: None,
+      viewport: None,
+      coordinate_system: None,
+    });
+    assert_eq!(state.measurement_count(), 1);
+    assert_eq!(state.page_count(), 1);
+    assert_eq!(state.group_count(), 0);
+  }
+
+  #[test]
+  fn test_total_area_and_length() {
+    let state = TakeoffStateHandler::new(None);
+    state.upsert_scale(Default {
+      id: "s1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 1.0,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    });
+    state.upsert_measurement(Rectangle {
+      id: "m1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(2.0, 3.0)),
+      rotation: None,
+      metadata: None,
+    });
+    state.upsert_measurement(Polyline {
+      id: "m2".to_string(),
+      page_id: "1".to_string(),
+      group_id: "2".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(4.0, 0.0)],
+      metadata: None,
+    });
+    // No scale on page 2: skipped.
+    state.upsert_measurement(Polyline {
+      id: "m3".to_string(),
+      page_id: "2".to_string(),
+      group_id: "2".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(100.0, 0.0)],
+      metadata: None,
+    });
+
+    assert!((state.total_area(Unit::Meters) - 6.0).abs() < 1e-9);
+    assert!((state.total_area(Unit::Centimeters) - 60_000.0).abs() < 1e-6);
+    assert!((state.total_length(Unit::Meters) - 14.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_convert_all_areas_and_lengths() {
+    let state = TakeoffStateHandler::new(None);
+    state.upsert_scale(Default {
+      id: "s1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 1.0,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    });
+    state.upsert_measurement(Rectangle {
+      id: "m1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(2.0, 3.0)),
+      rotation: None,
+      metadata: None,
+    });
+    state.upsert_measurement(Polyline {
+      id: "m2".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(4.0, 0.0)],
+      metadata: None,
+    });
+    // No scale on page 2.
+    state.upsert_measurement(Polyline {
+      id: "m3".to_string(),
+      page_id: "2".to_string(),
+      group_id: "1".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(100.0, 0.0)],
+      metadata: None,
+    });
+
+    let areas = state.convert_all_areas("1".to_string(), Unit::Centimeters);
+    assert_eq!(areas.len(), 3);
+    assert!((areas[0].unwrap() - 60_000.0).abs() < 1e-6);
+    assert_eq!(areas[1..], [None, None]);
+
+    let lengths = state.convert_all_lengths("1".to_string(), Unit::Meters);
+    assert!((lengths[0].unwrap() - 10.0).abs() < 1e-9);
+    assert!((lengths[1].unwrap() - 4.0).abs() < 1e-9);
+    assert_eq!(lengths[2], None);
+    assert!(state
+      .convert_all_lengths("x".to_string(), Unit::Meters)
+      .is_empty());
+  }
+
+  #[test]
+  fn test_group_net_area_excludes_overlap() {
+    let state = TakeoffStateHandler::new(None);
+    state.upsert_scale(Default {
+      id: "s1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 1.0,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    });
+    state.upsert_group(Group {
+      id: "1".to_string(),
+      name: None,
+      measurement_type: MeasurementType::Area,
+      color: None,
+      unit_override: None,
+    });
+    for (id, points) in [
+      ("m1", (Point::new(0.0, 0.0), Point::new(2.0, 2.0))),
+      ("m2", (Point::new(1.0, 1.0), Point::new(3.0, 3.0))),
+    ] {
+      state.upsert_measurement(Rectangle {
+        id: id.to_string(),
+        page_id: "1".to_string(),
+        group_id: "1".to_string(),
+        points,
+        rotation: None,
+        metadata: None,
+      });
+    }
+
+    let group = state.get_group("1".to_string()).unwrap();
+    assert_eq!(
+      group.get_area().unwrap().get_converted_value(Unit::Meters),
+      8.0
+    );
+    let net = group
+      .get_net_area()
+      .unwrap()
+      .get_converted_value(Unit::Meters);
+    assert!((net - 7.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_clear_group_measurements() {
+    let state = TakeoffStateHandler::new(None);
+    state.upsert_scale(Default {
+      id: "s1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 1.0,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    });
+    for group_id in ["1", "2"] {
+      state.upsert_group(Group {
+        id: group_id.to_string(),
+        name: None,
+        measurement_type: MeasurementType::Area,
+        color: Some("#ff0000".to_string()),
+        unit_override: None,
+      });
+    }
+    for (id, group_id) in [("m1", "1"), ("m2", "1"), ("m3", "2")] {
+      state.upsert_measurement(Rectangle {
+        id: id.to_string(),
+        page_id: "1".to_string(),
+        group_id: group_id.to_string(),
+        points: (Point::new(0.0, 0.0), Point::new(2.0, 2.0)),
+        rotation: None,
+        metadata: None,
+      });
+    }
+
+    let version = state.version();
+    assert_eq!(state.clear_group_measurements("1".to_string()), 2);
+    assert!(state.version() > version);
+    assert!(state.get_measurement("m1".to_string()).is_none());
+    assert_eq!(state.measurement_version("m2".to_string()), None);
+    assert!(state.get_measurement("m3".to_string()).is_some());
+
+    let group = state.get_group("1".to_string()).unwrap();
+    assert!(group.is_empty());
+    assert_eq!(group.color(), Some("#ff0000".to_string()));
+    assert_eq!(
+      group.get_area().unwrap().get_converted_value(Unit::Meters),
+      0.0
+    );
+    assert_eq!(state.clear_group_measurements("1".to_string()), 0);
+  }
+
+  #[test]
+  fn test_measurements_outside_page() {
+    let state = TakeoffStateHandler::new(None);
+    for (id, x) in [("b", 150.0), ("a", -1.0), ("c", 50.0)] {
+      state.upsert_measurement(Count {
+        id: id.to_string(),
+        page_id: "1".to_string(),
+        group_id: "1".to_string(),
+        points: (Point::new(x, 10.0),),
+        metadata: None,
+      });
+    }
+    assert!(state.measurements_outside_page("1".to_string()).is_empty());
+
+    state.upsert_page(Page {
+      id: "1".to_string(),
+      name: None,
+      width: Some(100.0),
+      height: Some(100.0),
+      viewport: None,
+      coordinate_system: None,
+    });
+    let ids: Vec<String> = state
+      .measurements_outside_page("1".to_string())
+      .iter()
+      .map(|measurement| measurement.id())
+      .collect();
+    assert_eq!(ids, ["a", "b"]);
+  }
+
+  #[test]
+  fn test_move_measurement_to_group() {
+    let state = TakeoffStateHandler::new(None);
+    state.upsert_scale(Default {
+      id: "s1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 1.0,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    });
+    for group_id in ["1", "2"] {
+      state.upsert_group(Group {
+        id: group_id.to_string(),
+        name: None,
+        measurement_type: MeasurementType::Area,
+        color: None,
+        unit_override: None,
+      });
+    }
+    for (id, side) in [("m1", 2.0), ("m2", 3.0)] {
+      state.upsert_measurement(Rectangle {
+        id: id.to_string(),
+        page_id: "1".to_string(),
+        group_id: "1".to_string(),
+        points: (Point::new(0.0, 0.0), Point::new(side, side)),
+        rotation: None,
+        metadata: None,
+      });
+    }
+    let area = |group_id: &str| {
+      state
+        .get_group(group_id.to_string())
+        .unwrap()
+        .get_area()
+        .unwrap()
+        .get_converted_value(Unit::Meters)
+    };
+    assert_eq!((area("1"), area("2")), (13.0, 0.0));
+
+    state
+      .move_measurement_to_group("m2".to_string(), "2".to_string())
+      .unwrap();
+    assert_eq!(
+      state
+        .get_measurement("m2".to_string())
+        .unwrap()
+        .get_group_id(),
+      "2"
+    );
+    assert_eq!((area("1"), area("2")), (4.0, 9.0));
+
+    assert!(state
+      .move_measurement_to_group("missing".to_string(), "2".to_string())
+      .is_err());
+    assert!(state
+      .move_measurement_to_group("m1".to_string(), "missing".to_string())
+      .is_err());
+    assert_eq!(
+      state
+        .get_measurement("m1".to_string())
+        .unwrap()
+        .get_group_id(),
+      "1"
+    );
+  }
+
+  #[test]
+  fn test_selection_totals() {
+    let state = TakeoffStateHandler::new(None);
+    state.upsert_scale(Default {
+      id: "s1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 1.0,
+        real_distance: 1.0,
+        unit: Unit::Feet,
+      },
+    });
+    state.upsert_measurement(Rectangle {
+      id: "room".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(10.0, 23.0)),
+      rotation: None,
+      metadata: None,
+    });
+    state.upsert_measurement(Polyline {
+      id: "wall".to_string(),
+      page_id: "1".to_string(),
+      group_id: "2".to_string(),
+      points: vec![Point::new(0.0, 0.0), Point::new(2.0, 0.0)],
+      metadata: None,
+    });
+    state.upsert_measurement(Count {
+      id: "outlet".to_string(),
+      page_id: "1".to_string(),
+      group_id: "3".to_string(),
+      points: (Point::new(1.0, 1.0),),
+      metadata: None,
+    });
+
+    let ids = ["room", "wall", "outlet", "room", "missing"].map(String::from);
+    let totals = state.selection_totals(ids.to_vec(), Unit::Feet);
+    assert_eq!(totals.count, 3);
+    assert!((totals.area - 230.0).abs() < 1e-9);
+    // Room perimeter plus the wall.
+    assert!((totals.length - 68.0).abs() < 1e-9);
+
+    let inches = state.selection_totals(vec!["wall".to_string()], Unit::Inches);
+    assert!((inches.length - 24.0).abs() < 1e-9);
+    assert_eq!(inches.area, 0.0);
+
+    let empty = state.selection_totals(vec![], Unit::Feet);
+    assert_eq!((empty.area, empty.length, empty.count), (0.0, 0.0, 0));
+  }
+
+  #[test]
+  fn test_group_extent() {
+    let state = TakeoffStateHandler::new(None);
+    state.upsert_group(Group {
+      id: "1".to_string(),
+      name: None,
+      measurement_type: MeasurementType::Count,
+      color: None,
+      unit_override: None,
+    });
+    let group = state.get_group("1".to_string()).unwrap();
+    assert_eq!(group.extent(), None);
+
+    state.upsert_measurement(Rectangle {
+      id: "m1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(10.0, 20.0), Point::new(30.0, 40.0)),
+      rotation: None,
+      metadata: None,
+    });
+    state.upsert_measurement(Count {
+      id: "m2".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(50.0, 5.0),),
+      metadata: None,
+    });
+    assert_eq!(group.extent(), Some(((10.0, 5.0), (50.0, 40.0))));
+  }
+
+  #[test]
+  fn test_group_area_histogram() {
+    let state = TakeoffStateHandler::new(None);
+    state.upsert_scale(Default {
+      id: "s1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 1.0,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    });
+    state.upsert_group(Group {
+      id: "1".to_string(),
+      name: None,
+      measurement_type: MeasurementType::Area,
+      color: None,
+      unit_override: None,
+    });
+    let group = state.get_group("1".to_string()).unwrap();
+    assert!(group.area_histogram(4, Unit::Meters).is_empty());
+
+    let add = |id: &str, side: f64| {
+      state.upsert_measurement(Rectangle {
+        id: id.to_string(),
+        page_id: "1".to_string(),
+        group_id: "1".to_string(),
+        points: (Point::new(0.0, 0.0), Point::new(side, side)),
+        rotation: None,
+        metadata: None,
+      });
+    };
+    add("m1", 1.0);
+    add("m2", 1.0);
+    let bins = group.area_histogram(4, Unit::Meters);
+    assert_eq!(bins.len(), 1);
+    assert_eq!((bins[0].lower, bins[0].upper, bins[0].count), (1.0, 1.0, 2));
+
+    add("m3", 2.0);
+    add("m4", 3.0);
+    let bins = group.area_histogram(4, Unit::Meters);
+    let summary: Vec<_> = bins.iter().map(|b| (b.lower, b.upper, b.count)).collect();
+    assert_eq!(
+      summary,
+      [(1.0, 3.0, 2), (3.0, 5.0, 1), (5.0, 7.0, 0), (7.0, 9.0, 1)]
+    );
+  }
+
+  #[test]
+  fn test_empty_group_totals_are_zero() {
+    let state = TakeoffStateHandler::new(None);
+    state.upsert_group(Group {
+      id: "1".to_string(),
+      name: None,
+      measurement_type: MeasurementType::Area,
+      color: None,
+      unit_override: None,
+    });
+    let group = state.get_group("1".to_string()).unwrap();
+    assert!(group.is_empty());
+    assert_eq!(
+      group.get_area().unwrap().get_converted_value(Unit::Meters),
+      0.0
+    );
+    assert_eq!(
+      group
+        .get_length()
+        .unwrap()
+        .get_converted_value(Unit::Meters),
+      0.0
+    );
+
+    // A member without a scale has no area: distinct from an empty group.
+    state.upsert_measurement(Rectangle {
+      id: "m1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(1.0, 1.0)),
+      rotation: None,
+      metadata: None,
+    });
+    assert!(!group.is_empty());
+    assert!(group.get_area().is_none());
+  }
+
+  #[test]
+  fn test_group_preview_totals_leave_cache_untouched() {
+    let state = TakeoffStateHandler::new(None);
+    let scale = |pixel_distance: f64| Default {
+      id: "s1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    };
+    state.upsert_scale(scale(1.0));
+    state.upsert_group(Group {
+      id: "1".to_string(),
+      name: None,
+      measurement_type: MeasurementType::Area,
+      color: None,
+      unit_override: None,
+    });
+    state.upsert_measurement(Rectangle {
+      id: "m1".to_string(),
+      page_id: "1".to_string(),
+      group_id: "1".to_string(),
+      points: (Point::new(0.0, 0.0), Point::new(4.0, 2.0)),
+      rotation: None,
+      metadata: None,
+    });
+    let group = state.get_group("1".to_string()).unwrap();
+
+    let current = group.preview_totals(None).unwrap();
+    assert_eq!(current.area.unwrap().get_converted_value(Unit::Meters), 8.0);
+
+    // At 2 px per meter the rectangle is 2 m x 1 m.
+    let preview = group.preview_totals(Some(scale(2.0))).unwrap();
+    assert!((preview.area.unwrap().get_converted_value(Unit::Meters) - 2.0).abs() < 1e-9);
+    assert!((preview.length.unwrap().get_converted_value(Unit::Meters) - 6.0).abs() < 1e-9);
+    assert_eq!(preview.count, Some(1.0));
+
+    assert_eq!(
+      group.get_area().unwrap().get_converted_value(Unit::Meters),
+      8.0
+    );
+    let measurement = state.get_measurement("m1".to_string()).unwrap();
+    assert_eq!(
+      measurement
+        .get_area()
+        .unwrap()
+        .get_converted_value(Unit::Meters),
+      8.0
+    );
+  }
+
+  #[test]
+  fn test_group_outer_perimeter_excludes_shared_walls() {
+    let state = TakeoffStateHandler::new(None);
+    state.upsert_scale(Default {
+      id: "s1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 1.0,
+        real_distance: 1.0,
+        unit: Unit::Meters,
+      },
+    });
+    state.upsert_group(Group {
+      id: "1".to_string(),
+      name: None,
+      measurement_type: MeasurementType::Area,
+      color: None,
+      unit_override: None,
+    });
+    assert!(state
+      .get_group("1".to_string())
+      .unwrap()
+      .get_outer_perimeter()
+      .is_none());
+
+    // Two 2 m x 2 m rooms sharing a wall.
+    for (id, points) in [
+      ("m1", (Point::new(0.0, 0.0), Point::new(2.0, 2.0))),
+      ("m2", (Point::new(2.0, 0.0), Point::new(4.0, 2.0))),
+    ] {
+      state.upsert_measurement(Rectangle {
+        id: id.to_string(),
+        page_id: "1".to_string(),
+        group_id: "1".to_string(),
+        points,
+        rotation: None,
+        metadata: None,
+      });
+    }
+
+    let group = state.get_group("1".to_string()).unwrap();
+    assert!(
+      (group
+        .get_length()
+        .unwrap()
+        .get_converted_value(Unit::Meters)
+        - 16.0)
+        .abs()
+        < 1e-9
+    );
+    let outer = group
+      .get_outer_perimeter()
+      .unwrap()
+      .get_converted_value(Unit::Meters);
+    assert!((outer - 12.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_volume_group_sums_volume_computations() {
+    let state = TakeoffStateHandler::new(None);
+    state.upsert_scale(Default {
+      id: "s1".to_string(),
+      page_id: "1".to_string(),
+      scale: ScaleDefinition {
+        pixel_distance: 1.0,
+        real_distance: 1.0,
+        unit: Unit::Feet,
+      },
+    });
+    state.upsert_contour(ContourInput {
+      id: "c1".to_string(),
+      name: None,
+      page_id: "1".to_string(),
+      lines: vec![ContourLineInput {
+        elevation: 10.0,
+        unit: Unit::Feet,
+        points: vec![
+          Point::new(0.0, 0.0),
+          Point::new(100.0, 0.0),
+          Point::new(100.0, 100.0),
+          Point::new(0.0, 100.0),
+        ],
+      }],
+      points_of_interest: vec![],
+    });
+    state.upsert_group(Group {
+      id: "g1".to_string(),
+      name: None,
+      measurement_type: MeasurementType::Volume,
+      color: None,
+      unit_override: None,
+    });
+    let pad = |id: &str, elevation: f64| VolumeComputation {
+      id: id.to_string(),
+      group_id: "g1".to_string(),
+      contour_id: "c1".to_string(),
+      reference: ReferenceSurfaceInput::Rectangle {
+        points: (Point::new(0.0, 0.0), Point::new(10.0, 10.0)),
+        elevation,
+      },
+      cell_size: Some(1.0),
+      strip_depth: None,
+    };
+    state.upsert_volume(pad("v1", 11.0)).unwrap();
+    state.upsert_volume(pad("v2", 12.0)).unwrap();
+
+    let group = state.get_group("g1".to_string()).unwrap();
+    let volume = group
+      .primary_value()
+      .unwrap()
+      .get_converted_value(Unit::Feet);
+    assert!((volume - 300.0).abs() < 1e-3);
+
+    state.remove_volume("v2".to_string());
+    let volume = group.get_volume().unwrap().get_converted_value(Unit::Feet);
+    assert!((volume - 100.0).abs() < 1e-3);
+
+    // A degenerate pad or a missing contour leaves no total rather than a partial one.
+    let mut flat = pad("v3", 11.0);
+    flat.reference = ReferenceSurfaceInput::Rectangle {
+      points: (Point::new(0.0, 0.0), Point::new(10.0, 0.0)),
+      elevation: 11.0,
+    };
+    state.upsert_volume(flat).unwrap();
+    assert!(group.get_volume().is_none());
+    assert!(group.recompute_measurements().is_err());
+
+    state.remove_volume("v3".to_string());
+    let mut orphan = pad("v4", 11.0);
+    orphan.contour_id = "missing".to_string();
+    state.upsert_volume(orphan).unwrap();
+    assert!(group.get_volume().is_none());
+
+    let version = state.version();
+    let mut zero_cells = pad("v5", 11.0);
+    zero_cells.cell_size = Some(0.0);
+    assert!(state.upsert_volume(zero_cells).is_err());
+    assert_eq!(state.version(), version);
+  }
+
+  #[test]
+  fn test_upsert_contour_with_deferred_scale() {
+    let state = TakeoffStateHandler::new(None);
+    state.upsert_contour(ContourInput {
+      id: "c1".to_string(),
+      name: None,
+      page_id: "1".to_string(),
+      lines: vec![ContourLineInput {
+        elevation: 10.0,
+        unit: Unit::Feet,
+        points: vec![
+          Point::new(0.0, 0.0),
+          Point::new(100.0, 0.0),
+          Point::new(100.0, 100.0),
+          Point::new(0.0, 100.0),
+        ],
+      }],
+      points_of_interest: vec![],
+    });
+
+    // No scale yet — mesh should be None
+    let contour = state.get_contour("c1".to_string()).unwrap();
+    assert!(contour.get_surface_points().is_none());
 
     // Add a scale for the page
     state.upsert_scale(Default {
@@ -655,5 +2926,13 @@ mod tests {
     let contour = state.get_contour("c1".to_string()).unwrap();
     assert!(contour.get_surface_points().is_some());
     assert_eq!(contour.get_surface_points().unwrap().len(), 4);
+    assert_eq!(state.get_contours_by_page_id("1".to_string()).len(), 1);
+    assert!(state.get_contours_by_page_id("2".to_string()).is_empty());
+
+    // Removing the page's scale drops the stale mesh
+    state.remove_scale("s1".to_string());
+    assert!(contour.get_scale().is_none());
+    assert!(contour.get_surface_points().is_none());
+    assert_eq!(state.get_contours_missing_scale().len(), 1);
   }
 }
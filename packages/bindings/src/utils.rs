@@ -1,6 +1,9 @@
 use takeoff_core::error::{TakeoffError, TakeoffResult};
 
 /// Helper function to lock a mutex and convert poison errors to TakeoffError.
+///
+/// All locks go through here so a `TakeoffError::PoisonError` always names the mutex
+/// that failed; there is deliberately no blanket `From<PoisonError<T>>` conversion.
 pub fn lock_mutex<'a, T>(
   guard: std::result::Result<
     std::sync::MutexGuard<'a, T>,